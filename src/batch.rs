@@ -0,0 +1,205 @@
+// Running a script of storify commands against one shared client (`storify batch`)
+use crate::cli::{self, Args, Commands, Outcome};
+use crate::error::{Error, Result};
+use crate::storage::StorageClient;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches};
+use std::path::Path;
+
+/// Global flags that configure the client rather than one command. Every line shares the
+/// client the batch started with, so a line that sets one is rejected instead of having it
+/// silently ignored.
+const CLIENT_FLAGS: &[&str] = &[
+    "read_only",
+    "debug_log",
+    "region_auto",
+    "max_concurrent_requests",
+    "proxy",
+    "follow_redirects",
+    "credential_process",
+];
+
+/// Run each command line of `script` in order, reporting every line's outcome. Blank
+/// lines and lines starting with `#` are skipped. A line fails on an error or a nonzero
+/// exit status, such as `exists` on an absent path. With `stop_on_error` the first failure
+/// ends the batch; otherwise the remaining lines still run and the batch fails at the end.
+pub async fn run(script: &Path, stop_on_error: bool, client: &StorageClient) -> Result<()> {
+    let content = std::fs::read_to_string(script)?;
+    let (mut succeeded, mut failed) = (0, 0);
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_no = index + 1;
+        let failure = match run_line(line, client).await {
+            Ok(Outcome::Success) => None,
+            Ok(outcome) => Some(format!("exit status {}", outcome.status())),
+            Err(err) => Some(err.to_string()),
+        };
+        match failure {
+            None => {
+                succeeded += 1;
+                println!("[{line_no}] ok: {line}");
+            }
+            Some(reason) => {
+                failed += 1;
+                eprintln!("[{line_no}] failed: {line}: {reason}");
+                if stop_on_error {
+                    eprintln!("Stopping at line {line_no} (--stop-on-error)");
+                    break;
+                }
+            }
+        }
+    }
+
+    println!("Batch: {succeeded} succeeded, {failed} failed");
+    if failed > 0 {
+        Err(Error::BatchFailed {
+            failed,
+            total: succeeded + failed,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Parse one line as a full `storify` command line and dispatch it.
+async fn run_line(line: &str, client: &StorageClient) -> Result<Outcome> {
+    let invalid = |reason: String| Error::InvalidBatchLine { reason };
+    let words = split_words(line).map_err(invalid)?;
+    // clap renders a usage block after the message; the first line says it all.
+    let parse_error = |e: clap::Error| {
+        let rendered = e.to_string();
+        let message = rendered.lines().next().unwrap_or_default();
+        invalid(message.trim_start_matches("error: ").to_string())
+    };
+    let matches = Args::command()
+        .try_get_matches_from(std::iter::once("storify".to_string()).chain(words))
+        .map_err(parse_error)?;
+    // Only flags written on the line count; environment variables already shaped the client.
+    if let Some(flag) = CLIENT_FLAGS
+        .iter()
+        .find(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+    {
+        return Err(invalid(format!(
+            "--{} configures the shared client and cannot be set on a batch line",
+            flag.replace('_', "-")
+        )));
+    }
+    let args = Args::from_arg_matches(&matches).map_err(parse_error)?;
+    if matches!(
+        args.command,
+        Commands::Batch(_) | Commands::Doctor | Commands::Config(_)
+    ) {
+        return Err(invalid(
+            "this command cannot run inside a batch".to_string(),
+        ));
+    }
+    Box::pin(cli::run(args, client.clone()))
+        .await
+        .map_err(|e| client.explain_error(e))
+}
+
+/// Split a line into words the way a POSIX shell would: whitespace separates words,
+/// single quotes keep their text literally, double quotes keep whitespace and honour `\"`
+/// and `\\`, and a backslash outside quotes escapes the next character.
+fn split_words(line: &str) -> std::result::Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("unterminated double quote".to_string()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+                None => return Err("trailing backslash".to_string()),
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::StorageConfig;
+    use crate::storage::fixture::TempDir;
+
+    #[test]
+    fn test_split_words_follows_shell_quoting() {
+        assert_eq!(
+            split_words("put  a.txt remote/").unwrap(),
+            ["put", "a.txt", "remote/"]
+        );
+        assert_eq!(
+            split_words(r#"cp 'dir/a b.txt' "dir/it's \"c\".txt" x\ y ''"#).unwrap(),
+            ["cp", "dir/a b.txt", r#"dir/it's "c".txt"#, "x y", ""]
+        );
+        assert_eq!(split_words(r#"ls "C:\temp""#).unwrap(), ["ls", r"C:\temp"]);
+        assert!(split_words("rm 'old/").is_err());
+        assert!(split_words(r#"rm "old/"#).is_err());
+        assert!(split_words(r"rm old\").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lines_cannot_set_client_flags() {
+        let dir = TempDir::new();
+        std::fs::write(dir.join("old.txt"), "keep").unwrap();
+        let client = StorageClient::new(StorageConfig::fs(dir.root()))
+            .await
+            .unwrap();
+
+        for line in [
+            "--read-only rm -f old.txt",
+            "rm -f old.txt --read-only",
+            "ls --debug-log trace.jsonl",
+        ] {
+            let err = run_line(line, &client).await.unwrap_err();
+            assert!(
+                matches!(err, Error::InvalidBatchLine { .. }),
+                "{line}: {err}"
+            );
+        }
+        assert!(dir.join("old.txt").exists());
+        assert!(!dir.join("trace.jsonl").exists());
+
+        // Per-command globals still apply to their own line.
+        assert!(matches!(
+            run_line("--no-progress exists old.txt", &client).await,
+            Ok(Outcome::Success)
+        ));
+    }
+}
@@ -1,8 +1,24 @@
 /// This module handles Command Line Interface (CLI) related logic.
-use crate::error::{Error, Result};
-use crate::storage::{OutputFormat, StorageClient};
+use crate::config::{
+    ResolvedSetting, SettingSource, StorageUrl, load_storage_config_for_url, resolve_config_sources,
+};
+use crate::error::{ContentTypeWithoutReplaceSnafu, Error, Result};
+use crate::storage::{
+    BreakerPolicy, ChecksumAlgorithm, Concurrency, ContentTypeDetection, CopyOptions,
+    DownloadOptions, ErrorPolicy, FindOptions, GrepOptions, HealthPolicy, HttpOptions, JsonStyle,
+    LIST_TEMPLATE_FIELDS, ListFormat, ListOptions, Manifest, MetadataDirective, OutputFormat,
+    OverwritePolicy, ProgressFormat, ProgressOptions, STAT_TEMPLATE_FIELDS, SelectOptions,
+    SizeRange, SortKey, StorageClient, Template, TimeRange, TimeStyle, TransferLog, UnicodeForm,
+    UploadOptions, UsageFormat, UsageOptions, parse_age, parse_datetime, parse_size,
+    validate_cache_control, validate_content_disposition,
+};
 use crate::utils::confirm_deletion;
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, Utc};
+use clap::{ArgGroup, Parser, Subcommand};
+use snafu::ensure;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 /// Custom parser to validate that a path is not empty.
 fn parse_validated_path(path_str: &str) -> Result<String> {
@@ -15,6 +31,34 @@ fn parse_validated_path(path_str: &str) -> Result<String> {
     }
 }
 
+/// Parse an `ls` output template, validating its placeholders.
+fn parse_list_template(template: &str) -> Result<Template> {
+    Template::parse(template, LIST_TEMPLATE_FIELDS)
+}
+
+/// Parse a `stat` output template, validating its placeholders.
+fn parse_stat_template(template: &str) -> Result<Template> {
+    Template::parse(template, STAT_TEMPLATE_FIELDS)
+}
+
+/// Parse a multipart part size such as `8MiB`, rejecting zero.
+fn parse_part_size(size: &str) -> std::result::Result<usize, String> {
+    match parse_size(size)? {
+        0 => Err("part size must be greater than zero".to_string()),
+        size => Ok(size),
+    }
+}
+
+/// How a failing command reports its error on stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ErrorFormat {
+    /// `Error: <message>`
+    #[default]
+    Text,
+    /// `{"error": {"kind": ..., "message": ..., "path": ...}}`
+    Json,
+}
+
 /// Storify - A unified tool for managing object storage with HDFS-like interface
 #[derive(Parser, Debug)]
 #[command(
@@ -26,6 +70,180 @@ fn parse_validated_path(path_str: &str) -> Result<String> {
 pub struct Args {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Transfer progress format (json emits newline-delimited events on stderr)
+    #[arg(long, global = true, value_enum, default_value_t = ProgressFormat::Text)]
+    pub progress_format: ProgressFormat,
+
+    /// Disable transfer progress output entirely
+    #[arg(long, global = true)]
+    pub no_progress: bool,
+
+    /// Write the latest progress line to this file instead of the terminal
+    #[arg(long, global = true, value_name = "PATH")]
+    pub progress_to_file: Option<PathBuf>,
+
+    /// Detect the bucket region from a custom S3 endpoint when no region is configured
+    #[arg(long, global = true, env = "STORAGE_REGION_AUTO")]
+    pub region_auto: bool,
+
+    /// Append a redacted JSON-lines trace of backend operations to FILE (for bug reports)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub debug_log: Option<PathBuf>,
+
+    /// Refuse every command that would modify storage (put, rm, cp, mkdir, ...)
+    #[arg(long, global = true, env = "STORAGE_READ_ONLY")]
+    pub read_only: bool,
+
+    /// Cap on backend requests in flight across listing, stat, and transfers combined
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        env = "STORAGE_MAX_CONCURRENT_REQUESTS"
+    )]
+    pub max_concurrent_requests: Option<NonZeroUsize>,
+
+    /// Send requests through this HTTP(S) proxy; without it HTTPS_PROXY, HTTP_PROXY,
+    /// ALL_PROXY and NO_PROXY are honored
+    #[arg(long, global = true, value_name = "URL", env = "STORAGE_PROXY")]
+    pub proxy: Option<String>,
+
+    /// Follow HTTP redirects from the endpoint (up to 10) instead of failing on them
+    #[arg(long, global = true)]
+    pub follow_redirects: bool,
+
+    /// Stop recursive get/put/cp at the first failed object (the default)
+    #[arg(long, global = true, overrides_with = "no_fail_fast")]
+    pub fail_fast: bool,
+
+    /// Keep going past failed objects in recursive get/put/cp, report each one, and exit
+    /// nonzero at the end
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "fail_fast",
+        env = "STORAGE_NO_FAIL_FAST"
+    )]
+    pub no_fail_fast: bool,
+
+    /// Per-object failure policy for recursive get/put/cp: abort (stop at once), skip
+    /// (report and carry on), or retry:N (try N more times with backoff, then skip).
+    /// Takes precedence over --fail-fast / --no-fail-fast
+    #[arg(long, global = true, value_name = "abort|skip|retry:N")]
+    pub on_error: Option<ErrorPolicy>,
+
+    /// Run CMD for cloud credentials: it prints JSON with AccessKeyId, SecretAccessKey and
+    /// optionally SessionToken and Expiration (AWS credential_process format)
+    #[arg(
+        long,
+        global = true,
+        value_name = "CMD",
+        env = "STORAGE_CREDENTIAL_PROCESS"
+    )]
+    pub credential_process: Option<String>,
+
+    /// Format of the error printed when a command fails
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Text)]
+    pub error_format: ErrorFormat,
+
+    /// Indent JSON output (stat --json, ls/du --format json, --error-format json) instead of
+    /// one line per record
+    #[arg(long, global = true)]
+    pub json_pretty: bool,
+}
+
+impl Args {
+    /// Layout of JSON output requested on the command line.
+    pub fn json_style(&self) -> JsonStyle {
+        JsonStyle::from_pretty(self.json_pretty)
+    }
+
+    /// Proxy and redirect settings requested on the command line.
+    pub fn http_options(&self) -> HttpOptions {
+        HttpOptions {
+            proxy: self.proxy.clone(),
+            follow_redirects: self.follow_redirects,
+        }
+    }
+
+    /// How recursive transfers treat failed objects. `--on-error` wins over the fail-fast
+    /// flags, and `--fail-fast` on the command line wins over `STORAGE_NO_FAIL_FAST`.
+    pub fn error_policy(&self) -> ErrorPolicy {
+        match self.on_error {
+            Some(policy) => policy,
+            None if self.no_fail_fast && !self.fail_fast => ErrorPolicy::Skip,
+            None => ErrorPolicy::Abort,
+        }
+    }
+
+    /// Checksum requested for uploads; the backend is configured to validate it when able.
+    pub fn upload_checksum_algorithm(&self) -> Option<ChecksumAlgorithm> {
+        match &self.command {
+            Commands::Put(put_args) => put_args.checksum_algorithm,
+            _ => None,
+        }
+    }
+
+    /// Config settings taken from global flags (or the variables clap reads for them).
+    fn flag_settings(&self) -> Vec<ResolvedSetting> {
+        let flag = |name, flag, env, value: Option<String>| {
+            let source = if value.is_some() {
+                SettingSource::Flag { flag, env }
+            } else {
+                SettingSource::Default
+            };
+            ResolvedSetting::new(name, value, source)
+        };
+        let switch = |on: bool| on.then(|| "true".to_string());
+        vec![
+            flag(
+                "region_auto",
+                "--region-auto",
+                Some("STORAGE_REGION_AUTO"),
+                switch(self.region_auto),
+            ),
+            flag(
+                "read_only",
+                "--read-only",
+                Some("STORAGE_READ_ONLY"),
+                switch(self.read_only),
+            ),
+            flag(
+                "max_concurrent_requests",
+                "--max-concurrent-requests",
+                Some("STORAGE_MAX_CONCURRENT_REQUESTS"),
+                self.max_concurrent_requests.map(|n| n.to_string()),
+            ),
+            flag(
+                "proxy",
+                "--proxy",
+                Some("STORAGE_PROXY"),
+                self.proxy.clone(),
+            ),
+            flag(
+                "follow_redirects",
+                "--follow-redirects",
+                None,
+                switch(self.follow_redirects),
+            ),
+            flag(
+                "debug_log",
+                "--debug-log",
+                None,
+                self.debug_log.as_ref().map(|p| p.display().to_string()),
+            ),
+        ]
+    }
+}
+
+/// Print the configuration the CLI would run with (`storify config show`). Runs before
+/// the config is loaded, so it also works when required settings are missing.
+pub fn show_config(args: &Args) -> Result<()> {
+    for setting in resolve_config_sources().iter().chain(&args.flag_settings()) {
+        println!("{setting}");
+    }
+    Ok(())
 }
 
 #[derive(Subcommand, Debug)]
@@ -42,10 +260,33 @@ pub enum Commands {
     Rm(RmArgs),
     /// Copy files/directories from remote to remote
     Cp(CpArgs),
+    /// Move or rename files/directories within remote storage
+    Mv(MvArgs),
     /// Create directories in remote storage
     Mkdir(MkdirArgs),
     /// Display object metadata
     Stat(StatArgs),
+    /// Exit with status 0 if a path exists (an object, or a prefix with entries), 1 if not
+    /// and 2 if the check failed
+    Exists(ExistsArgs),
+    /// Recursively find objects matching metadata predicates
+    Find(FindArgs),
+    /// Stream the contents of a remote file to stdout
+    Cat(CatArgs),
+    /// Print the first bytes or lines of a remote file
+    Head(PeekArgs),
+    /// Print the last bytes or lines of a remote file
+    Tail(PeekArgs),
+    /// Print lines of remote text objects that match a regular expression
+    Grep(GrepArgs),
+    /// Print the rows of a CSV object that match a SQL-style query
+    Select(SelectArgs),
+    /// Check configuration and connectivity, and suggest fixes for common problems
+    Doctor,
+    /// Inspect the resolved configuration
+    Config(ConfigArgs),
+    /// Run storify commands from a script file, one per line, against one connection
+    Batch(BatchArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -61,20 +302,275 @@ pub struct LsArgs {
     /// Process directories recursively
     #[arg(short = 'R', long)]
     pub recursive: bool,
+
+    /// Timestamp style used by the long format
+    #[arg(long, value_enum, default_value_t = TimeStyle::Rfc3339)]
+    pub time_style: TimeStyle,
+
+    /// Sort entries before printing (version = natural order, file2 before file10)
+    #[arg(long, value_enum)]
+    pub sort: Option<SortKey>,
+
+    /// Custom output per entry, e.g. '{size} {path}'. Placeholders: {type}, {size},
+    /// {human_size}, {mtime}, {path}, {etag}, {content_type}, {version}
+    #[arg(long, value_parser = parse_list_template)]
+    pub format_template: Option<Template>,
+
+    /// Separate entries with NUL bytes instead of newlines (for `xargs -0`)
+    #[arg(short = '0', long)]
+    pub null: bool,
+
+    /// List all versions of each object with their version IDs (versioned buckets)
+    #[arg(long)]
+    pub versions: bool,
+
+    #[command(flatten)]
+    pub time_range: TimeRangeArgs,
+
+    /// Complete metadata the listing lacks (e.g. content type) with concurrent stat calls
+    #[arg(long)]
+    pub parallel_stat: bool,
+
+    /// Walk recursively and print `<dir>: N files, <size>` as each directory's subtree
+    /// completes, instead of listing every file
+    #[arg(
+        long,
+        visible_alias = "list-recursive-summary",
+        conflicts_with_all = ["long", "sort", "format_template", "null", "versions"]
+    )]
+    pub recursive_summary: bool,
+
+    /// Entry layout: text, tsv (`type<TAB>size<TAB>mtime<TAB>path`, byte sizes and
+    /// RFC3339 times; tabs and newlines in keys are escaped), or json (one array of
+    /// `{path, size, modified, is_dir}` objects)
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ListFormat::Text,
+        conflicts_with_all = ["long", "format_template", "recursive_summary"]
+    )]
+    pub format: ListFormat,
+
+    /// Print only the keys, one per line, without long-format details even with -L
+    #[arg(long, conflicts_with_all = ["format_template", "format", "recursive_summary"])]
+    pub keys_only: bool,
+
+    /// Print only the last path segment of each key (directories keep a trailing '/')
+    #[arg(
+        long,
+        conflicts_with_all = ["keys_only", "format_template", "format", "recursive_summary"]
+    )]
+    pub basename_only: bool,
+
+    /// With -R, report subdirectories you may not list on stderr and list the rest
+    /// instead of failing (lists one directory at a time)
+    #[arg(long, visible_alias = "list-continue-on-access-denied")]
+    pub continue_on_access_denied: bool,
+
+    /// Print directory keys without the trailing '/' (-L still marks them DIR)
+    #[arg(long)]
+    pub strip_trailing_slash: bool,
+
+    /// Entries requested per backend listing call (default 1000): larger pages mean fewer
+    /// requests, smaller ones a faster first response
+    #[arg(long, value_name = "N", visible_alias = "list-page-size")]
+    pub page_size: Option<NonZeroUsize>,
+
+    /// Fail on keys that are not valid UTF-8 instead of printing them with U+FFFD
+    #[arg(long)]
+    pub strict_utf8: bool,
+
+    /// Show each object's ETag (or stored Content-MD5) in the long format, via concurrent
+    /// stat calls where needed; multipart ETags (`<hash>-<parts>`) are not MD5s
+    #[arg(long, visible_alias = "list-checksums", requires = "long")]
+    pub checksums: bool,
+
+    /// With -R, print a header per directory followed by its indented children (like
+    /// `ls -R`) instead of a full key on every line
+    #[arg(
+        long,
+        requires = "recursive",
+        conflicts_with_all = [
+            "format_template",
+            "format",
+            "keys_only",
+            "basename_only",
+            "null",
+            "recursive_summary"
+        ]
+    )]
+    pub grouped: bool,
+}
+
+/// Modification-time window shared by `ls`, `du` and `find`.
+#[derive(clap::Args, Debug)]
+pub struct TimeRangeArgs {
+    /// Only include objects modified at or after DATE (YYYY-MM-DD or RFC3339)
+    #[arg(long, value_name = "DATE", value_parser = parse_datetime)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only include objects modified at or before DATE (YYYY-MM-DD or RFC3339)
+    #[arg(long, value_name = "DATE", value_parser = parse_datetime)]
+    pub until: Option<DateTime<Utc>>,
+
+    /// Only include objects modified within AGE of now (e.g. 30m, 24h, 7d)
+    #[arg(
+        long,
+        value_name = "AGE",
+        value_parser = parse_age,
+        conflicts_with_all = ["since", "until"]
+    )]
+    pub modified_within: Option<chrono::Duration>,
+}
+
+impl TimeRangeArgs {
+    pub fn range(&self) -> TimeRange {
+        match self.modified_within {
+            Some(age) => TimeRange::within(age, Utc::now()),
+            None => TimeRange {
+                since: self.since,
+                until: self.until,
+            },
+        }
+    }
+}
+
+/// File size band shared by `put` and `get` directory transfers.
+#[derive(clap::Args, Debug)]
+pub struct SizeRangeArgs {
+    /// In directory transfers, skip files smaller than SIZE (e.g. 1K)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub min_file_size: Option<usize>,
+
+    /// In directory transfers, skip files larger than SIZE (e.g. 100M)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub max_file_size: Option<usize>,
+}
+
+impl SizeRangeArgs {
+    pub fn range(&self) -> SizeRange {
+        SizeRange {
+            min: self.min_file_size.map(|size| size as u64),
+            max: self.max_file_size.map(|size| size as u64),
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
 pub struct GetArgs {
-    /// The remote path to download from
-    #[arg(value_name = "REMOTE", value_parser = parse_validated_path)]
-    pub remote: String,
+    /// The remote path(s) to download from; all land in the local path
+    #[arg(value_name = "REMOTE", required = true, value_parser = parse_validated_path)]
+    pub remote: Vec<String>,
 
     /// The local path to download to
     #[arg(value_name = "LOCAL", value_parser = parse_validated_path)]
     pub local: String,
+
+    /// Download a specific version of the object (versioned buckets)
+    #[arg(long, value_name = "ID")]
+    pub version_id: Option<String>,
+
+    /// Store identical objects once locally, hard-linking duplicates (copies if unsupported)
+    #[arg(long)]
+    pub dedupe: bool,
+
+    /// Skip files whose local copy already has the same size as the remote object
+    #[arg(long)]
+    pub if_size_differs: bool,
+
+    /// Files of a directory fetched at once; `auto` starts low, ramps up while throughput
+    /// improves, and backs off on throttling or errors
+    #[arg(long, value_name = "N|auto", default_value_t = Concurrency::default())]
+    pub concurrency: Concurrency,
+
+    /// Check each file's MD5 against its ETag, re-downloading on a mismatch (multipart
+    /// ETags are skipped with a note)
+    #[arg(long, visible_alias = "integrity-on-download")]
+    pub verify: bool,
+
+    /// With --verify, download a mismatching file again up to N times before failing
+    #[arg(long, value_name = "N", default_value_t = 1, requires = "verify")]
+    pub retry_on_checksum_mismatch: u32,
+
+    /// After N consecutive 5xx responses, start no new transfers until the cooldown passes
+    #[arg(long, value_name = "N")]
+    pub throttle_on_5xx: Option<NonZeroUsize>,
+
+    /// How long --throttle-on-5xx pauses transfers (e.g. 30s, 2m)
+    #[arg(
+        long,
+        value_name = "AGE",
+        value_parser = parse_age,
+        default_value = "30s",
+        requires = "throttle_on_5xx"
+    )]
+    pub throttle_cooldown: chrono::Duration,
+
+    /// When transfers fail because the endpoint stops answering, ping it and wait for it
+    /// to come back, then retry them, instead of failing the download
+    #[arg(long)]
+    pub endpoint_health_check: bool,
+
+    /// How often --endpoint-health-check pings an unreachable endpoint (e.g. 10s)
+    #[arg(
+        long,
+        value_name = "AGE",
+        value_parser = parse_age,
+        default_value = "10s",
+        requires = "endpoint_health_check"
+    )]
+    pub health_check_interval: chrono::Duration,
+
+    /// Longest outage --endpoint-health-check waits out before failing (e.g. 10m, 1h)
+    #[arg(
+        long,
+        value_name = "AGE",
+        value_parser = parse_age,
+        default_value = "10m",
+        requires = "endpoint_health_check"
+    )]
+    pub health_check_max_wait: chrono::Duration,
+
+    /// Fail on keys that are not valid UTF-8 instead of fetching them by their lossy name
+    #[arg(long)]
+    pub strict_utf8: bool,
+
+    /// Append 'timestamp,operation,path,bytes,status,duration_ms' for every file to FILE
+    #[arg(long, value_name = "FILE")]
+    pub transfer_log: Option<PathBuf>,
+
+    /// Fail with a not-found error when a remote path does not exist (the default)
+    #[arg(long)]
+    pub strict_existence: bool,
+
+    /// Skip remote paths that do not exist with a note instead of failing
+    #[arg(long, conflicts_with = "strict_existence")]
+    pub lenient: bool,
+
+    #[command(flatten)]
+    pub size_range: SizeRangeArgs,
+}
+
+impl GetArgs {
+    /// The circuit breaker policy selected by --throttle-on-5xx, if any.
+    pub fn breaker_policy(&self) -> Option<BreakerPolicy> {
+        self.throttle_on_5xx.map(|threshold| BreakerPolicy {
+            threshold,
+            cooldown: self.throttle_cooldown.to_std().unwrap_or_default(),
+        })
+    }
+
+    /// The outage handling selected by --endpoint-health-check, if any.
+    pub fn health_policy(&self) -> Option<HealthPolicy> {
+        self.endpoint_health_check.then(|| HealthPolicy {
+            interval: self.health_check_interval.to_std().unwrap_or_default(),
+            max_wait: self.health_check_max_wait.to_std().unwrap_or_default(),
+        })
+    }
 }
 
 #[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("rollup").args(["summary", "by_prefix"]).multiple(true)))]
 pub struct DuArgs {
     /// The path to check usage for
     #[arg(value_name = "PATH", value_parser = parse_validated_path)]
@@ -83,13 +579,52 @@ pub struct DuArgs {
     /// Show summary only
     #[arg(short = 's', long)]
     pub summary: bool,
+
+    /// With --summary, also show totals for prefixes up to N levels deep
+    #[arg(long, value_name = "N", requires = "rollup")]
+    pub max_depth: Option<usize>,
+
+    /// Total sizes per '/'-delimited key prefix (one level, or --max-depth) instead of
+    /// listing every object; works without directory markers
+    #[arg(long)]
+    pub by_prefix: bool,
+
+    #[command(flatten)]
+    pub time_range: TimeRangeArgs,
+
+    /// Print exactly one line, '<bytes> <files> <path>', with the size in raw bytes
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "summary",
+            "max_depth",
+            "by_prefix",
+            "since",
+            "until",
+            "modified_within"
+        ]
+    )]
+    pub compact: bool,
+
+    /// Total size and object count per file extension, largest first
+    #[arg(long, conflicts_with_all = ["summary", "max_depth", "by_prefix", "compact"])]
+    pub by_extension: bool,
+
+    /// Output format: text, or json for `{path, size, files}` records in bytes
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = UsageFormat::Text,
+        conflicts_with_all = ["compact", "by_extension"]
+    )]
+    pub format: UsageFormat,
 }
 
 #[derive(Parser, Debug)]
 pub struct PutArgs {
-    /// The local path to upload from
-    #[arg(value_name = "LOCAL", value_parser = parse_validated_path)]
-    pub local: String,
+    /// The local path(s) to upload from; all land in the remote path
+    #[arg(value_name = "LOCAL", required = true, value_parser = parse_validated_path)]
+    pub local: Vec<String>,
 
     /// The remote path to upload to
     #[arg(value_name = "REMOTE", value_parser = parse_validated_path)]
@@ -98,6 +633,71 @@ pub struct PutArgs {
     /// Process directories recursively
     #[arg(short = 'R', long)]
     pub recursive: bool,
+
+    /// Skip files whose remote copy was modified more recently than the local file
+    #[arg(long)]
+    pub no_overwrite_newer: bool,
+
+    /// Upload only files modified after their existing remote copy
+    #[arg(long)]
+    pub only_newer: bool,
+
+    /// With --only-newer, skip remote objects that have no modification time
+    #[arg(long, requires = "only_newer")]
+    pub skip_undated: bool,
+
+    /// Checksum uploads so corrupted objects are rejected (server-side where supported)
+    #[arg(long, value_enum)]
+    pub checksum_algorithm: Option<ChecksumAlgorithm>,
+
+    /// With --checksum-algorithm, upload a file again up to N times before failing
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        requires = "checksum_algorithm"
+    )]
+    pub retry_on_checksum_mismatch: u32,
+
+    /// Content-Disposition stored with uploaded objects, e.g. 'attachment; filename="a.pdf"'
+    #[arg(long, value_parser = validate_content_disposition)]
+    pub content_disposition: Option<String>,
+
+    /// Cache-Control stored with uploaded objects, e.g. 'max-age=3600'
+    #[arg(long, value_parser = validate_cache_control)]
+    pub cache_control: Option<String>,
+
+    /// Normalize local file names to this Unicode form before building remote keys
+    #[arg(long, value_enum, value_name = "FORM")]
+    pub normalize_unicode: Option<UnicodeForm>,
+
+    /// Upload up to N parts of each file in parallel (multipart upload)
+    #[arg(long, value_name = "N")]
+    pub part_concurrency: Option<NonZeroUsize>,
+
+    /// Size of each multipart part, e.g. 16MiB (default 8MiB; S3 requires at least 5MiB)
+    #[arg(long, value_name = "SIZE", value_parser = parse_part_size)]
+    pub part_size: Option<usize>,
+
+    /// Set Content-Type from the file extension, or sniff the leading bytes first (magic)
+    #[arg(long, value_enum, value_name = "METHOD")]
+    pub detect_content_type: Option<ContentTypeDetection>,
+
+    /// Write '<etag>  <key>  <size>' for every uploaded object to FILE
+    #[arg(long, value_name = "FILE")]
+    pub output_manifest: Option<PathBuf>,
+
+    /// Discard a file's upload if its size or modification time changed while it was read
+    /// (combine with --on-error retry:N to try again)
+    #[arg(long, visible_alias = "local-checksum-on-put")]
+    pub fail_if_changed: bool,
+
+    /// Append 'timestamp,operation,path,bytes,status,duration_ms' for every file to FILE
+    #[arg(long, value_name = "FILE")]
+    pub transfer_log: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub size_range: SizeRangeArgs,
 }
 
 #[derive(Parser, Debug)]
@@ -115,15 +715,61 @@ pub struct RmArgs {
     pub force: bool,
 }
 
+#[derive(Parser, Debug)]
+pub struct MvArgs {
+    /// The remote path to move
+    #[arg(value_name = "SRC", value_parser = parse_validated_path)]
+    pub src_path: String,
+
+    /// The new remote path; an existing destination object is replaced
+    #[arg(value_name = "DEST", value_parser = parse_validated_path)]
+    pub dest_path: String,
+}
+
 #[derive(Parser, Debug)]
 pub struct CpArgs {
-    /// The remote path to copy from
+    /// The remote path to copy from (or a URL such as s3://bucket/key)
     #[arg(value_name = "SRC", value_parser = parse_validated_path)]
     pub src_path: String,
 
-    /// The remote path to copy to
+    /// The remote path to copy to (or a URL such as oss://bucket/key)
     #[arg(value_name = "DEST", value_parser = parse_validated_path)]
     pub dest_path: String,
+
+    /// Stream data through the client even when a server-side copy is possible
+    #[arg(long)]
+    pub client_side: bool,
+
+    /// Keep Content-Type, Cache-Control, Content-Disposition and user metadata when the
+    /// data is streamed (e.g. between buckets or providers)
+    #[arg(long, conflicts_with = "metadata_directive")]
+    pub preserve_metadata: bool,
+
+    /// Keep the source's headers (copy) or store only the ones given here (replace);
+    /// replacing streams the data through the client
+    #[arg(long, value_enum, default_value_t = MetadataDirective::Copy)]
+    pub metadata_directive: MetadataDirective,
+
+    /// Content-Type stored on the copies (requires --metadata-directive replace)
+    #[arg(long)]
+    pub content_type: Option<String>,
+
+    /// When an existing destination object is replaced: always, never, newer (source
+    /// modified after the destination) or if-size-differs
+    #[arg(long, value_enum, default_value_t = OverwritePolicy::Always)]
+    pub overwrite_policy: OverwritePolicy,
+}
+
+#[derive(Parser, Debug)]
+pub struct BatchArgs {
+    /// Script with one command per line as typed after `storify` (e.g. `put a.txt
+    /// remote/`); blank lines and lines starting with '#' are skipped
+    #[arg(value_name = "SCRIPT")]
+    pub script: PathBuf,
+
+    /// Stop at the first failing line instead of running the rest
+    #[arg(long)]
+    pub stop_on_error: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -150,40 +796,327 @@ pub struct StatArgs {
     /// Output as raw key=value lines (compatible with opendal-mkdir)
     #[arg(long, conflicts_with = "json")]
     pub raw: bool,
+
+    /// Print fields through a template, e.g. '{size}'. Placeholders: {path}, {type},
+    /// {size}, {last_modified}, {etag}, {content_type}, {content_disposition},
+    /// {cache_control}
+    #[arg(long, value_parser = parse_stat_template, conflicts_with_all = ["json", "raw"])]
+    pub format: Option<Template>,
+
+    /// Print nothing; exit with status 0 if the path exists and 1 otherwise
+    #[arg(long, conflicts_with_all = ["json", "raw", "format"])]
+    pub exists_only: bool,
 }
 
-pub async fn run(args: Args, client: StorageClient) -> Result<()> {
+#[derive(Parser, Debug)]
+pub struct ExistsArgs {
+    /// The object or prefix to look for
+    #[arg(value_name = "PATH", value_parser = parse_validated_path)]
+    pub path: String,
+}
+
+/// Resolve a location argument to the client that owns it and the path within that client.
+/// URL-style locations get their own client; plain paths use the default one.
+async fn resolve_location(
+    location: &str,
+    default: &StorageClient,
+) -> Result<(StorageClient, String)> {
+    match StorageUrl::parse(location) {
+        Some(url) => {
+            let mut config = load_storage_config_for_url(&url)?;
+            // Global flags apply to every client. --checksum-algorithm is put-only, and put
+            // never opens a URL client, so there is none to carry over.
+            config.read_only = default.is_read_only();
+            config.region_auto = default.is_region_auto();
+            config.debug_log = default.debug_log().map(Path::to_path_buf);
+            config.http = default.http_options().clone();
+            let client = StorageClient::new(config)
+                .await?
+                .with_request_limit_of(default)
+                .with_progress(default.progress().clone());
+            Ok((client, url.path))
+        }
+        None => Ok((default.clone(), location.to_string())),
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct FindArgs {
+    /// The prefix to search recursively
+    #[arg(value_name = "PATH", value_parser = parse_validated_path)]
+    pub path: String,
+
+    /// Only report zero-byte objects (directory markers are excluded)
+    #[arg(long)]
+    pub empty: bool,
+
+    /// Separate keys with NUL bytes instead of newlines (for `xargs -0`)
+    #[arg(short = '0', long)]
+    pub null: bool,
+
+    #[command(flatten)]
+    pub time_range: TimeRangeArgs,
+}
+
+#[derive(Parser, Debug)]
+pub struct CatArgs {
+    /// The file to print
+    #[arg(value_name = "PATH", value_parser = parse_validated_path)]
+    pub path: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct PeekArgs {
+    /// The file to print from
+    #[arg(value_name = "PATH", value_parser = parse_validated_path)]
+    pub path: String,
+
+    /// Print this many bytes instead of lines
+    #[arg(short = 'c', long, value_name = "N", conflicts_with = "lines")]
+    pub bytes: Option<u64>,
+
+    /// Print this many lines [default: 10]
+    #[arg(short = 'n', long, value_name = "N")]
+    pub lines: Option<usize>,
+}
+
+#[derive(Parser, Debug)]
+pub struct GrepArgs {
+    /// Regular expression to search for
+    #[arg(value_name = "PATTERN")]
+    pub pattern: String,
+
+    /// The object, or prefix whose objects are searched
+    #[arg(value_name = "PATH", value_parser = parse_validated_path)]
+    pub path: String,
+
+    /// Search every object under PATH, not just its direct children
+    #[arg(short = 'r', short_alias = 'R', long)]
+    pub recursive: bool,
+
+    /// Match without regard to case
+    #[arg(short = 'i', long)]
+    pub ignore_case: bool,
+
+    /// Print only the keys of objects containing a match
+    #[arg(short = 'l', long)]
+    pub files_with_matches: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct SelectArgs {
+    /// The CSV object to filter
+    #[arg(value_name = "PATH", value_parser = parse_validated_path)]
+    pub path: String,
+
+    /// Query such as "SELECT col1, col3 WHERE col3 > 100 AND col2 = 'eu'"; columns are
+    /// col1, col2, ... (or _1, _2, ...)
+    #[arg(short = 'q', long)]
+    pub query: String,
+
+    /// Skip the first row, which holds column names
+    #[arg(long)]
+    pub header: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Print every setting with its value (secrets redacted) and where it came from
+    Show,
+}
+
+/// Exit status of a command that failed, as grep(1) uses for errors. Distinct from
+/// [`Outcome::NotFound`], so scripts can tell a miss from a failed request.
+pub const ERROR_EXIT_STATUS: u8 = 2;
+
+/// How a command that ran without error ended, which sets the process exit status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Exit status 0
+    Success,
+    /// The path tested is absent, or nothing matched: exit status 1
+    NotFound,
+}
+
+impl Outcome {
+    /// `Success` if something was found, `NotFound` otherwise.
+    pub fn found(found: bool) -> Self {
+        if found { Self::Success } else { Self::NotFound }
+    }
+
+    /// The process exit status for this outcome.
+    pub fn status(self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::NotFound => 1,
+        }
+    }
+
+    pub fn exit_code(self) -> ExitCode {
+        ExitCode::from(self.status())
+    }
+}
+
+pub async fn run(args: Args, client: StorageClient) -> Result<Outcome> {
+    let on_error = args.error_policy();
+    let json_style = args.json_style();
+    let client = client.with_progress(ProgressOptions {
+        format: args.progress_format,
+        disabled: args.no_progress,
+        file: args.progress_to_file.clone(),
+    });
+
     match args.command {
         Commands::Ls(ls_args) => {
-            client
-                .list_directory(&ls_args.path, ls_args.long, ls_args.recursive)
-                .await?;
+            let options = ListOptions {
+                long: ls_args.long,
+                recursive: ls_args.recursive,
+                time_style: ls_args.time_style,
+                sort: ls_args.sort,
+                template: ls_args.format_template,
+                null_separated: ls_args.null,
+                versions: ls_args.versions,
+                time_range: ls_args.time_range.range(),
+                parallel_stat: ls_args.parallel_stat,
+                recursive_summary: ls_args.recursive_summary,
+                format: ls_args.format,
+                json_style,
+                keys_only: ls_args.keys_only,
+                basename_only: ls_args.basename_only,
+                skip_denied: ls_args.continue_on_access_denied,
+                strip_trailing_slash: ls_args.strip_trailing_slash,
+                page_size: ls_args.page_size,
+                strict_utf8: ls_args.strict_utf8,
+                checksums: ls_args.checksums,
+                grouped: ls_args.grouped,
+            };
+            client.list_directory(&ls_args.path, &options).await?;
         }
         Commands::Get(get_args) => {
-            client
-                .download_files(&get_args.remote, &get_args.local)
-                .await?;
+            let circuit_breaker = get_args.breaker_policy();
+            let health_check = get_args.health_policy();
+            let options = DownloadOptions {
+                version_id: get_args.version_id,
+                dedupe: get_args.dedupe,
+                if_size_differs: get_args.if_size_differs,
+                on_error,
+                concurrency: get_args.concurrency,
+                verify: get_args.verify,
+                checksum_retries: get_args.retry_on_checksum_mismatch,
+                circuit_breaker,
+                health_check,
+                strict_utf8: get_args.strict_utf8,
+                transfer_log: get_args
+                    .transfer_log
+                    .as_deref()
+                    .map(TransferLog::open)
+                    .transpose()?,
+                lenient: get_args.lenient,
+                size_range: get_args.size_range.range(),
+            };
+            for remote in &get_args.remote {
+                let stats = client
+                    .download_files(remote, &get_args.local, &options)
+                    .await?;
+                println!("Downloaded {stats}");
+            }
         }
         Commands::Du(du_args) => {
-            client.disk_usage(&du_args.path, du_args.summary).await?;
+            let options = UsageOptions {
+                summary: du_args.summary,
+                max_depth: du_args.max_depth,
+                by_prefix: du_args.by_prefix,
+                time_range: du_args.time_range.range(),
+                compact: du_args.compact,
+                by_extension: du_args.by_extension,
+                format: du_args.format,
+                json_style,
+            };
+            client.disk_usage(&du_args.path, &options).await?;
         }
         Commands::Put(put_args) => {
-            client
-                .upload_files(&put_args.local, &put_args.remote, put_args.recursive)
-                .await?;
+            let options = UploadOptions {
+                recursive: put_args.recursive,
+                no_overwrite_newer: put_args.no_overwrite_newer,
+                only_newer: put_args.only_newer,
+                skip_undated: put_args.skip_undated,
+                checksum_algorithm: put_args.checksum_algorithm,
+                checksum_retries: put_args.retry_on_checksum_mismatch,
+                content_disposition: put_args.content_disposition,
+                cache_control: put_args.cache_control,
+                normalize_unicode: put_args.normalize_unicode,
+                part_concurrency: put_args.part_concurrency.map(NonZeroUsize::get),
+                part_size: put_args.part_size,
+                detect_content_type: put_args.detect_content_type,
+                on_error,
+                manifest: put_args
+                    .output_manifest
+                    .as_deref()
+                    .map(Manifest::create)
+                    .transpose()?,
+                fail_if_changed: put_args.fail_if_changed,
+                transfer_log: put_args
+                    .transfer_log
+                    .as_deref()
+                    .map(TransferLog::open)
+                    .transpose()?,
+                size_range: put_args.size_range.range(),
+            };
+            for local in &put_args.local {
+                let stats = client
+                    .upload_files(local, &put_args.remote, &options)
+                    .await?;
+                println!("Uploaded {stats}");
+            }
         }
         Commands::Rm(rm_args) => {
+            client.ensure_writable("delete")?;
             if !confirm_deletion(&rm_args.paths, rm_args.force)? {
                 println!("Operation cancelled.");
-                return Ok(());
+                return Ok(Outcome::Success);
             }
             client
                 .delete_files(&rm_args.paths, rm_args.recursive)
                 .await?;
         }
         Commands::Cp(cp_args) => {
+            ensure!(
+                cp_args.content_type.is_none()
+                    || cp_args.metadata_directive == MetadataDirective::Replace,
+                ContentTypeWithoutReplaceSnafu
+            );
+            let options = CopyOptions {
+                client_side: cp_args.client_side,
+                preserve_metadata: cp_args.preserve_metadata,
+                on_error,
+                metadata_directive: cp_args.metadata_directive,
+                content_type: cp_args.content_type,
+                overwrite_policy: cp_args.overwrite_policy,
+            };
+            if StorageUrl::parse(&cp_args.src_path).is_some()
+                || StorageUrl::parse(&cp_args.dest_path).is_some()
+            {
+                let (src_client, src_path) = resolve_location(&cp_args.src_path, &client).await?;
+                let (dest_client, dest_path) =
+                    resolve_location(&cp_args.dest_path, &client).await?;
+                src_client
+                    .copy_files_to(&dest_client, &src_path, &dest_path, &options)
+                    .await?;
+            } else {
+                client
+                    .copy_files(&cp_args.src_path, &cp_args.dest_path, &options)
+                    .await?;
+            }
+        }
+        Commands::Mv(mv_args) => {
             client
-                .copy_files(&cp_args.src_path, &cp_args.dest_path)
+                .move_object(&mv_args.src_path, &mv_args.dest_path)
                 .await?;
         }
         Commands::Mkdir(mkdir_args) => {
@@ -191,9 +1124,17 @@ pub async fn run(args: Args, client: StorageClient) -> Result<()> {
                 .create_directory(&mkdir_args.path, mkdir_args.parents)
                 .await?;
         }
+        Commands::Exists(exists_args) => {
+            return Ok(Outcome::found(client.path_exists(&exists_args.path).await?));
+        }
         Commands::Stat(stat_args) => {
-            let format = if stat_args.json {
-                OutputFormat::Json
+            if stat_args.exists_only {
+                return Ok(Outcome::found(client.path_exists(&stat_args.path).await?));
+            }
+            let format = if let Some(template) = stat_args.format {
+                OutputFormat::Template(template)
+            } else if stat_args.json {
+                OutputFormat::Json(json_style)
             } else if stat_args.raw {
                 OutputFormat::Raw
             } else {
@@ -201,6 +1142,53 @@ pub async fn run(args: Args, client: StorageClient) -> Result<()> {
             };
             client.stat_metadata(&stat_args.path, format).await?;
         }
+        Commands::Find(find_args) => {
+            let options = FindOptions {
+                empty: find_args.empty,
+                time_range: find_args.time_range.range(),
+                null_separated: find_args.null,
+            };
+            client.find_objects(&find_args.path, &options).await?;
+        }
+        Commands::Cat(cat_args) => {
+            client.cat_file(&cat_args.path).await?;
+        }
+        Commands::Head(peek_args) => {
+            client
+                .head(&peek_args.path, peek_args.bytes, peek_args.lines)
+                .await?;
+        }
+        Commands::Tail(peek_args) => {
+            client
+                .tail(&peek_args.path, peek_args.bytes, peek_args.lines)
+                .await?;
+        }
+        Commands::Grep(grep_args) => {
+            let options = GrepOptions {
+                recursive: grep_args.recursive,
+                ignore_case: grep_args.ignore_case,
+                files_with_matches: grep_args.files_with_matches,
+            };
+            // Like grep(1), exit with status 1 when nothing matched.
+            return Ok(Outcome::found(
+                client
+                    .grep_objects(&grep_args.path, &grep_args.pattern, &options)
+                    .await?,
+            ));
+        }
+        Commands::Select(select_args) => {
+            let options = SelectOptions {
+                header: select_args.header,
+            };
+            client
+                .select_object(&select_args.path, &select_args.query, &options)
+                .await?;
+        }
+        Commands::Doctor => crate::doctor::run(args.region_auto).await?,
+        Commands::Config(_) => show_config(&args)?,
+        Commands::Batch(batch_args) => {
+            crate::batch::run(&batch_args.script, batch_args.stop_on_error, &client).await?;
+        }
     }
-    Ok(())
+    Ok(Outcome::Success)
 }
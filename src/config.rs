@@ -1,9 +1,38 @@
 use crate::error::{Error, Result};
 use crate::storage::constants::DEFAULT_FS_ROOT;
-use crate::storage::{StorageConfig, StorageProvider};
+use crate::storage::{StorageConfig, StorageProvider, region_from_endpoint};
 use log::warn;
 use std::env;
+use std::fmt;
 use std::str::FromStr;
+use std::sync::OnceLock;
+
+mod credential_process;
+pub use self::credential_process::{CredentialProcess, ProcessCredentials};
+
+/// Environment variable naming a command that prints credentials as JSON.
+pub const CREDENTIAL_PROCESS_KEY: &str = "STORAGE_CREDENTIAL_PROCESS";
+
+/// Credential process shared by every config loaded in this run, so its cache is too.
+static CREDENTIAL_PROCESS: OnceLock<Option<CredentialProcess>> = OnceLock::new();
+
+/// Take cloud credentials from `command` instead of the access key variables. Must be
+/// called before the first config is loaded; later calls have no effect.
+pub fn set_credential_process(command: String) {
+    let _ = CREDENTIAL_PROCESS.set(Some(CredentialProcess::new(command)));
+}
+
+/// The configured credential process, from [`set_credential_process`] or the environment.
+fn credential_process() -> Option<&'static CredentialProcess> {
+    CREDENTIAL_PROCESS
+        .get_or_init(|| {
+            env::var(CREDENTIAL_PROCESS_KEY)
+                .ok()
+                .filter(|command| !command.trim().is_empty())
+                .map(CredentialProcess::new)
+        })
+        .as_ref()
+}
 
 /// Read the first available environment variable from a list of keys
 fn env_any(keys: &[&str]) -> Option<String> {
@@ -22,6 +51,33 @@ fn env_any_required(keys: &[&str]) -> Result<String> {
     })
 }
 
+/// A storage location written as `<scheme>://<bucket>/<path>`, e.g. `s3://bucket/key`.
+///
+/// The scheme is any provider name accepted by `STORAGE_PROVIDER`. For `fs` the part after
+/// `fs://` is an absolute local path, e.g. `fs:///data/file.txt`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageUrl {
+    pub scheme: String,
+    pub bucket: String,
+    pub path: String,
+}
+
+impl StorageUrl {
+    /// Parse a URL-style location; returns `None` for plain paths.
+    pub fn parse(input: &str) -> Option<Self> {
+        let (scheme, rest) = input.split_once("://")?;
+        if scheme.is_empty() || !scheme.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let (bucket, path) = rest.split_once('/').unwrap_or((rest, ""));
+        Some(Self {
+            scheme: scheme.to_lowercase(),
+            bucket: bucket.to_string(),
+            path: path.to_string(),
+        })
+    }
+}
+
 /// Provider-specific environment variable keys
 struct ProviderKeys {
     bucket: Vec<&'static str>,
@@ -52,6 +108,17 @@ impl ProviderKeys {
         }
     }
 
+    fn for_azure() -> Self {
+        Self {
+            bucket: vec!["STORAGE_BUCKET", "AZURE_STORAGE_CONTAINER"],
+            access_key_id: vec!["STORAGE_ACCESS_KEY_ID", "AZURE_STORAGE_ACCOUNT_NAME"],
+            secret_key: vec!["STORAGE_ACCESS_KEY_SECRET", "AZURE_STORAGE_ACCOUNT_KEY"],
+            // Azure addresses storage by account, not by region.
+            region: vec![],
+            endpoint: vec!["STORAGE_ENDPOINT", "AZURE_STORAGE_ENDPOINT"],
+        }
+    }
+
     fn for_minio() -> Self {
         Self {
             bucket: vec!["STORAGE_BUCKET", "MINIO_BUCKET"],
@@ -63,6 +130,55 @@ impl ProviderKeys {
     }
 }
 
+impl ProviderKeys {
+    /// Check provider-specific variables before the generic `STORAGE_*` ones, so that two
+    /// providers can be configured side by side for cross-provider transfers.
+    fn prefer_provider_specific(mut self) -> Self {
+        for keys in [
+            &mut self.bucket,
+            &mut self.access_key_id,
+            &mut self.secret_key,
+            &mut self.region,
+            &mut self.endpoint,
+        ] {
+            keys.sort_by_key(|key| key.starts_with("STORAGE_"));
+        }
+        self
+    }
+}
+
+/// Environment variable keys for Google Cloud Storage, which authenticates with a
+/// service-account key file rather than an access key pair
+struct GcsKeys {
+    bucket: Vec<&'static str>,
+    credential_path: Vec<&'static str>,
+    endpoint: Vec<&'static str>,
+}
+
+impl Default for GcsKeys {
+    fn default() -> Self {
+        Self {
+            bucket: vec!["STORAGE_BUCKET", "GCS_BUCKET"],
+            credential_path: vec!["STORAGE_CREDENTIAL_PATH", "GOOGLE_APPLICATION_CREDENTIALS"],
+            endpoint: vec!["STORAGE_ENDPOINT", "GCS_ENDPOINT"],
+        }
+    }
+}
+
+impl GcsKeys {
+    /// See [`ProviderKeys::prefer_provider_specific`].
+    fn prefer_provider_specific(mut self) -> Self {
+        for keys in [
+            &mut self.bucket,
+            &mut self.credential_path,
+            &mut self.endpoint,
+        ] {
+            keys.sort_by_key(|key| key.starts_with("STORAGE_"));
+        }
+        self
+    }
+}
+
 /// Select appropriate ProviderKeys for S3-like providers (AWS/MinIO)
 fn s3_like_keys(provider_str: &str) -> ProviderKeys {
     if provider_str.eq_ignore_ascii_case("minio") {
@@ -81,30 +197,435 @@ pub fn load_storage_config() -> Result<StorageConfig> {
     let provider = StorageProvider::from_str(&provider_str)?;
 
     match provider {
-        StorageProvider::Oss => load_cloud_config(ProviderKeys::for_oss(), StorageConfig::oss),
-        StorageProvider::S3 => load_cloud_config(s3_like_keys(&provider_str), StorageConfig::s3),
+        StorageProvider::Oss => {
+            load_cloud_config(ProviderKeys::for_oss(), None, StorageConfig::oss)
+        }
+        StorageProvider::S3 => {
+            load_cloud_config(s3_like_keys(&provider_str), None, StorageConfig::s3)
+        }
+        StorageProvider::Gcs => load_gcs_config(GcsKeys::default(), None),
+        StorageProvider::Azblob => {
+            load_cloud_config(ProviderKeys::for_azure(), None, azblob_config)
+        }
         StorageProvider::Fs => load_fs_config(),
         StorageProvider::Hdfs => load_hdfs_config(),
     }
 }
 
-/// Load configuration for any cloud storage provider
-fn load_cloud_config<F>(keys: ProviderKeys, config_constructor: F) -> Result<StorageConfig>
+/// Load configuration for the location named by a storage URL. The bucket comes from the
+/// URL; credentials and the remaining settings come from the environment.
+pub fn load_storage_config_for_url(url: &StorageUrl) -> Result<StorageConfig> {
+    let provider = StorageProvider::from_str(&url.scheme)?;
+    let bucket = Some(url.bucket.clone());
+
+    match provider {
+        StorageProvider::Oss => load_cloud_config(
+            ProviderKeys::for_oss().prefer_provider_specific(),
+            bucket,
+            StorageConfig::oss,
+        ),
+        StorageProvider::S3 => load_cloud_config(
+            s3_like_keys(&url.scheme).prefer_provider_specific(),
+            bucket,
+            StorageConfig::s3,
+        ),
+        StorageProvider::Gcs => {
+            load_gcs_config(GcsKeys::default().prefer_provider_specific(), bucket)
+        }
+        StorageProvider::Azblob => load_cloud_config(
+            ProviderKeys::for_azure().prefer_provider_specific(),
+            bucket,
+            azblob_config,
+        ),
+        StorageProvider::Fs => Ok(StorageConfig::fs("/".to_string())),
+        StorageProvider::Hdfs => load_hdfs_config(),
+    }
+}
+
+/// Adapt [`StorageConfig::azblob`] to [`load_cloud_config`]; Azure has no region.
+fn azblob_config(
+    container: String,
+    account_name: String,
+    account_key: String,
+    _region: Option<String>,
+) -> StorageConfig {
+    StorageConfig::azblob(container, account_name, account_key)
+}
+
+/// Load configuration for any cloud storage provider, optionally overriding the bucket
+fn load_cloud_config<F>(
+    keys: ProviderKeys,
+    bucket: Option<String>,
+    config_constructor: F,
+) -> Result<StorageConfig>
 where
     F: FnOnce(String, String, String, Option<String>) -> StorageConfig,
 {
-    let bucket = env_any_required(&keys.bucket)?;
-    let access_key_id = env_any_required(&keys.access_key_id)?;
-    let secret_key = env_any_required(&keys.secret_key)?;
+    let bucket = match bucket {
+        Some(bucket) => bucket,
+        None => env_any_required(&keys.bucket)?,
+    };
+    let process = credential_process();
+    let (access_key_id, secret_key, session_token) = match process {
+        Some(process) => {
+            let credentials = process.credentials()?;
+            (
+                credentials.access_key_id,
+                credentials.secret_access_key,
+                credentials.session_token,
+            )
+        }
+        None => (
+            env_any_required(&keys.access_key_id)?,
+            env_any_required(&keys.secret_key)?,
+            None,
+        ),
+    };
 
     let region = env_any(&keys.region);
     let endpoint = env_any(&keys.endpoint);
 
     let mut config = config_constructor(bucket, access_key_id, secret_key, region);
+    config.session_token = session_token;
+    config.warn_unused_session_token();
+    config.credential_process = process;
     config.endpoint = endpoint;
     Ok(config)
 }
 
+/// A configuration problem found by [`diagnose_config`], with a suggested fix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// The problem stops the CLI from working (rather than being a likely mistake)
+    pub fatal: bool,
+    pub problem: String,
+    pub fix: String,
+}
+
+impl ConfigIssue {
+    fn fatal(problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            fatal: true,
+            problem: problem.into(),
+            fix: fix.into(),
+        }
+    }
+
+    fn warning(problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            fatal: false,
+            problem: problem.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+/// Check the environment for every missing or malformed setting, instead of stopping at
+/// the first one like [`load_storage_config`] does.
+pub fn diagnose_config() -> Vec<ConfigIssue> {
+    diagnose_config_with(|key| match key {
+        CREDENTIAL_PROCESS_KEY => credential_process().map(|process| process.command().to_string()),
+        _ => env::var(key).ok(),
+    })
+}
+
+fn diagnose_config_with(lookup: impl Fn(&str) -> Option<String>) -> Vec<ConfigIssue> {
+    let first = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| lookup(key))
+            .filter(|value| !value.trim().is_empty())
+    };
+    let mut issues = Vec::new();
+
+    let provider_str = match lookup("STORAGE_PROVIDER") {
+        Some(provider_str) => provider_str,
+        None => {
+            issues.push(ConfigIssue::warning(
+                "STORAGE_PROVIDER is not set; defaulting to oss",
+                "export STORAGE_PROVIDER=oss (or s3, minio, gcs, azblob, fs, hdfs)",
+            ));
+            "oss".to_string()
+        }
+    };
+    let provider = match StorageProvider::from_str(&provider_str) {
+        Ok(provider) => provider,
+        Err(_) => {
+            issues.push(ConfigIssue::fatal(
+                format!("STORAGE_PROVIDER '{provider_str}' is not a supported provider"),
+                "export STORAGE_PROVIDER=oss (or s3, minio, gcs, azblob, fs, hdfs)",
+            ));
+            return issues;
+        }
+    };
+
+    let keys = match provider {
+        StorageProvider::Oss => ProviderKeys::for_oss(),
+        StorageProvider::S3 => s3_like_keys(&provider_str),
+        StorageProvider::Azblob => ProviderKeys::for_azure(),
+        StorageProvider::Gcs => {
+            // Without a key file, GCS falls back to the application default credentials.
+            let keys = GcsKeys::default();
+            if first(&keys.bucket).is_none() {
+                issues.push(ConfigIssue::fatal(
+                    "bucket is not set",
+                    format!("export {}", keys.bucket.join(" or ")),
+                ));
+            }
+            return issues;
+        }
+        StorageProvider::Fs => return issues,
+        StorageProvider::Hdfs => {
+            if first(&["HDFS_NAME_NODE"]).is_none() {
+                issues.push(ConfigIssue::fatal(
+                    "HDFS name node is not set",
+                    "export HDFS_NAME_NODE=hdfs://<host>:<port>",
+                ));
+            }
+            return issues;
+        }
+    };
+
+    // A credential process supplies both keys.
+    let keys_from_process = first(&[CREDENTIAL_PROCESS_KEY]).is_some();
+    for (label, names) in [
+        ("bucket", &keys.bucket),
+        ("access key ID", &keys.access_key_id),
+        ("access key secret", &keys.secret_key),
+    ] {
+        let supplied = keys_from_process && label != "bucket";
+        if !supplied && first(names).is_none() {
+            issues.push(ConfigIssue::fatal(
+                format!("{label} is not set"),
+                format!("export {}", names.join(" or ")),
+            ));
+        }
+    }
+
+    let endpoint = first(&keys.endpoint);
+    if let Some(endpoint) = &endpoint
+        && !endpoint.starts_with("http://")
+        && !endpoint.starts_with("https://")
+    {
+        issues.push(ConfigIssue::fatal(
+            format!("endpoint '{endpoint}' has no scheme"),
+            format!("export {}=https://{endpoint}", keys.endpoint[0]),
+        ));
+    }
+
+    let region = first(&keys.region);
+    match provider {
+        StorageProvider::S3 if region.is_none() && endpoint.is_none() => {
+            issues.push(ConfigIssue::fatal(
+                "region is not set",
+                format!("export {}=<bucket region, e.g. us-east-1>", keys.region[0]),
+            ));
+        }
+        StorageProvider::S3 if region.is_none() => {
+            issues.push(ConfigIssue::warning(
+                "region is not set for the custom endpoint",
+                format!(
+                    "export {}=<bucket region>, or pass --region-auto to detect it",
+                    keys.region[0]
+                ),
+            ));
+        }
+        StorageProvider::Oss if endpoint.is_none() => {
+            issues.push(ConfigIssue::warning(
+                "OSS endpoint is not set",
+                format!(
+                    "export {}=https://oss-<region>.aliyuncs.com",
+                    keys.endpoint[0]
+                ),
+            ));
+        }
+        _ => {}
+    }
+
+    issues
+}
+
+/// Where a resolved setting got its value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingSource {
+    /// A global flag, or the environment variable clap reads in its place
+    Flag {
+        flag: &'static str,
+        env: Option<&'static str>,
+    },
+    /// An environment variable
+    Env(String),
+    /// Returned by the credential process when the config is loaded
+    CredentialProcess,
+    /// Read from the endpoint hostname
+    Endpoint,
+    /// The built-in default
+    Default,
+    /// Not set anywhere
+    Unset,
+}
+
+impl fmt::Display for SettingSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingSource::Flag {
+                flag,
+                env: Some(env),
+            } => write!(f, "flag {flag} or env {env}"),
+            SettingSource::Flag { flag, env: None } => write!(f, "flag {flag}"),
+            SettingSource::Env(key) => write!(f, "env {key}"),
+            SettingSource::CredentialProcess => f.write_str("credential process"),
+            SettingSource::Endpoint => f.write_str("from endpoint"),
+            SettingSource::Default => f.write_str("default"),
+            SettingSource::Unset => f.write_str("unset"),
+        }
+    }
+}
+
+/// One field of the resolved configuration and where it came from (`storify config show`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSetting {
+    pub name: &'static str,
+    pub value: Option<String>,
+    pub source: SettingSource,
+    /// Printed redacted
+    pub secret: bool,
+}
+
+impl ResolvedSetting {
+    pub fn new(name: &'static str, value: Option<String>, source: SettingSource) -> Self {
+        Self {
+            name,
+            value,
+            source,
+            secret: false,
+        }
+    }
+
+    fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+}
+
+impl fmt::Display for ResolvedSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match &self.value {
+            Some(_) if self.secret => "********",
+            Some(value) => value.as_str(),
+            None => "-",
+        };
+        write!(f, "{:<24} {value:<40} ({})", self.name, self.source)
+    }
+}
+
+/// Resolve the settings [`load_storage_config`] would use, recording where each one came
+/// from. Unlike loading, missing settings are reported as unset rather than failing.
+pub fn resolve_config_sources() -> Vec<ResolvedSetting> {
+    let mut settings = resolve_config_sources_with(|key| match key {
+        CREDENTIAL_PROCESS_KEY => credential_process().map(|process| process.command().to_string()),
+        _ => env::var(key).ok(),
+    });
+    // A process that doesn't match the environment was given with --credential-process.
+    for setting in &mut settings {
+        if setting.name == "credential_process"
+            && setting.value != env::var(CREDENTIAL_PROCESS_KEY).ok()
+        {
+            setting.source = SettingSource::Flag {
+                flag: "--credential-process",
+                env: None,
+            };
+        }
+    }
+    settings
+}
+
+fn resolve_config_sources_with(lookup: impl Fn(&str) -> Option<String>) -> Vec<ResolvedSetting> {
+    let from_env = |name: &'static str, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| lookup(key).map(|value| (key, value)))
+            .map_or_else(
+                || ResolvedSetting::new(name, None, SettingSource::Unset),
+                |(key, value)| {
+                    ResolvedSetting::new(name, Some(value), SettingSource::Env(key.to_string()))
+                },
+            )
+    };
+    let or_default = |setting: ResolvedSetting, default: &str| {
+        if setting.value.is_some() {
+            setting
+        } else {
+            ResolvedSetting::new(
+                setting.name,
+                Some(default.to_string()),
+                SettingSource::Default,
+            )
+        }
+    };
+
+    let provider = or_default(from_env("provider", &["STORAGE_PROVIDER"]), "oss");
+    let provider_str = provider.value.clone().unwrap_or_default();
+    let mut settings = vec![provider];
+    let keys = match StorageProvider::from_str(&provider_str) {
+        Ok(StorageProvider::Oss) => ProviderKeys::for_oss(),
+        Ok(StorageProvider::S3) => s3_like_keys(&provider_str),
+        Ok(StorageProvider::Azblob) => ProviderKeys::for_azure(),
+        Ok(StorageProvider::Gcs) => {
+            let keys = GcsKeys::default();
+            settings.push(from_env("bucket", &keys.bucket));
+            settings.push(from_env("credential_path", &keys.credential_path));
+            settings.push(from_env("endpoint", &keys.endpoint));
+            return settings;
+        }
+        Ok(StorageProvider::Fs) => {
+            settings.push(or_default(
+                from_env("root_path", &["STORAGE_ROOT_PATH"]),
+                DEFAULT_FS_ROOT,
+            ));
+            return settings;
+        }
+        Ok(StorageProvider::Hdfs) => {
+            settings.push(from_env("name_node", &["HDFS_NAME_NODE"]));
+            settings.push(or_default(from_env("root_path", &["HDFS_ROOT_PATH"]), "/"));
+            return settings;
+        }
+        Err(_) => return settings,
+    };
+
+    settings.push(from_env("bucket", &keys.bucket));
+    let process = from_env("credential_process", &[CREDENTIAL_PROCESS_KEY]);
+    if process.value.is_some() {
+        settings.push(process);
+        for name in ["access_key_id", "access_key_secret", "session_token"] {
+            let setting = ResolvedSetting::new(name, None, SettingSource::CredentialProcess);
+            settings.push(setting.secret());
+        }
+    } else {
+        settings.push(from_env("access_key_id", &keys.access_key_id).secret());
+        settings.push(from_env("access_key_secret", &keys.secret_key).secret());
+    }
+    let mut region = from_env("region", &keys.region);
+    let endpoint = from_env("endpoint", &keys.endpoint);
+    if region.value.is_none()
+        && let Some(inferred) = endpoint.value.as_deref().and_then(region_from_endpoint)
+    {
+        region = ResolvedSetting::new("region", Some(inferred), SettingSource::Endpoint);
+    }
+    settings.push(region);
+    settings.push(endpoint);
+    settings
+}
+
+/// Load Google Cloud Storage configuration, optionally overriding the bucket
+fn load_gcs_config(keys: GcsKeys, bucket: Option<String>) -> Result<StorageConfig> {
+    let bucket = match bucket {
+        Some(bucket) => bucket,
+        None => env_any_required(&keys.bucket)?,
+    };
+    let mut config = StorageConfig::gcs(bucket, env_any(&keys.credential_path));
+    config.endpoint = env_any(&keys.endpoint);
+    Ok(config)
+}
+
 /// Load HDFS configuration
 fn load_hdfs_config() -> Result<StorageConfig> {
     let name_node = env::var("HDFS_NAME_NODE").map_err(|_| Error::MissingEnvVar {
@@ -119,3 +640,184 @@ fn load_fs_config() -> Result<StorageConfig> {
     let root_path = env::var("STORAGE_ROOT_PATH").unwrap_or_else(|_| DEFAULT_FS_ROOT.to_string());
     Ok(StorageConfig::fs(root_path))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_storage_url() {
+        let url = StorageUrl::parse("s3://bucket-a/dir/key.txt").unwrap();
+        assert_eq!(url.scheme, "s3");
+        assert_eq!(url.bucket, "bucket-a");
+        assert_eq!(url.path, "dir/key.txt");
+
+        let url = StorageUrl::parse("OSS://bucket-b").unwrap();
+        assert_eq!(url.scheme, "oss");
+        assert_eq!(url.bucket, "bucket-b");
+        assert_eq!(url.path, "");
+
+        let url = StorageUrl::parse("fs:///tmp/data/file.txt").unwrap();
+        assert_eq!(url.bucket, "");
+        assert_eq!(url.path, "tmp/data/file.txt");
+    }
+
+    #[test]
+    fn test_parse_plain_path_is_not_url() {
+        assert_eq!(StorageUrl::parse("dir/key.txt"), None);
+        assert_eq!(StorageUrl::parse("/abs/path"), None);
+        assert_eq!(StorageUrl::parse("weird dir://x"), None);
+    }
+
+    fn diagnose(vars: &[(&str, &str)]) -> Vec<ConfigIssue> {
+        let vars: std::collections::HashMap<_, _> = vars.iter().copied().collect();
+        diagnose_config_with(|key| vars.get(key).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn test_diagnose_incomplete_config() {
+        let issues = diagnose(&[
+            ("STORAGE_PROVIDER", "s3"),
+            ("STORAGE_BUCKET", "bucket"),
+            ("STORAGE_ACCESS_KEY_ID", "id"),
+            ("STORAGE_ENDPOINT", "minio.local:9000"),
+        ]);
+        let fatal: Vec<_> = issues.iter().filter(|i| i.fatal).collect();
+        assert_eq!(fatal.len(), 2, "{issues:?}");
+        assert_eq!(fatal[0].problem, "access key secret is not set");
+        assert!(fatal[0].fix.contains("STORAGE_ACCESS_KEY_SECRET"));
+        assert!(fatal[1].problem.contains("has no scheme"));
+        assert_eq!(
+            fatal[1].fix,
+            "export STORAGE_ENDPOINT=https://minio.local:9000"
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|i| !i.fatal && i.fix.contains("--region-auto"))
+        );
+    }
+
+    #[test]
+    fn test_diagnose_complete_config() {
+        let issues = diagnose(&[
+            ("STORAGE_PROVIDER", "s3"),
+            ("AWS_S3_BUCKET", "bucket"),
+            ("AWS_ACCESS_KEY_ID", "id"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("AWS_DEFAULT_REGION", "us-east-1"),
+        ]);
+        assert!(issues.is_empty(), "{issues:?}");
+
+        let issues = diagnose(&[
+            ("STORAGE_PROVIDER", "s3"),
+            ("STORAGE_BUCKET", "bucket"),
+            ("STORAGE_REGION", "us-east-1"),
+            (CREDENTIAL_PROCESS_KEY, "vault-creds --role storage"),
+        ]);
+        assert!(issues.is_empty(), "{issues:?}");
+
+        let issues = diagnose(&[("STORAGE_PROVIDER", "ftp")]);
+        assert!(issues[0].fatal);
+        assert!(issues[0].problem.contains("ftp"));
+    }
+
+    fn resolve(vars: &[(&str, &str)]) -> Vec<ResolvedSetting> {
+        let vars: std::collections::HashMap<_, _> = vars.iter().copied().collect();
+        resolve_config_sources_with(|key| vars.get(key).map(|value| value.to_string()))
+    }
+
+    fn setting<'a>(settings: &'a [ResolvedSetting], name: &str) -> &'a ResolvedSetting {
+        settings.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_config_sources() {
+        let settings = resolve(&[
+            ("STORAGE_PROVIDER", "s3"),
+            ("STORAGE_BUCKET", "generic-bucket"),
+            ("AWS_S3_BUCKET", "aws-bucket"),
+            ("AWS_ACCESS_KEY_ID", "AKIDEXAMPLE"),
+            ("AWS_SECRET_ACCESS_KEY", "very-secret"),
+        ]);
+        // The generic STORAGE_* variable overrides the provider-specific one.
+        let bucket = setting(&settings, "bucket");
+        assert_eq!(bucket.value.as_deref(), Some("generic-bucket"));
+        assert_eq!(bucket.source.to_string(), "env STORAGE_BUCKET");
+        assert_eq!(setting(&settings, "region").source, SettingSource::Unset);
+
+        let rendered: Vec<String> = settings.iter().map(ToString::to_string).collect();
+        assert!(rendered.iter().all(|line| !line.contains("very-secret")));
+        assert!(rendered.iter().all(|line| !line.contains("AKIDEXAMPLE")));
+        assert!(
+            rendered
+                .iter()
+                .any(|line| line.contains("********") && line.contains("AWS_SECRET_ACCESS_KEY"))
+        );
+
+        let settings = resolve(&[]);
+        let provider = setting(&settings, "provider");
+        assert_eq!(provider.value.as_deref(), Some("oss"));
+        assert_eq!(provider.source, SettingSource::Default);
+
+        let settings = resolve(&[
+            ("STORAGE_PROVIDER", "oss"),
+            (CREDENTIAL_PROCESS_KEY, "vault-creds"),
+        ]);
+        assert_eq!(
+            setting(&settings, "access_key_secret").source,
+            SettingSource::CredentialProcess
+        );
+
+        let settings = resolve(&[
+            ("STORAGE_PROVIDER", "oss"),
+            ("STORAGE_ENDPOINT", "https://oss-cn-hangzhou.aliyuncs.com"),
+        ]);
+        let region = setting(&settings, "region");
+        assert_eq!(region.value.as_deref(), Some("cn-hangzhou"));
+        assert_eq!(region.source, SettingSource::Endpoint);
+    }
+
+    #[test]
+    fn test_gcs_config() {
+        let issues = diagnose(&[("STORAGE_PROVIDER", "gcs")]);
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert_eq!(issues[0].problem, "bucket is not set");
+        assert!(issues[0].fix.contains("GCS_BUCKET"));
+        // The key file is optional: application default credentials are used without one.
+        assert!(diagnose(&[("STORAGE_PROVIDER", "google"), ("GCS_BUCKET", "b")]).is_empty());
+
+        let settings = resolve(&[
+            ("STORAGE_PROVIDER", "gcs"),
+            ("GCS_BUCKET", "bucket"),
+            ("GOOGLE_APPLICATION_CREDENTIALS", "/etc/gcs/key.json"),
+        ]);
+        let credential_path = setting(&settings, "credential_path");
+        assert_eq!(credential_path.value.as_deref(), Some("/etc/gcs/key.json"));
+        assert_eq!(
+            credential_path.source.to_string(),
+            "env GOOGLE_APPLICATION_CREDENTIALS"
+        );
+        assert_eq!(setting(&settings, "endpoint").source, SettingSource::Unset);
+    }
+
+    #[test]
+    fn test_azure_config() {
+        let issues = diagnose(&[
+            ("STORAGE_PROVIDER", "azure"),
+            ("AZURE_STORAGE_CONTAINER", "container"),
+            ("AZURE_STORAGE_ACCOUNT_NAME", "myaccount"),
+        ]);
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert_eq!(issues[0].problem, "access key secret is not set");
+        assert!(issues[0].fix.contains("AZURE_STORAGE_ACCOUNT_KEY"));
+
+        let issues = diagnose(&[
+            ("STORAGE_PROVIDER", "azblob"),
+            ("AZURE_STORAGE_CONTAINER", "container"),
+            ("AZURE_STORAGE_ACCOUNT_NAME", "myaccount"),
+            ("AZURE_STORAGE_ACCOUNT_KEY", "a2V5"),
+        ]);
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+}
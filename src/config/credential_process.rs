@@ -0,0 +1,208 @@
+// Credentials from an external command, in the format of AWS's `credential_process`
+use crate::error::{CredentialProcessSnafu, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+
+/// Cached credentials this close to expiring are fetched again.
+const REFRESH_MARGIN: Duration = Duration::minutes(5);
+
+/// Keys printed by a credential process:
+/// `{"Version": 1, "AccessKeyId": ..., "SecretAccessKey": ..., "SessionToken": ...,
+/// "Expiration": "<RFC3339>"}`. Only the two keys are required.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ProcessCredentials {
+    #[serde(default)]
+    version: Option<u32>,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    #[serde(default)]
+    pub session_token: Option<String>,
+    #[serde(default, rename = "Expiration")]
+    expiration_text: Option<String>,
+    #[serde(skip)]
+    pub expiration: Option<DateTime<Utc>>,
+}
+
+impl ProcessCredentials {
+    /// Whether these credentials should be replaced before use at `now`.
+    pub fn expires_soon(&self, now: DateTime<Utc>) -> bool {
+        self.expiration
+            .is_some_and(|expiration| expiration - REFRESH_MARGIN <= now)
+    }
+}
+
+/// An external command that prints credentials as JSON on stdout.
+///
+/// Results are cached and reused until they come within [`REFRESH_MARGIN`] of their
+/// expiration, so short-lived credentials are renewed without running the command for
+/// every client.
+pub struct CredentialProcess {
+    command: String,
+    cached: Mutex<Option<ProcessCredentials>>,
+}
+
+// The cached keys are secret, so only the command is shown.
+impl fmt::Debug for CredentialProcess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CredentialProcess")
+            .field("command", &self.command)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CredentialProcess {
+    pub fn new(command: String) -> Self {
+        Self {
+            command,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Current credentials, running the command when none are cached or they expire soon.
+    pub fn credentials(&self) -> Result<ProcessCredentials> {
+        let mut cached = self.cached.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(credentials) = cached.as_ref()
+            && !credentials.expires_soon(Utc::now())
+        {
+            return Ok(credentials.clone());
+        }
+        let credentials = self.fetch()?;
+        *cached = Some(credentials.clone());
+        Ok(credentials)
+    }
+
+    /// Run the command and parse what it prints.
+    fn fetch(&self) -> Result<ProcessCredentials> {
+        log::debug!("running credential process: {}", self.command);
+        let fail = |reason: String| {
+            CredentialProcessSnafu {
+                command: self.command.clone(),
+                reason,
+            }
+            .fail()
+        };
+        let output = match shell(&self.command).stdin(Stdio::null()).output() {
+            Ok(output) => output,
+            Err(err) => return fail(err.to_string()),
+        };
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return fail(format!("{} {}", output.status, stderr.trim()));
+        }
+        let mut credentials: ProcessCredentials = match serde_json::from_slice(&output.stdout) {
+            Ok(credentials) => credentials,
+            Err(err) => return fail(format!("invalid JSON output: {err}")),
+        };
+        if let Some(version) = credentials.version.filter(|&version| version != 1) {
+            return fail(format!("unsupported Version {version}, expected 1"));
+        }
+        if let Some(text) = &credentials.expiration_text {
+            match DateTime::parse_from_rfc3339(text) {
+                Ok(expiration) => credentials.expiration = Some(expiration.with_timezone(&Utc)),
+                Err(err) => return fail(format!("invalid Expiration '{text}': {err}")),
+            }
+        }
+        Ok(credentials)
+    }
+}
+
+/// Run `command` through the platform shell, so it may carry arguments and quoting.
+fn shell(command: &str) -> Command {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::storage::fixture::TempDir;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+
+    /// A script that counts its runs in `calls` and prints credentials expiring in
+    /// `expires_in` (or never, when `None`).
+    fn stub_script(dir: &Path, expires_in: Option<Duration>) -> (PathBuf, PathBuf) {
+        let calls = dir.join("calls");
+        let expiration = expires_in
+            .map(|after| format!(r#", "Expiration": "{}""#, (Utc::now() + after).to_rfc3339()))
+            .unwrap_or_default();
+        let script = dir.join("creds.sh");
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho run >> '{}'\nprintf '%s' '{{\"Version\": 1, \"AccessKeyId\": \"AKID\", \"SecretAccessKey\": \"SECRET\", \"SessionToken\": \"TOKEN\"{expiration}}}'\n",
+                calls.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        (script, calls)
+    }
+
+    fn runs(calls: &Path) -> usize {
+        fs::read_to_string(calls).map_or(0, |log| log.lines().count())
+    }
+
+    #[test]
+    fn test_stub_script_provides_credentials() {
+        let dir = TempDir::new();
+        let (script, calls) = stub_script(dir.path(), Some(Duration::hours(1)));
+        let process = CredentialProcess::new(format!("'{}' --profile test", script.display()));
+
+        let credentials = process.credentials().unwrap();
+        assert_eq!(credentials.access_key_id, "AKID");
+        assert_eq!(credentials.secret_access_key, "SECRET");
+        assert_eq!(credentials.session_token.as_deref(), Some("TOKEN"));
+        assert!(credentials.expiration.is_some());
+
+        // Still valid for an hour: the cached copy is reused.
+        process.credentials().unwrap();
+        assert_eq!(runs(&calls), 1);
+    }
+
+    #[test]
+    fn test_expiring_credentials_are_fetched_again() {
+        let dir = TempDir::new();
+        let (script, calls) = stub_script(dir.path(), Some(Duration::minutes(1)));
+        let process = CredentialProcess::new(script.display().to_string());
+
+        process.credentials().unwrap();
+        process.credentials().unwrap();
+        assert_eq!(runs(&calls), 2);
+    }
+
+    #[test]
+    fn test_failing_or_malformed_process_is_reported() {
+        let err = CredentialProcess::new("echo denied >&2; exit 3".to_string())
+            .credentials()
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::CredentialProcess { .. }));
+        assert!(err.to_string().contains("denied"), "{err}");
+
+        let err = CredentialProcess::new("echo not-json".to_string())
+            .credentials()
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("invalid JSON"), "{err}");
+    }
+}
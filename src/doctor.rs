@@ -0,0 +1,128 @@
+/// Self-check for configuration and connectivity problems (`storify doctor`).
+use crate::config::{diagnose_config, load_storage_config};
+use crate::error::{Error, Result};
+use crate::storage::StorageClient;
+use opendal::ErrorKind;
+
+/// Tally of check outcomes, printed as the final summary.
+#[derive(Debug, Default)]
+struct Report {
+    passed: usize,
+    warnings: usize,
+    failed: usize,
+}
+
+impl Report {
+    fn pass(&mut self, message: impl AsRef<str>) {
+        self.passed += 1;
+        println!("  ✅ {}", message.as_ref());
+    }
+
+    fn warn(&mut self, message: impl AsRef<str>, fix: impl AsRef<str>) {
+        self.warnings += 1;
+        println!("  ⚠️  {}", message.as_ref());
+        println!("     fix: {}", fix.as_ref());
+    }
+
+    fn fail(&mut self, message: impl AsRef<str>, fix: impl AsRef<str>) {
+        self.failed += 1;
+        println!("  ❌ {}", message.as_ref());
+        println!("     fix: {}", fix.as_ref());
+    }
+
+    /// Print the summary; any failed check makes the command fail.
+    fn finish(self) -> Result<()> {
+        println!(
+            "\nSummary: {} passed, {} warning(s), {} failed",
+            self.passed, self.warnings, self.failed
+        );
+        if self.failed > 0 {
+            Err(Error::DoctorFailed {
+                failed: self.failed,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Validate the configuration, check that the bucket is reachable, and report what the
+/// backend supports. Later stages are skipped once an earlier one fails.
+pub async fn run(region_auto: bool) -> Result<()> {
+    let mut report = Report::default();
+
+    println!("Configuration");
+    let issues = diagnose_config();
+    for issue in &issues {
+        if issue.fatal {
+            report.fail(&issue.problem, &issue.fix);
+        } else {
+            report.warn(&issue.problem, &issue.fix);
+        }
+    }
+    if report.failed > 0 {
+        return report.finish();
+    }
+    let mut config = match load_storage_config() {
+        Ok(config) => config,
+        Err(err) => {
+            report.fail(err.to_string(), "set the variable named above");
+            return report.finish();
+        }
+    };
+    config.region_auto = region_auto;
+    let provider = format!("{:?}", config.provider).to_lowercase();
+    report.pass(format!(
+        "provider {provider}, bucket {}, endpoint {}, region {}",
+        config.bucket,
+        config.endpoint.as_deref().unwrap_or("(default)"),
+        config.region.as_deref().unwrap_or("(unset)")
+    ));
+
+    println!("\nConnectivity");
+    let client = match StorageClient::new(config).await {
+        Ok(client) => client,
+        Err(err) => {
+            report.fail(
+                format!("could not build a client: {err}"),
+                "check the endpoint URL and provider settings",
+            );
+            return report.finish();
+        }
+    };
+    match client.operator().check().await {
+        Ok(()) => report.pass("bucket is reachable with the configured credentials"),
+        Err(err) => {
+            let fix = match err.kind() {
+                ErrorKind::PermissionDenied => {
+                    "check the access key, secret, and that the region matches the bucket"
+                }
+                ErrorKind::NotFound => "check the bucket name",
+                _ => "check that the endpoint is reachable from this machine",
+            };
+            report.fail(client.explain_error(err.into()).to_string(), fix);
+            return report.finish();
+        }
+    }
+
+    println!("\nCapabilities");
+    let capability = client.operator().info().full_capability();
+    for (name, supported) in [
+        ("read", capability.read),
+        ("write", capability.write),
+        ("list", capability.list),
+        ("delete", capability.delete),
+        ("server-side copy", capability.copy),
+        ("multipart write", capability.write_can_multi),
+        ("versions", capability.read_with_version),
+        (
+            "content-disposition",
+            capability.write_with_content_disposition,
+        ),
+        ("cache-control", capability.write_with_cache_control),
+    ] {
+        println!("  {name:<20} {}", if supported { "yes" } else { "no" });
+    }
+
+    report.finish()
+}
@@ -9,6 +9,9 @@ pub enum Error {
     #[snafu(display("Environment variable '{key}' is required but not found"))]
     MissingEnvVar { key: String },
 
+    #[snafu(display("Credential process '{command}' failed: {reason}"))]
+    CredentialProcess { command: String, reason: String },
+
     #[snafu(display(
         "Unsupported storage provider: {provider}. Allowed: 'oss' | 's3' | 'minio' | 'fs'"
     ))]
@@ -17,9 +20,27 @@ pub enum Error {
     #[snafu(display("Path does not exist: {}", path.display()))]
     PathNotFound { path: PathBuf },
 
+    #[snafu(display("Invalid endpoint '{endpoint}': {reason}"))]
+    InvalidEndpoint { endpoint: String, reason: String },
+
+    #[snafu(display("Invalid proxy '{proxy}': {source}"))]
+    InvalidProxy {
+        proxy: String,
+        source: reqwest::Error,
+    },
+
+    #[snafu(display("Failed to build HTTP client: {source}"))]
+    HttpClient { source: reqwest::Error },
+
+    #[snafu(display("--content-type only applies with --metadata-directive replace"))]
+    ContentTypeWithoutReplace,
+
     #[snafu(display("Invalid path: {path}"))]
     InvalidPath { path: String },
 
+    #[snafu(display("{path} is a directory, not a file"))]
+    NotAFile { path: String },
+
     #[snafu(display("Cannot delete directory without -R flag: {path}"))]
     DirectoryDeletionNotRecursive { path: String },
 
@@ -57,6 +78,13 @@ pub enum Error {
         source: Box<Error>,
     },
 
+    #[snafu(display("Failed to move '{src_path}' to '{dest_path}': {source}"))]
+    MoveFailed {
+        src_path: String,
+        dest_path: String,
+        source: Box<Error>,
+    },
+
     #[snafu(display("Failed to list directory '{path}': {source}"))]
     ListDirectoryFailed { path: String, source: Box<Error> },
 
@@ -66,6 +94,67 @@ pub enum Error {
     #[snafu(display("Failed to create directory '{path}': {source}"))]
     DirectoryCreationFailed { path: String, source: Box<Error> },
 
+    #[snafu(display("Invalid pattern '{pattern}': {source}"))]
+    InvalidPattern {
+        pattern: String,
+        source: regex::Error,
+    },
+
+    #[snafu(display("Invalid output template: {reason}"))]
+    InvalidTemplate { reason: String },
+
+    #[snafu(display("Object is archived; restore it first: {path}"))]
+    ObjectArchived { path: String },
+
+    #[snafu(display("Invalid query '{query}': {reason}"))]
+    InvalidQuery { query: String, reason: String },
+
+    #[snafu(display("Select only filters CSV objects client-side; not supported for: {path}"))]
+    SelectUnsupported { path: String },
+
+    #[snafu(display("Object versioning is not supported for provider: {provider}"))]
+    VersioningUnsupported { provider: String },
+
+    #[snafu(display("Checksum mismatch for '{path}': expected {expected}, got {actual}"))]
+    ChecksumMismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[snafu(display(
+        "Key is not valid UTF-8 and was decoded lossily, so it cannot be addressed exactly: {key}"
+    ))]
+    LossyKey { key: String },
+
+    #[snafu(display("'{path}' changed while it was being uploaded; the upload was discarded"))]
+    LocalFileChanged { path: String },
+
+    #[snafu(display(
+        "Authentication failed — check region (got {region}) and credentials: {source}"
+    ))]
+    AuthenticationFailed { region: String, source: Box<Error> },
+
+    #[snafu(display(
+        "Refusing to {operation}: the client is read-only (--read-only / STORAGE_READ_ONLY)"
+    ))]
+    ReadOnly { operation: String },
+
+    #[snafu(display("{failed} of {total} object(s) failed to transfer"))]
+    PartialFailure { failed: usize, total: usize },
+
+    #[snafu(display("doctor found {failed} problem(s)"))]
+    DoctorFailed { failed: usize },
+
+    #[snafu(display("Invalid limit: {reason}"))]
+    InvalidLimit { reason: String },
+
+    #[snafu(display("Invalid batch line: {reason}"))]
+    InvalidBatchLine { reason: String },
+
+    #[snafu(display("{failed} of {total} batch command(s) failed"))]
+    BatchFailed { failed: usize, total: usize },
+
     #[snafu(display("OpenDAL error: {source}"))]
     OpenDal { source: opendal::Error },
 
@@ -76,6 +165,95 @@ pub enum Error {
     Json { source: serde_json::Error },
 }
 
+impl Error {
+    /// The storify error wrapped by a `*Failed` variant, if any.
+    fn wrapped(&self) -> Option<&Error> {
+        match self {
+            Error::DeleteFailed { source, .. }
+            | Error::DownloadFailed { source, .. }
+            | Error::UploadFailed { source, .. }
+            | Error::CopyFailed { source, .. }
+            | Error::MoveFailed { source, .. }
+            | Error::ListDirectoryFailed { source, .. }
+            | Error::DiskUsageFailed { source, .. }
+            | Error::DirectoryCreationFailed { source, .. }
+            | Error::AuthenticationFailed { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+
+    /// This error followed by everything it wraps, outermost first.
+    fn chain(&self) -> impl Iterator<Item = &Error> {
+        std::iter::successors(Some(self), |err| err.wrapped())
+    }
+
+    /// Stable snake_case name of the root cause, e.g. `path_not_found`. Backend errors
+    /// report the OpenDAL error kind instead, e.g. `permission_denied`.
+    pub fn kind(&self) -> String {
+        let root = self.chain().last().unwrap_or(self);
+        let name = match root {
+            Error::OpenDal { source } => format!("{:?}", source.kind()),
+            // The derived Debug output starts with the variant name.
+            other => format!("{other:?}"),
+        };
+        let name: String = name
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        to_snake_case(&name)
+    }
+
+    /// The path the failure is about, preferring the one closest to the root cause.
+    pub fn path(&self) -> Option<String> {
+        let errors: Vec<&Error> = self.chain().collect();
+        errors.into_iter().rev().find_map(|err| match err {
+            Error::PathNotFound { path } => Some(path.display().to_string()),
+            Error::InvalidPath { path }
+            | Error::NotAFile { path }
+            | Error::DirectoryDeletionNotRecursive { path }
+            | Error::ListDirectoryFailed { path, .. }
+            | Error::DiskUsageFailed { path, .. }
+            | Error::DirectoryCreationFailed { path, .. }
+            | Error::ObjectArchived { path }
+            | Error::ChecksumMismatch { path, .. } => Some(path.clone()),
+            Error::DeleteFailed { paths, .. } => Some(paths.clone()),
+            Error::DownloadFailed { remote_path, .. } => Some(remote_path.clone()),
+            Error::UploadFailed { local_path, .. } => Some(local_path.clone()),
+            Error::CopyFailed { src_path, .. } | Error::MoveFailed { src_path, .. } => {
+                Some(src_path.clone())
+            }
+            _ => None,
+        })
+    }
+
+    /// Machine-readable form: `{"error": {"kind": ..., "message": ..., "path": ...}}`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": {
+                "kind": self.kind(),
+                "message": self.to_string(),
+                "path": self.path(),
+            }
+        })
+    }
+}
+
+/// `PathNotFound` → `path_not_found`.
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
 impl From<opendal::Error> for Error {
     fn from(error: opendal::Error) -> Self {
         Error::OpenDal { source: error }
@@ -93,3 +271,36 @@ impl From<serde_json::Error> for Error {
         Error::Json { source: error }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_and_path_follow_root_cause() {
+        let err = Error::DownloadFailed {
+            remote_path: "logs/a.txt".to_string(),
+            local_path: "/tmp/out".to_string(),
+            source: Box::new(Error::PathNotFound {
+                path: PathBuf::from("logs/a.txt"),
+            }),
+        };
+        assert_eq!(err.kind(), "path_not_found");
+        assert_eq!(err.path().as_deref(), Some("logs/a.txt"));
+
+        let json = err.to_json();
+        assert_eq!(json["error"]["kind"], "path_not_found");
+        assert_eq!(json["error"]["message"], err.to_string());
+
+        let backend = Error::from(opendal::Error::new(
+            opendal::ErrorKind::PermissionDenied,
+            "denied",
+        ));
+        assert_eq!(backend.kind(), "permission_denied");
+        assert_eq!(backend.path(), None);
+        assert_eq!(
+            Error::DirectoryUploadNotRecursive.kind(),
+            "directory_upload_not_recursive"
+        );
+    }
+}
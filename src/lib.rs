@@ -1,5 +1,7 @@
+pub mod batch;
 pub mod cli;
 pub mod config;
+pub mod doctor;
 pub mod error;
 pub mod storage;
 pub mod utils;
@@ -1,25 +1,60 @@
 use clap::Parser;
+use std::process::ExitCode;
 
 use storify::cli;
+use storify::doctor;
 use storify::error::Result;
 use storify::storage::StorageClient;
 
-use storify::cli::Args;
-use storify::config::load_storage_config;
+use storify::cli::{Args, Commands, ERROR_EXIT_STATUS, ErrorFormat, Outcome};
+use storify::config::{load_storage_config, set_credential_process};
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
     let args = Args::parse();
+    let error_format = args.error_format;
+    let json_style = args.json_style();
 
-    if let Err(e) = run_app(args).await {
-        eprintln!("Error: {e}");
-        std::process::exit(1);
+    match run_app(args).await {
+        Ok(outcome) => outcome.exit_code(),
+        Err(e) => {
+            match error_format {
+                ErrorFormat::Text => eprintln!("Error: {e}"),
+                ErrorFormat::Json => match json_style.render(&e.to_json()) {
+                    Ok(json) => eprintln!("{json}"),
+                    Err(_) => eprintln!("{}", e.to_json()),
+                },
+            }
+            ExitCode::from(ERROR_EXIT_STATUS)
+        }
     }
 }
 
-async fn run_app(args: Args) -> Result<()> {
-    let config = load_storage_config()?;
+async fn run_app(args: Args) -> Result<Outcome> {
+    if let Some(command) = &args.credential_process {
+        set_credential_process(command.clone());
+    }
+
+    // Runs before loading the config so it can report every problem with it.
+    if let Commands::Doctor = args.command {
+        doctor::run(args.region_auto).await?;
+        return Ok(Outcome::Success);
+    }
+    if let Commands::Config(_) = args.command {
+        cli::show_config(&args)?;
+        return Ok(Outcome::Success);
+    }
+
+    let mut config = load_storage_config()?;
+    config.region_auto = args.region_auto;
+    config.checksum_algorithm = args.upload_checksum_algorithm();
+    config.debug_log = args.debug_log.clone();
+    config.read_only = args.read_only;
+    config.max_concurrent_requests = args.max_concurrent_requests;
+    config.http = args.http_options();
     let client = StorageClient::new(config).await?;
-    cli::run(args, client).await?;
-    Ok(())
+    let explainer = client.clone();
+    cli::run(args, client)
+        .await
+        .map_err(|e| explainer.explain_error(e))
 }
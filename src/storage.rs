@@ -1,22 +1,72 @@
-use crate::error::{Error, Result};
-use opendal::Operator;
+use crate::config::{CredentialProcess, ProcessCredentials};
+use crate::error::{
+    Error, InvalidEndpointSnafu, ReadOnlySnafu, Result, VersioningUnsupportedSnafu,
+};
+use chrono::Utc;
+use http::{HeaderMap, StatusCode};
+use opendal::layers::{ConcurrentLimitLayer, HttpClientLayer, LoggingLayer};
+use opendal::raw::HttpClient;
+use opendal::{Buffer, Operator};
+use snafu::ensure;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::Semaphore;
 
 pub mod constants;
 mod operations;
 mod utils;
+pub use self::operations::copy::{CopyOptions, MetadataDirective, OverwritePolicy};
+pub use self::operations::download::DownloadOptions;
+pub use self::operations::find::FindOptions;
+pub use self::operations::grep::GrepOptions;
+pub use self::operations::head::PeekLimit;
+pub use self::operations::list::{LIST_TEMPLATE_FIELDS, ListFormat, ListOptions};
+pub use self::operations::select::SelectOptions;
+pub use self::operations::stat::STAT_TEMPLATE_FIELDS;
+pub use self::operations::upload::UploadOptions;
+pub use self::operations::usage::{UsageFormat, UsageOptions};
 pub use self::utils::OutputFormat;
+pub use self::utils::checksum::ChecksumAlgorithm;
+pub use self::utils::concurrency::{BreakerPolicy, Concurrency, HealthPolicy};
+pub use self::utils::content_type::ContentTypeDetection;
+pub(crate) use self::utils::endpoint::region_from_endpoint;
+pub use self::utils::failure::ErrorPolicy;
+#[cfg(test)]
+pub(crate) use self::utils::fixture;
+pub use self::utils::header::{validate_cache_control, validate_content_disposition};
+pub use self::utils::http::HttpOptions;
+pub use self::utils::json::JsonStyle;
+pub use self::utils::manifest::Manifest;
+pub use self::utils::path::UnicodeForm;
+pub use self::utils::progress::{ProgressFormat, ProgressOptions};
+pub use self::utils::size::{SizeRange, parse_size};
+pub use self::utils::sort::SortKey;
+pub use self::utils::stats::TransferStats;
+pub use self::utils::template::Template;
+pub use self::utils::time::{TimeRange, TimeStyle, parse_age, parse_datetime};
+pub use self::utils::transfer_log::TransferLog;
 
+use self::operations::cat::OpenDalCatter;
 use self::operations::copy::OpenDalCopier;
 use self::operations::delete::OpenDalDeleter;
 use self::operations::download::OpenDalDownloader;
+use self::operations::find::OpenDalFinder;
+use self::operations::grep::OpenDalGrepper;
+use self::operations::head::OpenDalPeeker;
 use self::operations::list::OpenDalLister;
 use self::operations::mkdir::OpenDalMkdirer;
+use self::operations::rename::OpenDalMover;
+use self::operations::select::{OpenDalSelector, SelectQuery};
 use self::operations::upload::OpenDalUploader;
 use self::operations::usage::OpenDalUsageCalculator;
 use self::operations::{
-    Copier, Deleter, Downloader, Lister, Mkdirer, Stater, Uploader, UsageCalculator,
+    Catter, Copier, Deleter, Downloader, Finder, Grepper, Lister, Mkdirer, Mover, Peeker, Selector,
+    Stater, Uploader, UsageCalculator,
 };
+use self::utils::debug_log::DebugLogInterceptor;
+use self::utils::endpoint::Endpoint;
 use crate::wrap_err;
 
 /// Storage provider types
@@ -24,6 +74,8 @@ use crate::wrap_err;
 pub enum StorageProvider {
     Oss,
     S3,
+    Gcs,
+    Azblob,
     Fs,
     Hdfs,
 }
@@ -35,6 +87,8 @@ impl FromStr for StorageProvider {
         match s.to_lowercase().as_str() {
             "oss" => Ok(Self::Oss),
             "s3" | "minio" => Ok(Self::S3),
+            "gcs" | "google" => Ok(Self::Gcs),
+            "azblob" | "azure" => Ok(Self::Azblob),
             "fs" => Ok(Self::Fs),
             "hdfs" => Ok(Self::Hdfs),
             _ => Err(Error::UnsupportedProvider {
@@ -51,10 +105,28 @@ pub struct StorageConfig {
     pub bucket: String,
     pub access_key_id: Option<String>,
     pub access_key_secret: Option<String>,
+    /// Temporary session token issued with the keys (S3 only)
+    pub session_token: Option<String>,
+    /// Command the keys came from; clients run it again when the keys near expiry
+    pub credential_process: Option<&'static CredentialProcess>,
     pub endpoint: Option<String>,
     pub region: Option<String>,
     pub root_path: Option<String>,
     pub name_node: Option<String>,
+    /// Service-account key file (GCS only); the application default credentials otherwise
+    pub credential_path: Option<String>,
+    /// Probe the endpoint for the bucket region when none is configured
+    pub region_auto: bool,
+    /// Checksum the backend should validate on writes, when it supports one
+    pub checksum_algorithm: Option<ChecksumAlgorithm>,
+    /// Append a redacted JSON-lines trace of every backend operation to this file
+    pub debug_log: Option<PathBuf>,
+    /// Reject every operation that would modify storage
+    pub read_only: bool,
+    /// Cap on backend requests in flight at once, shared by every operation of the client
+    pub max_concurrent_requests: Option<NonZeroUsize>,
+    /// Proxy and redirect settings for HTTP backends
+    pub http: HttpOptions,
 }
 
 impl StorageConfig {
@@ -69,10 +141,19 @@ impl StorageConfig {
             bucket,
             access_key_id: Some(access_key_id),
             access_key_secret: Some(access_key_secret),
+            session_token: None,
+            credential_process: None,
             endpoint: None,
             region,
             root_path: None,
             name_node: None,
+            credential_path: None,
+            region_auto: false,
+            checksum_algorithm: None,
+            debug_log: None,
+            read_only: false,
+            max_concurrent_requests: None,
+            http: HttpOptions::default(),
         }
     }
 
@@ -87,10 +168,65 @@ impl StorageConfig {
             bucket,
             access_key_id: Some(access_key_id),
             access_key_secret: Some(secret_access_key),
+            session_token: None,
+            credential_process: None,
             endpoint: None,
             region,
             root_path: None,
             name_node: None,
+            credential_path: None,
+            region_auto: false,
+            checksum_algorithm: None,
+            debug_log: None,
+            read_only: false,
+            max_concurrent_requests: None,
+            http: HttpOptions::default(),
+        }
+    }
+
+    pub fn gcs(bucket: String, credential_path: Option<String>) -> Self {
+        Self {
+            provider: StorageProvider::Gcs,
+            bucket,
+            access_key_id: None,
+            access_key_secret: None,
+            session_token: None,
+            credential_process: None,
+            endpoint: None,
+            region: None,
+            root_path: None,
+            name_node: None,
+            credential_path,
+            region_auto: false,
+            checksum_algorithm: None,
+            debug_log: None,
+            read_only: false,
+            max_concurrent_requests: None,
+            http: HttpOptions::default(),
+        }
+    }
+
+    /// Azure Blob Storage, authenticated with a storage account's shared key. The
+    /// container is kept in `bucket`, the account name and key in the access key fields.
+    pub fn azblob(container: String, account_name: String, account_key: String) -> Self {
+        Self {
+            provider: StorageProvider::Azblob,
+            bucket: container,
+            access_key_id: Some(account_name),
+            access_key_secret: Some(account_key),
+            session_token: None,
+            credential_process: None,
+            endpoint: None,
+            region: None,
+            root_path: None,
+            name_node: None,
+            credential_path: None,
+            region_auto: false,
+            checksum_algorithm: None,
+            debug_log: None,
+            read_only: false,
+            max_concurrent_requests: None,
+            http: HttpOptions::default(),
         }
     }
 
@@ -100,10 +236,19 @@ impl StorageConfig {
             bucket: "local".to_string(),
             access_key_id: None,
             access_key_secret: None,
+            session_token: None,
+            credential_process: None,
             endpoint: None,
             region: None,
             root_path: Some(root_path),
             name_node: None,
+            credential_path: None,
+            region_auto: false,
+            checksum_algorithm: None,
+            debug_log: None,
+            read_only: false,
+            max_concurrent_requests: None,
+            http: HttpOptions::default(),
         }
     }
 
@@ -113,39 +258,335 @@ impl StorageConfig {
             bucket: "hdfs".to_string(), // Bucket is not really used for HDFS
             access_key_id: None,
             access_key_secret: None,
+            session_token: None,
+            credential_process: None,
             endpoint: None,
             region: None,
             root_path: Some(root_path),
             name_node: Some(name_node),
+            credential_path: None,
+            region_auto: false,
+            checksum_algorithm: None,
+            debug_log: None,
+            read_only: false,
+            max_concurrent_requests: None,
+            http: HttpOptions::default(),
         }
     }
+
+    /// Warn that a session token is ignored by a provider that cannot use one. Called once
+    /// when the config is loaded, not on every operator rebuild.
+    pub(crate) fn warn_unused_session_token(&self) {
+        if self.session_token.is_none() {
+            return;
+        }
+        match self.provider {
+            StorageProvider::Oss => {
+                eprintln!("Warning: OSS does not accept a session token here; using the keys alone")
+            }
+            StorageProvider::Azblob => {
+                eprintln!("Warning: Azure Blob does not use a session token; using the account key")
+            }
+            _ => {}
+        }
+    }
+
+    /// Use keys returned by a credential process in place of the configured ones.
+    pub(crate) fn set_credentials(&mut self, credentials: &ProcessCredentials) {
+        self.access_key_id = Some(credentials.access_key_id.clone());
+        self.access_key_secret = Some(credentials.secret_access_key.clone());
+        self.session_token = credentials.session_token.clone();
+    }
+
+    /// Identify the backend this config points at, so two clients can tell whether they
+    /// share storage (and therefore whether server-side operations are possible).
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "{:?}|{}|{}|{}|{}",
+            self.provider,
+            self.endpoint.as_deref().unwrap_or_default(),
+            self.region.as_deref().unwrap_or_default(),
+            self.bucket,
+            self.root_path.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// The requested checksum algorithm if this backend validates it server-side.
+    pub fn server_checksum(&self) -> Option<ChecksumAlgorithm> {
+        self.checksum_algorithm.filter(|algorithm| {
+            matches!(self.provider, StorageProvider::S3) && algorithm.s3_name().is_some()
+        })
+    }
+
+    /// The Azure Blob endpoint: the configured one, or the account's public endpoint.
+    fn azblob_endpoint(&self) -> Result<String> {
+        match &self.endpoint {
+            Some(endpoint) => Ok(Endpoint::parse(endpoint)?.to_url()),
+            None => Ok(format!(
+                "https://{}.blob.core.windows.net",
+                self.access_key_id.as_deref().unwrap_or_default()
+            )),
+        }
+    }
+
+    /// Whether the region is unknown for an S3-compatible custom endpoint and may be probed.
+    pub fn needs_region_probe(&self) -> bool {
+        matches!(self.provider, StorageProvider::S3)
+            && self.region.as_deref().is_none_or(|r| r.trim().is_empty())
+            && self.endpoint.is_some()
+    }
+}
+
+/// Keys from a credential process and the config to rebuild a client's operator with
+/// once they near expiry.
+struct CredentialRefresh {
+    process: &'static CredentialProcess,
+    config: StorageConfig,
+    credentials: tokio::sync::Mutex<ProcessCredentials>,
+}
+
+/// Credentials from `process`, which may run its command, without blocking the runtime.
+async fn run_credential_process(process: &'static CredentialProcess) -> Result<ProcessCredentials> {
+    tokio::task::spawn_blocking(move || process.credentials())
+        .await
+        .expect("credential process task panicked")
 }
 
 /// Unified storage client using OpenDAL
 #[derive(Clone)]
 pub struct StorageClient {
-    operator: Operator,
+    /// Replaced with one built from fresh keys when credential-process keys near expiry;
+    /// clones of a client share it.
+    operator: Arc<Mutex<Operator>>,
+    credentials: Option<Arc<CredentialRefresh>>,
     provider: StorageProvider,
+    fingerprint: String,
+    progress: ProgressOptions,
+    server_checksum: Option<ChecksumAlgorithm>,
+    region: Option<String>,
+    read_only: bool,
+    region_auto: bool,
+    debug_log: Option<PathBuf>,
+    request_limit: Option<ConcurrentLimitLayer>,
+    http: HttpOptions,
 }
 
 impl StorageClient {
-    pub async fn new(config: StorageConfig) -> Result<Self> {
-        let operator = Self::build_operator(&config)?;
+    pub async fn new(mut config: StorageConfig) -> Result<Self> {
+        // An explicit region wins; a provider endpoint that names its region comes next.
+        if config.region.as_deref().is_none_or(|r| r.trim().is_empty())
+            && let Some(region) = config.endpoint.as_deref().and_then(region_from_endpoint)
+        {
+            log::debug!("using region {region} from endpoint");
+            config.region = Some(region);
+        }
+        if config.region_auto && config.needs_region_probe() {
+            config.region = Self::probe_region(&config).await;
+        }
+        let credentials = match config.credential_process {
+            Some(process) => {
+                let credentials = run_credential_process(process).await?;
+                config.set_credentials(&credentials);
+                Some(Arc::new(CredentialRefresh {
+                    process,
+                    config: config.clone(),
+                    credentials: tokio::sync::Mutex::new(credentials),
+                }))
+            }
+            None => None,
+        };
+        let request_limit = config.max_concurrent_requests.map(|limit| {
+            // Only HTTP requests are counted: readers and writers hold operation permits for
+            // their whole lifetime, so an operation-level cap could deadlock a streaming copy.
+            ConcurrentLimitLayer::new(Semaphore::MAX_PERMITS)
+                .with_http_concurrent_limit(limit.get())
+        });
+        let operator = Self::layered_operator(&config, request_limit.as_ref())?;
         Ok(Self {
-            operator,
+            operator: Arc::new(Mutex::new(operator)),
+            credentials,
             provider: config.provider,
+            fingerprint: config.fingerprint(),
+            progress: ProgressOptions::default(),
+            server_checksum: config.server_checksum(),
+            region: config.region,
+            read_only: config.read_only,
+            region_auto: config.region_auto,
+            debug_log: config.debug_log,
+            request_limit,
+            http: config.http,
         })
     }
 
+    /// The backend operator with the debug log and request limit layered on.
+    fn layered_operator(
+        config: &StorageConfig,
+        request_limit: Option<&ConcurrentLimitLayer>,
+    ) -> Result<Operator> {
+        let mut operator = Self::build_operator(config)?;
+        if let Some(path) = &config.debug_log {
+            let secrets = [
+                &config.access_key_id,
+                &config.access_key_secret,
+                &config.session_token,
+            ]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+            let interceptor = DebugLogInterceptor::open(path, secrets)?;
+            operator = operator.layer(LoggingLayer::new(interceptor));
+        }
+        if let Some(layer) = request_limit {
+            operator = operator.layer(layer.clone());
+        }
+        Ok(operator)
+    }
+
+    /// The operator for a new operation. When the credential process's keys expire soon, it
+    /// is run again and the operator rebuilt with the new keys first, so a long-lived client
+    /// (a batch script, say) does not go on signing with expired ones. Operations starting
+    /// during a refresh wait for it rather than run the command again.
+    async fn fresh_operator(&self) -> Result<Operator> {
+        if let Some(refresh) = &self.credentials {
+            let mut credentials = refresh.credentials.lock().await;
+            if credentials.expires_soon(Utc::now()) {
+                let renewed = run_credential_process(refresh.process).await?;
+                let mut config = refresh.config.clone();
+                config.set_credentials(&renewed);
+                let operator = Self::layered_operator(&config, self.request_limit.as_ref())?;
+                log::debug!("rebuilt operator with refreshed credentials");
+                *self.operator.lock().unwrap_or_else(PoisonError::into_inner) = operator;
+                *credentials = renewed;
+            }
+        }
+        Ok(self.operator())
+    }
+
+    /// Route this client's requests through `other`'s request limit, so clients opened for
+    /// URL locations draw from the same budget as the default one.
+    pub fn with_request_limit_of(mut self, other: &StorageClient) -> Self {
+        if self.request_limit.is_none()
+            && let Some(layer) = &other.request_limit
+        {
+            self.operator = Arc::new(Mutex::new(self.operator().layer(layer.clone())));
+            self.request_limit = Some(layer.clone());
+        }
+        self
+    }
+
+    /// Replace the progress settings used by transfer operations.
+    pub fn with_progress(mut self, progress: ProgressOptions) -> Self {
+        self.progress = progress;
+        self
+    }
+
     pub fn provider(&self) -> StorageProvider {
         self.provider
     }
 
-    pub fn operator(&self) -> &Operator {
-        &self.operator
+    /// The operator as currently built, without refreshing its credentials first.
+    pub fn operator(&self) -> Operator {
+        self.operator
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn progress(&self) -> &ProgressOptions {
+        &self.progress
+    }
+
+    /// Region requests are signed for, after any auto-detection.
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Proxy and redirect settings, shared with clients opened for URL locations.
+    pub fn http_options(&self) -> &HttpOptions {
+        &self.http
+    }
+
+    /// Whether the bucket region is probed when none is configured.
+    pub fn is_region_auto(&self) -> bool {
+        self.region_auto
+    }
+
+    /// File backend operations are traced to, shared with clients opened for URL locations.
+    pub fn debug_log(&self) -> Option<&Path> {
+        self.debug_log.as_deref()
+    }
+
+    /// Whether this client rejects operations that modify storage.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Fail before `operation` starts when the client is read-only.
+    pub fn ensure_writable(&self, operation: &str) -> Result<()> {
+        ensure!(
+            !self.read_only,
+            ReadOnlySnafu {
+                operation: operation.to_string()
+            }
+        );
+        Ok(())
+    }
+
+    /// Rewrite signature/region mismatch failures into an actionable authentication error.
+    pub fn explain_error(&self, err: Error) -> Error {
+        utils::error::map_region_auth_error(err, self.region())
+    }
+
+    /// Fail early when a version-aware request targets a backend without versioning support.
+    fn ensure_versioning(&self, supported: bool) -> Result<()> {
+        ensure!(
+            supported,
+            VersioningUnsupportedSnafu {
+                provider: format!("{:?}", self.provider).to_lowercase()
+            }
+        );
+        Ok(())
+    }
+
+    /// Ask the endpoint which region the bucket lives in via the `x-amz-bucket-region`
+    /// header of a bucket HEAD request, sent with the same proxy and redirect settings as
+    /// the backend. The result is kept in the config for the session.
+    async fn probe_region(config: &StorageConfig) -> Option<String> {
+        let endpoint = config.endpoint.as_deref()?;
+        let url = format!("{}/{}", endpoint.trim_end_matches('/'), config.bucket);
+        let request = http::Request::head(&url).body(Buffer::new()).ok()?;
+        // An invalid proxy is reported when the backend is built; the probe just gives up.
+        let client = match config.http.http_client().ok()? {
+            Some(client) => client,
+            None => HttpClient::new().ok()?,
+        };
+        let region = match client.send(request).await {
+            Ok(response) => region_from_response(response.status(), response.headers()),
+            Err(err) => {
+                log::debug!("bucket HEAD {url} failed: {err}");
+                None
+            }
+        };
+        match &region {
+            Some(region) => log::info!("detected region {region} for bucket {}", config.bucket),
+            None => log::warn!(
+                "could not detect region for bucket {} at {endpoint}",
+                config.bucket
+            ),
+        }
+        region
     }
 
     fn build_operator(config: &StorageConfig) -> Result<Operator> {
+        let operator = Self::build_backend(config)?;
+        Ok(match config.http.http_client()? {
+            Some(client) => operator.layer(HttpClientLayer::new(client)),
+            None => operator,
+        })
+    }
+
+    fn build_backend(config: &StorageConfig) -> Result<Operator> {
         match &config.provider {
             StorageProvider::Oss => {
                 let mut builder = opendal::services::Oss::default().bucket(&config.bucket);
@@ -156,7 +597,16 @@ impl StorageClient {
                     builder = builder.access_key_secret(access_key_secret);
                 }
                 if let Some(endpoint) = &config.endpoint {
-                    builder = builder.endpoint(endpoint);
+                    let endpoint = Endpoint::parse(endpoint)?;
+                    // OSS addresses buckets as `<bucket>.<host>`, which has no room for a path.
+                    ensure!(
+                        endpoint.base_path.is_empty(),
+                        InvalidEndpointSnafu {
+                            endpoint: endpoint.to_url(),
+                            reason: "OSS endpoints cannot include a base path",
+                        }
+                    );
+                    builder = builder.endpoint(&endpoint.to_url());
                 }
                 Ok(Operator::new(builder)?.finish())
             }
@@ -168,11 +618,42 @@ impl StorageClient {
                 if let Some(secret_access_key) = &config.access_key_secret {
                     builder = builder.secret_access_key(secret_access_key);
                 }
+                if let Some(session_token) = &config.session_token {
+                    builder = builder.session_token(session_token);
+                }
                 if let Some(region) = &config.region {
                     builder = builder.region(region);
                 }
                 if let Some(endpoint) = &config.endpoint {
-                    builder = builder.endpoint(endpoint);
+                    // Path-style requests go to `<endpoint>/<bucket>/<key>`, keeping any base path.
+                    builder = builder.endpoint(&Endpoint::parse(endpoint)?.to_url());
+                }
+                if let Some(algorithm) = config.server_checksum().and_then(|a| a.s3_name()) {
+                    builder = builder.checksum_algorithm(algorithm);
+                }
+                // Only advertises version-aware reads/listings; plain requests are unchanged.
+                builder = builder.enable_versioning(true);
+                Ok(Operator::new(builder)?.finish())
+            }
+            StorageProvider::Gcs => {
+                let mut builder = opendal::services::Gcs::default().bucket(&config.bucket);
+                if let Some(credential_path) = &config.credential_path {
+                    builder = builder.credential_path(credential_path);
+                }
+                if let Some(endpoint) = &config.endpoint {
+                    builder = builder.endpoint(&Endpoint::parse(endpoint)?.to_url());
+                }
+                Ok(Operator::new(builder)?.finish())
+            }
+            StorageProvider::Azblob => {
+                let mut builder = opendal::services::Azblob::default()
+                    .container(&config.bucket)
+                    .endpoint(&config.azblob_endpoint()?);
+                if let Some(account_name) = &config.access_key_id {
+                    builder = builder.account_name(account_name);
+                }
+                if let Some(account_key) = &config.access_key_secret {
+                    builder = builder.account_key(account_key);
                 }
                 Ok(Operator::new(builder)?.finish())
             }
@@ -202,33 +683,142 @@ impl StorageClient {
         }
     }
 
-    pub async fn list_directory(&self, path: &str, long: bool, recursive: bool) -> Result<()> {
+    pub async fn list_directory(&self, path: &str, options: &ListOptions) -> Result<()> {
         log::debug!(
-            "list_directory provider={:?} path={} long={} recursive={}",
+            "list_directory provider={:?} path={} options={:?}",
             self.provider,
             path,
-            long,
-            recursive
+            options
+        );
+        if options.versions {
+            self.ensure_versioning(self.operator().info().full_capability().list_with_versions)?;
+        }
+        let lister = OpenDalLister::new(self.fresh_operator().await?);
+        wrap_err!(
+            lister.list(path, options).await,
+            ListDirectoryFailed {
+                path: path.to_string()
+            }
+        )
+    }
+
+    pub async fn find_objects(&self, path: &str, options: &FindOptions) -> Result<()> {
+        log::debug!(
+            "find_objects provider={:?} path={} options={:?}",
+            self.provider,
+            path,
+            options
+        );
+        let finder = OpenDalFinder::new(self.fresh_operator().await?);
+        wrap_err!(
+            finder.find(path, options).await,
+            ListDirectoryFailed {
+                path: path.to_string()
+            }
+        )
+    }
+
+    /// Print the rows of a CSV object that satisfy `query`; returns how many were printed.
+    pub async fn select_object(
+        &self,
+        path: &str,
+        query: &str,
+        options: &SelectOptions,
+    ) -> Result<u64> {
+        log::debug!(
+            "select_object provider={:?} path={} query={} options={:?}",
+            self.provider,
+            path,
+            query,
+            options
+        );
+        let query = SelectQuery::parse(query)?;
+        let selector = OpenDalSelector::new(self.fresh_operator().await?);
+        selector.select(path, &query, options).await
+    }
+
+    pub async fn cat_file(&self, remote_path: &str) -> Result<()> {
+        log::debug!(
+            "cat_file provider={:?} remote_path={}",
+            self.provider,
+            remote_path
         );
-        let lister = OpenDalLister::new(self.operator.clone());
+        let catter = OpenDalCatter::new(self.fresh_operator().await?);
+        catter.cat(remote_path).await?;
+        Ok(())
+    }
+
+    pub async fn head(&self, path: &str, bytes: Option<u64>, lines: Option<usize>) -> Result<()> {
+        log::debug!(
+            "head provider={:?} path={} bytes={:?} lines={:?}",
+            self.provider,
+            path,
+            bytes,
+            lines
+        );
+        let limit = PeekLimit::new(bytes, lines)?;
+        let peeker = OpenDalPeeker::new(self.fresh_operator().await?);
+        peeker.head(path, limit).await?;
+        Ok(())
+    }
+
+    pub async fn tail(&self, path: &str, bytes: Option<u64>, lines: Option<usize>) -> Result<()> {
+        log::debug!(
+            "tail provider={:?} path={} bytes={:?} lines={:?}",
+            self.provider,
+            path,
+            bytes,
+            lines
+        );
+        let limit = PeekLimit::new(bytes, lines)?;
+        let peeker = OpenDalPeeker::new(self.fresh_operator().await?);
+        peeker.tail(path, limit).await?;
+        Ok(())
+    }
+
+    /// Print lines matching `pattern` in objects under `path`; returns whether any matched.
+    pub async fn grep_objects(
+        &self,
+        path: &str,
+        pattern: &str,
+        options: &GrepOptions,
+    ) -> Result<bool> {
+        log::debug!(
+            "grep_objects provider={:?} path={} pattern={} options={:?}",
+            self.provider,
+            path,
+            pattern,
+            options
+        );
+        let regex = options.regex(pattern)?;
+        let grepper = OpenDalGrepper::new(self.fresh_operator().await?);
         wrap_err!(
-            lister.list(path, long, recursive).await,
+            grepper.grep(path, &regex, options).await,
             ListDirectoryFailed {
                 path: path.to_string()
             }
         )
     }
 
-    pub async fn download_files(&self, remote_path: &str, local_path: &str) -> Result<()> {
+    pub async fn download_files(
+        &self,
+        remote_path: &str,
+        local_path: &str,
+        options: &DownloadOptions,
+    ) -> Result<TransferStats> {
         log::debug!(
-            "download_files provider={:?} remote_path={} local_path={}",
+            "download_files provider={:?} remote_path={} local_path={} options={:?}",
             self.provider,
             remote_path,
-            local_path
+            local_path,
+            options
         );
-        let downloader = OpenDalDownloader::new(self.operator.clone());
+        if options.version_id.is_some() {
+            self.ensure_versioning(self.operator().info().full_capability().read_with_version)?;
+        }
+        let downloader = OpenDalDownloader::new(self.fresh_operator().await?);
         wrap_err!(
-            downloader.download(remote_path, local_path).await,
+            downloader.download(remote_path, local_path, options).await,
             DownloadFailed {
                 remote_path: remote_path.to_string(),
                 local_path: local_path.to_string()
@@ -236,16 +826,16 @@ impl StorageClient {
         )
     }
 
-    pub async fn disk_usage(&self, path: &str, summary: bool) -> Result<()> {
+    pub async fn disk_usage(&self, path: &str, options: &UsageOptions) -> Result<()> {
         log::debug!(
-            "disk_usage provider={:?} path={} summary={}",
+            "disk_usage provider={:?} path={} options={:?}",
             self.provider,
             path,
-            summary
+            options
         );
-        let calculator = OpenDalUsageCalculator::new(self.operator.clone());
+        let calculator = OpenDalUsageCalculator::new(self.fresh_operator().await?);
         wrap_err!(
-            calculator.calculate_usage(path, summary).await,
+            calculator.calculate_usage(path, options).await,
             DiskUsageFailed {
                 path: path.to_string()
             }
@@ -256,18 +846,41 @@ impl StorageClient {
         &self,
         local_path: &str,
         remote_path: &str,
-        is_recursive: bool,
-    ) -> Result<()> {
+        options: &UploadOptions,
+    ) -> Result<TransferStats> {
         log::debug!(
-            "upload_files provider={:?} local_path={} remote_path={} recursive={}",
+            "upload_files provider={:?} local_path={} remote_path={} options={:?}",
             self.provider,
             local_path,
             remote_path,
-            is_recursive
+            options
         );
-        let uploader = OpenDalUploader::new(self.operator.clone());
+        self.ensure_writable("upload")?;
+        let server_checksum = options.checksum_algorithm.is_some()
+            && options.checksum_algorithm == self.server_checksum;
+        if let Some(algorithm) = options.checksum_algorithm.filter(|_| !server_checksum) {
+            eprintln!(
+                "Warning: {:?} does not validate {algorithm:?} checksums on upload; verifying by reading objects back",
+                self.provider
+            );
+        }
+        let capability = self.operator().info().full_capability();
+        if options.content_disposition.is_some() && !capability.write_with_content_disposition {
+            eprintln!(
+                "Warning: {:?} does not store Content-Disposition; the header will be ignored",
+                self.provider
+            );
+        }
+        if options.cache_control.is_some() && !capability.write_with_cache_control {
+            eprintln!(
+                "Warning: {:?} does not store Cache-Control; the header will be ignored",
+                self.provider
+            );
+        }
+        let uploader = OpenDalUploader::new(self.fresh_operator().await?, self.progress.clone())
+            .with_server_checksum(server_checksum);
         wrap_err!(
-            uploader.upload(local_path, remote_path, is_recursive).await,
+            uploader.upload(local_path, remote_path, options).await,
             UploadFailed {
                 local_path: local_path.to_string(),
                 remote_path: remote_path.to_string()
@@ -282,7 +895,8 @@ impl StorageClient {
             paths.len(),
             recursive
         );
-        let deleter = OpenDalDeleter::new(self.operator.clone());
+        self.ensure_writable("delete")?;
+        let deleter = OpenDalDeleter::new(self.fresh_operator().await?);
         wrap_err!(
             deleter.delete(paths, recursive).await,
             DeleteFailed {
@@ -293,14 +907,64 @@ impl StorageClient {
         )
     }
 
-    pub async fn copy_files(&self, src_path: &str, dest_path: &str) -> Result<()> {
+    pub async fn copy_files(
+        &self,
+        src_path: &str,
+        dest_path: &str,
+        options: &CopyOptions,
+    ) -> Result<()> {
+        self.copy_files_to(self, src_path, dest_path, options).await
+    }
+
+    /// Whether a native server-side copy can be used between this client and `dest`.
+    fn can_copy_server_side(&self, dest: &StorageClient, options: &CopyOptions) -> bool {
+        options.allows_server_side()
+            && self.fingerprint == dest.fingerprint
+            && self.operator().info().full_capability().copy
+    }
+
+    /// Copy files from this client's storage into another client's storage, which may be a
+    /// different bucket or provider. Data is streamed from source to destination.
+    pub async fn copy_files_to(
+        &self,
+        dest: &StorageClient,
+        src_path: &str,
+        dest_path: &str,
+        options: &CopyOptions,
+    ) -> Result<()> {
+        let server_side = self.can_copy_server_side(dest, options);
         log::debug!(
-            "copy_files provider={:?} src_path={} dest_path={}",
+            "copy_files src_provider={:?} dest_provider={:?} src_path={} dest_path={} server_side={}",
             self.provider,
+            dest.provider,
             src_path,
-            dest_path
+            dest_path,
+            server_side
         );
-        let copier = OpenDalCopier::new(self.operator.clone());
+        dest.ensure_writable("copy")?;
+        if options.preserve_metadata
+            && !dest
+                .operator()
+                .info()
+                .full_capability()
+                .write_with_user_metadata
+        {
+            eprintln!(
+                "Warning: {:?} does not store user metadata; only headers will be preserved",
+                dest.provider
+            );
+        }
+        let copier = OpenDalCopier::new(
+            self.fresh_operator().await?,
+            dest.fresh_operator().await?,
+            self.progress.clone(),
+        )
+        .with_server_side(server_side)
+        .with_same_backend(self.fingerprint == dest.fingerprint)
+        .with_preserve_metadata(options.preserve_metadata)
+        .with_on_error(options.on_error)
+        .with_metadata_directive(options)
+        .with_overwrite_policy(options.overwrite_policy);
         wrap_err!(
             copier.copy(src_path, dest_path).await,
             CopyFailed {
@@ -310,6 +974,24 @@ impl StorageClient {
         )
     }
 
+    pub async fn move_object(&self, src: &str, dst: &str) -> Result<()> {
+        log::debug!(
+            "move_object provider={:?} src={} dst={}",
+            self.provider,
+            src,
+            dst
+        );
+        self.ensure_writable("move")?;
+        let mover = OpenDalMover::new(self.fresh_operator().await?);
+        wrap_err!(
+            mover.move_path(src, dst).await,
+            MoveFailed {
+                src_path: src.to_string(),
+                dest_path: dst.to_string()
+            }
+        )
+    }
+
     pub async fn create_directory(&self, path: &str, parents: bool) -> Result<()> {
         log::debug!(
             "create_directory provider={:?} path={} parents={}",
@@ -317,7 +999,8 @@ impl StorageClient {
             path,
             parents
         );
-        let mkdirer = OpenDalMkdirer::new(self.operator.clone());
+        self.ensure_writable("create directories")?;
+        let mkdirer = OpenDalMkdirer::new(self.fresh_operator().await?);
         wrap_err!(
             mkdirer.mkdir(path, parents).await,
             DirectoryCreationFailed {
@@ -333,7 +1016,7 @@ impl StorageClient {
             path,
             format
         );
-        let stater = self::operations::stat::OpenDalStater::new(self.operator.clone());
+        let stater = self::operations::stat::OpenDalStater::new(self.fresh_operator().await?);
         let meta = stater.stat(path).await?;
 
         match format {
@@ -350,6 +1033,12 @@ impl StorageClient {
                 if let Some(ct) = meta.content_type {
                     println!("content_type={}", ct);
                 }
+                if let Some(cd) = meta.content_disposition {
+                    println!("content_disposition={}", cd);
+                }
+                if let Some(cc) = meta.cache_control {
+                    println!("cache_control={}", cc);
+                }
             }
             OutputFormat::Raw => {
                 println!("path={}", meta.path);
@@ -364,8 +1053,14 @@ impl StorageClient {
                 if let Some(ct) = meta.content_type {
                     println!("content_type={}", ct);
                 }
+                if let Some(cd) = meta.content_disposition {
+                    println!("content_disposition={}", cd);
+                }
+                if let Some(cc) = meta.cache_control {
+                    println!("cache_control={}", cc);
+                }
             }
-            OutputFormat::Json => {
+            OutputFormat::Json(style) => {
                 #[derive(serde::Serialize)]
                 struct JsonMeta<'a> {
                     path: &'a str,
@@ -374,6 +1069,8 @@ impl StorageClient {
                     last_modified: Option<String>,
                     etag: Option<String>,
                     content_type: Option<String>,
+                    content_disposition: Option<String>,
+                    cache_control: Option<String>,
                 }
                 let json = JsonMeta {
                     path: &meta.path,
@@ -382,11 +1079,268 @@ impl StorageClient {
                     last_modified: meta.last_modified,
                     etag: meta.etag,
                     content_type: meta.content_type,
+                    content_disposition: meta.content_disposition,
+                    cache_control: meta.cache_control,
                 };
-                println!("{}", serde_json::to_string(&json)?);
+                println!("{}", style.render(&json)?);
+            }
+            OutputFormat::Template(template) => {
+                println!("{}", meta.render(&template));
             }
         }
 
         Ok(())
     }
+
+    /// Whether an object or directory exists at `path`.
+    pub async fn path_exists(&self, path: &str) -> Result<bool> {
+        log::debug!("path_exists provider={:?} path={}", self.provider, path);
+        self::operations::stat::path_exists(&self.fresh_operator().await?, path).await
+    }
+}
+
+/// Region named by the `x-amz-bucket-region` header of a bucket HEAD response. An endpoint
+/// that answers without the header ignores regions, so the default one is used.
+fn region_from_response(status: StatusCode, headers: &HeaderMap) -> Option<String> {
+    let header = headers
+        .get("x-amz-bucket-region")
+        .and_then(|v| v.to_str().ok());
+    match header.map(str::trim) {
+        Some(region) if !region.is_empty() => Some(region.to_string()),
+        _ => matches!(status, StatusCode::OK | StatusCode::FORBIDDEN)
+            .then(|| "us-east-1".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::utils::fixture::TempDir;
+
+    fn s3_config(region: Option<&str>, endpoint: Option<&str>) -> StorageConfig {
+        let mut config = StorageConfig::s3(
+            "bucket".to_string(),
+            "ak".to_string(),
+            "sk".to_string(),
+            region.map(str::to_string),
+        );
+        config.endpoint = endpoint.map(str::to_string);
+        config
+    }
+
+    #[test]
+    fn test_needs_region_probe() {
+        assert!(s3_config(None, Some("http://127.0.0.1:9000")).needs_region_probe());
+        assert!(s3_config(Some(" "), Some("http://127.0.0.1:9000")).needs_region_probe());
+        assert!(!s3_config(Some("us-east-1"), Some("http://127.0.0.1:9000")).needs_region_probe());
+        assert!(!s3_config(None, None).needs_region_probe());
+        assert!(!StorageConfig::fs("./storage".to_string()).needs_region_probe());
+    }
+
+    #[test]
+    fn test_region_from_response() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-amz-bucket-region", "eu-west-1".parse().unwrap());
+        for status in [
+            StatusCode::OK,
+            StatusCode::MOVED_PERMANENTLY,
+            StatusCode::NOT_FOUND,
+        ] {
+            assert_eq!(
+                region_from_response(status, &headers).as_deref(),
+                Some("eu-west-1")
+            );
+        }
+
+        let empty = HeaderMap::new();
+        assert_eq!(
+            region_from_response(StatusCode::FORBIDDEN, &empty).as_deref(),
+            Some("us-east-1")
+        );
+        assert_eq!(region_from_response(StatusCode::NOT_FOUND, &empty), None);
+
+        headers.insert("x-amz-bucket-region", " ".parse().unwrap());
+        assert_eq!(region_from_response(StatusCode::NOT_FOUND, &headers), None);
+    }
+
+    #[test]
+    fn test_server_checksum_wiring() {
+        let mut config = s3_config(Some("us-east-1"), None);
+        assert_eq!(config.server_checksum(), None);
+
+        config.checksum_algorithm = Some(ChecksumAlgorithm::Crc32c);
+        assert_eq!(config.server_checksum(), Some(ChecksumAlgorithm::Crc32c));
+        assert!(StorageClient::build_operator(&config).is_ok());
+
+        config.checksum_algorithm = Some(ChecksumAlgorithm::Sha256);
+        assert_eq!(config.server_checksum(), None);
+
+        let mut fs = StorageConfig::fs("./storage".to_string());
+        fs.checksum_algorithm = Some(ChecksumAlgorithm::Crc32c);
+        assert_eq!(fs.server_checksum(), None);
+    }
+
+    #[test]
+    fn test_build_operator_validates_endpoint() {
+        let config = s3_config(
+            Some("us-east-1"),
+            Some("https://gateway.example.com:8443/s3/"),
+        );
+        assert!(StorageClient::build_operator(&config).is_ok());
+
+        let config = s3_config(Some("us-east-1"), Some("gateway.example.com:8443"));
+        let err = StorageClient::build_operator(&config).unwrap_err();
+        assert!(matches!(err, Error::InvalidEndpoint { .. }));
+
+        let mut oss = StorageConfig::oss(
+            "bucket".to_string(),
+            "id".to_string(),
+            "secret".to_string(),
+            None,
+        );
+        oss.endpoint = Some("https://oss-cn-hangzhou.aliyuncs.com:443".to_string());
+        assert!(StorageClient::build_operator(&oss).is_ok());
+        oss.endpoint = Some("https://gateway.example.com/oss".to_string());
+        let err = StorageClient::build_operator(&oss).unwrap_err();
+        assert!(err.to_string().contains("cannot include a base path"));
+    }
+
+    #[test]
+    fn test_gcs_provider() {
+        assert!(matches!(
+            "google".parse::<StorageProvider>(),
+            Ok(StorageProvider::Gcs)
+        ));
+        assert!(matches!(
+            "GCS".parse::<StorageProvider>(),
+            Ok(StorageProvider::Gcs)
+        ));
+
+        let mut config = StorageConfig::gcs("bucket".to_string(), None);
+        config.endpoint = Some("https://storage.googleapis.com".to_string());
+        assert!(StorageClient::build_operator(&config).is_ok());
+        config.endpoint = Some("storage.googleapis.com".to_string());
+        let err = StorageClient::build_operator(&config).unwrap_err();
+        assert!(matches!(err, Error::InvalidEndpoint { .. }));
+    }
+
+    #[test]
+    fn test_azblob_provider() {
+        assert!(matches!(
+            "azure".parse::<StorageProvider>(),
+            Ok(StorageProvider::Azblob)
+        ));
+
+        let mut config = StorageConfig::azblob(
+            "container".to_string(),
+            "myaccount".to_string(),
+            "a2V5".to_string(),
+        );
+        assert_eq!(
+            config.azblob_endpoint().unwrap(),
+            "https://myaccount.blob.core.windows.net"
+        );
+        assert!(StorageClient::build_operator(&config).is_ok());
+
+        config.endpoint = Some("http://127.0.0.1:10000/devstoreaccount1".to_string());
+        assert_eq!(
+            config.azblob_endpoint().unwrap(),
+            "http://127.0.0.1:10000/devstoreaccount1"
+        );
+        assert!(StorageClient::build_operator(&config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_client_rejects_writes() {
+        let dir = TempDir::new();
+        let mut config = StorageConfig::fs(dir.root());
+        config.read_only = true;
+        let client = StorageClient::new(config).await.unwrap();
+
+        let err = client.create_directory("dir/", true).await.unwrap_err();
+        assert!(matches!(err, Error::ReadOnly { .. }));
+        let err = client
+            .delete_files(&["dir/".to_string()], true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ReadOnly { .. }));
+        assert!(!client.path_exists("dir/").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_request_limit_shared_with_url_clients() {
+        let dir = TempDir::new();
+        let fs_config = || StorageConfig::fs(dir.root());
+
+        let mut config = fs_config();
+        config.max_concurrent_requests = NonZeroUsize::new(2);
+        let limited = StorageClient::new(config).await.unwrap();
+        assert!(limited.request_limit.is_some());
+
+        let other = StorageClient::new(fs_config()).await.unwrap();
+        assert!(other.request_limit.is_none());
+        let other = other.with_request_limit_of(&limited);
+        assert!(other.request_limit.is_some());
+        other.operator().write("limited.txt", "ok").await.unwrap();
+        assert!(limited.path_exists("limited.txt").await.unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_expiring_process_credentials_refresh_within_one_client() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new();
+        let calls = dir.join("calls");
+        // Each run hands out a new key that is already inside the refresh margin.
+        let script = dir.join("creds.sh");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho run >> '{calls}'\nn=$(wc -l < '{calls}' | tr -d ' ')\nprintf '{{\"AccessKeyId\": \"AKID-%s\", \"SecretAccessKey\": \"SECRET\", \"Expiration\": \"%s\"}}' \"$n\" '{expiration}'\n",
+                calls = calls.display(),
+                expiration = (Utc::now() + chrono::Duration::minutes(1)).to_rfc3339(),
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let process = Box::leak(Box::new(CredentialProcess::new(
+            script.display().to_string(),
+        )));
+
+        let mut config = StorageConfig::fs(dir.root());
+        config.credential_process = Some(process);
+        let client = StorageClient::new(config).await.unwrap();
+        let key = || {
+            let refresh = client.credentials.as_ref().unwrap();
+            refresh
+                .credentials
+                .try_lock()
+                .unwrap()
+                .access_key_id
+                .clone()
+        };
+        assert_eq!(key(), "AKID-1");
+
+        // The keys expire while the client is in use: each operation runs the process
+        // again and goes on working with the operator rebuilt from the new keys.
+        client.create_directory("dir/", true).await.unwrap();
+        assert_eq!(key(), "AKID-2");
+        assert!(client.path_exists("dir/").await.unwrap());
+        assert_eq!(key(), "AKID-3");
+    }
+
+    #[test]
+    fn test_versioning_capability_by_provider() {
+        let s3 = StorageClient::build_operator(&s3_config(Some("us-east-1"), None)).unwrap();
+        let capability = s3.info().full_capability();
+        assert!(capability.read_with_version);
+        assert!(capability.list_with_versions);
+
+        let fs =
+            StorageClient::build_operator(&StorageConfig::fs("./storage".to_string())).unwrap();
+        let capability = fs.info().full_capability();
+        assert!(!capability.read_with_version);
+        assert!(!capability.list_with_versions);
+    }
 }
@@ -1,6 +1,21 @@
 // Buffer related constants
 pub const DEFAULT_BUFFER_SIZE: usize = 8192;
 pub const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
+// Part size for multipart uploads when only `--part-concurrency` is given
+pub const DEFAULT_PART_SIZE: usize = 8 * 1024 * 1024;
+
+// Number of child prefixes `du -s` lists in parallel
+pub const DEFAULT_USAGE_CONCURRENCY: usize = 8;
+// Number of `stat` requests `ls --parallel-stat` keeps in flight
+pub const DEFAULT_STAT_CONCURRENCY: usize = 16;
+// Entries requested per listing page; the most S3 and OSS return in one response
+pub const DEFAULT_LIST_PAGE_SIZE: usize = 1000;
+// Files a directory download fetches at once unless `--concurrency` says otherwise
+pub const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 8;
+// Listed entries a directory download may queue ahead of its transfers
+pub const DOWNLOAD_LIST_AHEAD: usize = 1000;
+// Bytes `head`/`tail` fetch per ranged read while counting lines
+pub const LINE_SCAN_CHUNK_SIZE: usize = 64 * 1024;
 
 // Progress related constants
 // Controls how often progress is printed (in multiples of buffer size)
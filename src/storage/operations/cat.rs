@@ -0,0 +1,167 @@
+// Streaming object contents to stdout without touching local disk
+use crate::error::{NotAFileSnafu, Result};
+use crate::storage::constants::DEFAULT_BUFFER_SIZE;
+use crate::storage::utils::error::{map_io_read_error, map_read_error};
+use futures::AsyncReadExt;
+use opendal::Operator;
+use snafu::ensure;
+use std::io::{ErrorKind, Write};
+
+/// Trait for printing the contents of objects in storage.
+pub trait Catter {
+    /// Stream the object at `path` to stdout.
+    ///
+    /// # Arguments
+    /// * `path` - Key of the object to print
+    ///
+    /// # Returns
+    /// * `Result<u64>` - Bytes written, or detailed error information
+    async fn cat(&self, path: &str) -> Result<u64>;
+}
+
+/// Implementation of Catter for OpenDAL Operator.
+pub struct OpenDalCatter {
+    operator: Operator,
+}
+
+impl OpenDalCatter {
+    /// Create a new catter with the given OpenDAL operator.
+    pub fn new(operator: Operator) -> Self {
+        Self { operator }
+    }
+
+    /// Copy the object at `path` into `out` one buffer at a time. A reader that goes away
+    /// early (`storify cat ... | head`) ends the copy quietly rather than failing it.
+    async fn copy_to(&self, path: &str, out: &mut impl Write) -> Result<u64> {
+        let meta = self.operator.stat(path).await?;
+        ensure!(
+            !meta.is_dir(),
+            NotAFileSnafu {
+                path: path.to_string()
+            }
+        );
+
+        let mut reader = self
+            .operator
+            .reader(path)
+            .await
+            .map_err(|e| map_read_error(e, path))?
+            .into_futures_async_read(..)
+            .await
+            .map_err(|e| map_read_error(e, path))?;
+        let mut buffer = vec![0u8; DEFAULT_BUFFER_SIZE];
+        let mut total_bytes = 0u64;
+        loop {
+            let read = reader
+                .read(&mut buffer)
+                .await
+                .map_err(|e| map_io_read_error(e, path))?;
+            if read == 0 {
+                break;
+            }
+            if !write_chunk(out, &buffer[..read])? {
+                return Ok(total_bytes);
+            }
+            total_bytes += read as u64;
+        }
+        write_chunk(out, &[])?;
+        Ok(total_bytes)
+    }
+}
+
+/// Write `chunk` to `out` and flush it, returning `false` once the reader has gone away
+/// (a closed pipe) so the caller can stop quietly instead of failing.
+pub(crate) fn write_chunk(out: &mut impl Write, chunk: &[u8]) -> Result<bool> {
+    match out.write_all(chunk).and_then(|()| out.flush()) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl Catter for OpenDalCatter {
+    async fn cat(&self, path: &str) -> Result<u64> {
+        self.copy_to(path, &mut std::io::stdout()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+
+    #[tokio::test]
+    async fn test_cat_streams_whole_object_and_rejects_directories() {
+        let root = TempDir::new();
+        let operator = fs_operator(root.path());
+        let catter = OpenDalCatter::new(operator.clone());
+
+        // Larger than one buffer, and not a multiple of it.
+        let content: Vec<u8> = (0..DEFAULT_BUFFER_SIZE * 2 + 100)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        operator
+            .write("dir/big.bin", content.clone())
+            .await
+            .unwrap();
+
+        let mut out = Vec::new();
+        let written = catter.copy_to("dir/big.bin", &mut out).await.unwrap();
+        assert_eq!(written, content.len() as u64);
+        assert_eq!(out, content);
+
+        let err = catter.copy_to("dir/", &mut Vec::new()).await.unwrap_err();
+        assert!(matches!(err, Error::NotAFile { .. }));
+    }
+
+    /// S3 operator against a local stub that answers HEAD for a small object and GET with
+    /// the error S3 returns for an object in an archive storage class.
+    async fn archived_s3_operator() -> Operator {
+        use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = vec![0u8; 8192];
+                    let read = socket.read(&mut request).await.unwrap_or(0);
+                    let response = if request[..read].starts_with(b"HEAD") {
+                        "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    } else {
+                        let body = "<Error><Code>InvalidObjectState</Code>\
+                            <Message>The operation is not valid for the object's storage class</Message>\
+                            </Error>";
+                        format!(
+                            "HTTP/1.1 403 Forbidden\r\nContent-Type: application/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                            body.len()
+                        )
+                    };
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        let builder = opendal::services::S3::default()
+            .bucket("bucket")
+            .endpoint(&endpoint)
+            .region("us-east-1")
+            .access_key_id("ak")
+            .secret_access_key("sk");
+        Operator::new(builder).unwrap().finish()
+    }
+
+    #[tokio::test]
+    async fn test_cat_reports_archived_objects() {
+        let catter = OpenDalCatter::new(archived_s3_operator().await);
+        let err = catter
+            .copy_to("cold/data.csv", &mut Vec::new())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::ObjectArchived { ref path } if path == "cold/data.csv"),
+            "{err}"
+        );
+    }
+}
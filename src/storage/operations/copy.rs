@@ -1,14 +1,98 @@
 use crate::error::{InvalidPathSnafu, Result};
 use crate::storage::constants::DEFAULT_CHUNK_SIZE;
+use crate::storage::operations::stat::stat_if_exists;
+use crate::storage::utils::failure::{ErrorPolicy, FailureTracker};
 use crate::storage::utils::path::{
     basename, build_remote_path, ensure_trailing_slash, get_root_relative_path,
 };
-use crate::storage::utils::progress::ConsoleProgressReporter;
+use crate::storage::utils::progress::{ConsoleProgressReporter, ProgressOptions};
 use async_recursion::async_recursion;
 use futures::stream::TryStreamExt;
-use opendal::{EntryMode, Operator};
+use opendal::{EntryMode, Metadata, Operator};
 use snafu::ensure;
 
+/// What happens to the source object's headers when it is copied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum MetadataDirective {
+    /// Keep the source's headers (server-side copies always do)
+    #[default]
+    Copy,
+    /// Store only the headers given with the copy, e.g. `--content-type`
+    Replace,
+}
+
+/// When a copy may replace an object that already exists at the destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OverwritePolicy {
+    /// Always replace the destination
+    #[default]
+    Always,
+    /// Never replace an existing destination
+    Never,
+    /// Replace only when the source was modified after the destination
+    Newer,
+    /// Replace only when the source and destination sizes differ
+    IfSizeDiffers,
+}
+
+impl OverwritePolicy {
+    /// Why copying `src` over the existing `dest` should be skipped, if it should.
+    /// Objects without a modification time are always copied under `Newer`.
+    fn skip_reason(self, src: &Metadata, dest: &Metadata) -> Option<&'static str> {
+        match self {
+            OverwritePolicy::Always => None,
+            OverwritePolicy::Never => Some("destination already exists"),
+            OverwritePolicy::Newer => match (src.last_modified(), dest.last_modified()) {
+                (Some(src), Some(dest)) if src <= dest => {
+                    Some("destination is at least as new as the source")
+                }
+                _ => None,
+            },
+            OverwritePolicy::IfSizeDiffers => (src.content_length() == dest.content_length())
+                .then_some("destination has the same size"),
+        }
+    }
+}
+
+/// Options controlling how `cp` transfers data.
+#[derive(Debug, Clone, Default)]
+pub struct CopyOptions {
+    /// Always stream data through the client, even when a server-side copy is possible
+    pub client_side: bool,
+    /// Replay the source's content type, cache control, content disposition and user
+    /// metadata onto streamed copies (server-side copies keep them already)
+    pub preserve_metadata: bool,
+    /// What to do when an object of a directory copy fails
+    pub on_error: ErrorPolicy,
+    /// Whether copies keep the source's headers or take the ones given here
+    pub metadata_directive: MetadataDirective,
+    /// `Content-Type` stored on each copy when replacing metadata
+    pub content_type: Option<String>,
+    /// When an existing destination object is replaced
+    pub overwrite_policy: OverwritePolicy,
+}
+
+impl CopyOptions {
+    /// Headers to store instead of the source's, or `None` to keep them.
+    fn replacement_metadata(&self) -> Option<ReplacementMetadata> {
+        (self.metadata_directive == MetadataDirective::Replace).then(|| ReplacementMetadata {
+            content_type: self.content_type.clone(),
+        })
+    }
+
+    /// Replacing metadata always streams the data: OpenDAL's native copy cannot send
+    /// new headers.
+    pub(crate) fn allows_server_side(&self) -> bool {
+        !self.client_side && self.metadata_directive == MetadataDirective::Copy
+    }
+}
+
+/// Headers written in place of the source's under `MetadataDirective::Replace`.
+#[derive(Debug, Clone, Default)]
+struct ReplacementMetadata {
+    content_type: Option<String>,
+}
+
 /// Trait for copying files and directories within storage.
 pub trait Copier {
     /// Copy a single file or entire directory from one location to another in object storage.
@@ -23,24 +107,84 @@ pub trait Copier {
 }
 
 /// Implementation of Copier for OpenDAL Operator.
+///
+/// Source and destination operators are usually the same; they differ when copying
+/// between two buckets or providers.
 pub struct OpenDalCopier {
-    operator: Operator,
+    src: Operator,
+    dest: Operator,
+    progress: ProgressOptions,
+    server_side: bool,
+    same_backend: bool,
+    preserve_metadata: bool,
+    on_error: ErrorPolicy,
+    replacement: Option<ReplacementMetadata>,
+    overwrite_policy: OverwritePolicy,
 }
 
 impl OpenDalCopier {
-    /// Create a new copier with the given OpenDAL operator.
-    pub fn new(operator: Operator) -> Self {
-        Self { operator }
+    /// Create a new copier that reads from `src` and writes to `dest` with the given
+    /// progress settings.
+    pub fn new(src: Operator, dest: Operator, progress: ProgressOptions) -> Self {
+        Self {
+            src,
+            dest,
+            progress,
+            server_side: false,
+            same_backend: false,
+            preserve_metadata: false,
+            on_error: ErrorPolicy::default(),
+            replacement: None,
+            overwrite_policy: OverwritePolicy::default(),
+        }
+    }
+
+    /// Use the backend's native copy (no data round-trip) for each object.
+    /// Callers must only enable this when source and destination are the same backend.
+    pub fn with_server_side(mut self, server_side: bool) -> Self {
+        self.server_side = server_side;
+        self
+    }
+
+    /// Declare that source and destination are the same backend, so a copy of a key onto
+    /// itself rewrites one object rather than copying between two.
+    pub fn with_same_backend(mut self, same_backend: bool) -> Self {
+        self.same_backend = same_backend;
+        self
+    }
+
+    /// Carry the source object's headers and user metadata over to streamed copies.
+    pub fn with_preserve_metadata(mut self, preserve_metadata: bool) -> Self {
+        self.preserve_metadata = preserve_metadata;
+        self
+    }
+
+    /// Apply `options`' metadata directive to streamed copies.
+    pub fn with_metadata_directive(mut self, options: &CopyOptions) -> Self {
+        self.replacement = options.replacement_metadata();
+        self
+    }
+
+    /// Decide from source and destination metadata whether existing objects are replaced.
+    pub fn with_overwrite_policy(mut self, overwrite_policy: OverwritePolicy) -> Self {
+        self.overwrite_policy = overwrite_policy;
+        self
+    }
+
+    /// How to handle objects that fail while copying a directory.
+    pub fn with_on_error(mut self, on_error: ErrorPolicy) -> Self {
+        self.on_error = on_error;
+        self
     }
 
     /// Hybrid directory detection for object storage: stat first; if not available, probe prefix.
-    async fn is_directory(&self, path: &str) -> bool {
-        match self.operator.stat(path).await.ok().map(|m| m.mode()) {
+    async fn is_directory(operator: &Operator, path: &str) -> bool {
+        match operator.stat(path).await.ok().map(|m| m.mode()) {
             Some(EntryMode::DIR) => true,
             Some(_) => false,
             None => {
                 let probe = ensure_trailing_slash(path);
-                self.operator
+                operator
                     .list_with(&probe)
                     .limit(1)
                     .await
@@ -50,17 +194,18 @@ impl OpenDalCopier {
         }
     }
 
-    /// Ensure a remote directory exists (appends trailing '/').
+    /// Ensure a remote directory exists on the destination (appends trailing '/').
     async fn ensure_directory(&self, dir_path: &str) -> Result<()> {
         let to_create = ensure_trailing_slash(dir_path);
-        self.operator.create_dir(&to_create).await?;
+        self.dest.create_dir(&to_create).await?;
         Ok(())
     }
 
     /// Copy files recursively with directory structure preservation.
     #[async_recursion]
     async fn copy_file_recursive(&self, src_path: &str, dest_path: &str) -> Result<()> {
-        let lister = self.operator.lister_with(src_path).recursive(true).await?;
+        let lister = self.src.lister_with(src_path).recursive(true).await?;
+        let mut failures = FailureTracker::new(self.on_error);
 
         let mut stream = lister;
         while let Some(entry) = stream.try_next().await? {
@@ -80,19 +225,106 @@ impl OpenDalCopier {
             if meta.mode() == EntryMode::DIR {
                 self.ensure_directory(&new_dest_path).await?;
             } else {
-                self.stream_copy(entry_path, &new_dest_path).await?;
+                let result = self
+                    .on_error
+                    .run(entry_path, || self.copy_object(entry_path, &new_dest_path))
+                    .await;
+                failures.record(entry_path, result)?;
             }
         }
 
-        Ok(())
+        failures.finish()
+    }
+
+    /// Copy a single object, server-side when enabled, otherwise by streaming.
+    async fn copy_object(&self, src_path: &str, dest_path: &str) -> Result<()> {
+        if let Some(reason) = self.overwrite_skip_reason(src_path, dest_path).await? {
+            eprintln!("Skipped {src_path}: {reason}");
+            return Ok(());
+        }
+        if self.server_side {
+            self.src.copy(src_path, dest_path).await?;
+            println!("✅ Copied (server-side): {src_path} → {dest_path}");
+            Ok(())
+        } else {
+            Ok(self.stream_copy(src_path, dest_path).await?)
+        }
+    }
+
+    /// Compare source and destination under the overwrite policy; an absent destination
+    /// is always written.
+    async fn overwrite_skip_reason(
+        &self,
+        src_path: &str,
+        dest_path: &str,
+    ) -> Result<Option<&'static str>> {
+        if self.overwrite_policy == OverwritePolicy::Always {
+            return Ok(None);
+        }
+        let Some(dest) = stat_if_exists(&self.dest, dest_path).await? else {
+            return Ok(None);
+        };
+        let src = self.src.stat(src_path).await?;
+        Ok(self.overwrite_policy.skip_reason(&src, &dest))
     }
 
     /// Stream copy a single file with progress reporting.
     async fn stream_copy(&self, src_path: &str, dest_path: &str) -> opendal::Result<()> {
-        let metadata = self.operator.stat(src_path).await?;
+        let in_place = self.same_backend
+            && src_path.trim_start_matches('/') == dest_path.trim_start_matches('/');
+        if !in_place {
+            return self.stream_to(src_path, dest_path, dest_path).await;
+        }
+        // Rewriting an object onto itself (e.g. to replace its metadata): a writer may
+        // truncate the destination before the source is read through, so the copy is
+        // streamed to a staging key and then moved over the original.
+        let staging = staging_key(dest_path);
+        let result = async {
+            self.stream_to(src_path, &staging, dest_path).await?;
+            if self.dest.info().full_capability().rename {
+                self.dest.rename(&staging, dest_path).await
+            } else {
+                self.dest.copy(&staging, dest_path).await
+            }
+        }
+        .await;
+        if let Err(err) = self.dest.delete(&staging).await {
+            log::debug!("removing staging object {staging} failed: {err}");
+        }
+        result
+    }
+
+    /// Stream `src_path` into the `target` key, reporting progress and the result under
+    /// `dest_path`.
+    async fn stream_to(
+        &self,
+        src_path: &str,
+        target: &str,
+        dest_path: &str,
+    ) -> opendal::Result<()> {
+        let metadata = self.src.stat(src_path).await?;
         let file_size = metadata.content_length();
 
-        let mut writer = self.operator.writer(dest_path).await?;
+        let mut write = self.dest.writer_with(target);
+        if let Some(replacement) = &self.replacement {
+            if let Some(content_type) = &replacement.content_type {
+                write = write.content_type(content_type);
+            }
+        } else if self.preserve_metadata {
+            if let Some(content_type) = metadata.content_type() {
+                write = write.content_type(content_type);
+            }
+            if let Some(cache_control) = metadata.cache_control() {
+                write = write.cache_control(cache_control);
+            }
+            if let Some(content_disposition) = metadata.content_disposition() {
+                write = write.content_disposition(content_disposition);
+            }
+            if let Some(user_metadata) = metadata.user_metadata() {
+                write = write.user_metadata(user_metadata.clone());
+            }
+        }
+        let mut writer = write.await?;
         let mut total_bytes = 0u64;
         let mut offset = 0u64;
 
@@ -100,39 +332,63 @@ impl OpenDalCopier {
             format!("Copying {src_path}"),
             Some(file_size),
             DEFAULT_CHUNK_SIZE as u64,
-        );
+        )
+        .with_options(&self.progress, dest_path);
 
-        loop {
-            let chunk_size = std::cmp::min(DEFAULT_CHUNK_SIZE as u64, file_size - offset);
-
-            let data = self
-                .operator
-                .read_with(src_path)
-                .range(offset..offset + chunk_size)
-                .await?;
-            let data_len = data.len();
-            if data_len == 0 {
-                break;
-            }
+        let written: opendal::Result<()> = async {
+            loop {
+                let chunk_size = std::cmp::min(DEFAULT_CHUNK_SIZE as u64, file_size - offset);
 
-            writer.write(data).await?;
-            total_bytes += data_len as u64;
-            offset += chunk_size;
+                let data = self
+                    .src
+                    .read_with(src_path)
+                    .range(offset..offset + chunk_size)
+                    .await?;
+                let data_len = data.len();
+                if data_len == 0 {
+                    break;
+                }
 
-            reporter.maybe_report(total_bytes);
-        }
+                writer.write(data).await?;
+                total_bytes += data_len as u64;
+                offset += chunk_size;
 
-        writer.close().await?;
-        println!("\n✅ Copied: {src_path} → {dest_path} ({total_bytes} bytes)");
+                reporter.maybe_report(total_bytes);
+            }
+            writer.close().await?;
+            Ok(())
+        }
+        .await;
+        if let Err(err) = written {
+            // Drop the partial object rather than leaving a multipart upload behind.
+            if let Err(abort_err) = writer.abort().await {
+                log::debug!("aborting copy to {target} failed: {abort_err}");
+            }
+            return Err(err);
+        }
+        reporter.finish(total_bytes);
+        println!("✅ Copied: {src_path} → {dest_path} ({total_bytes} bytes)");
 
         Ok(())
     }
 }
 
+/// Key next to `dest_path` that an in-place rewrite is streamed to first.
+fn staging_key(dest_path: &str) -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    format!(
+        "{}.storify-copy-{}-{nanos}",
+        dest_path.trim_end_matches('/'),
+        std::process::id()
+    )
+}
+
 impl Copier for OpenDalCopier {
     async fn copy(&self, src_path: &str, dest_path: &str) -> Result<()> {
-        let src_stat = self.operator.stat(src_path).await.ok();
-        let src_is_dir = self.is_directory(src_path).await;
+        let src_stat = self.src.stat(src_path).await.ok();
+        let src_is_dir = Self::is_directory(&self.src, src_path).await;
         ensure!(
             src_stat.is_some() || src_is_dir,
             InvalidPathSnafu {
@@ -141,7 +397,7 @@ impl Copier for OpenDalCopier {
         );
 
         if src_is_dir {
-            let target_root = if self.is_directory(dest_path).await {
+            let target_root = if Self::is_directory(&self.dest, dest_path).await {
                 let base_name = basename(src_path);
                 let target_root = build_remote_path(dest_path, &base_name);
                 self.ensure_directory(&target_root).await?;
@@ -155,7 +411,7 @@ impl Copier for OpenDalCopier {
             Ok(())
         } else {
             let dest_is_dir_hint = dest_path.ends_with('/');
-            let dest_is_dir = self.is_directory(dest_path).await;
+            let dest_is_dir = Self::is_directory(&self.dest, dest_path).await;
 
             if dest_is_dir_hint && !dest_is_dir {
                 return Err(crate::error::Error::InvalidPath {
@@ -170,8 +426,90 @@ impl Copier for OpenDalCopier {
                 dest_path.to_string()
             };
 
-            self.stream_copy(src_path, &final_dest).await?;
+            self.copy_object(src_path, &final_dest).await?;
             Ok(())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+    use chrono::{TimeZone, Utc};
+
+    fn object(size: u64, modified_secs: Option<i64>) -> Metadata {
+        let meta = Metadata::new(EntryMode::FILE).with_content_length(size);
+        match modified_secs {
+            Some(secs) => meta.with_last_modified(Utc.timestamp_opt(secs, 0).unwrap()),
+            None => meta,
+        }
+    }
+
+    #[test]
+    fn test_overwrite_policy_skip_reason() {
+        let src = object(10, Some(200));
+        let older = object(10, Some(100));
+        let newer = object(20, Some(300));
+        let undated = object(10, None);
+
+        for dest in [&older, &newer, &undated] {
+            assert_eq!(OverwritePolicy::Always.skip_reason(&src, dest), None);
+            assert!(OverwritePolicy::Never.skip_reason(&src, dest).is_some());
+        }
+
+        assert_eq!(OverwritePolicy::Newer.skip_reason(&src, &older), None);
+        assert!(OverwritePolicy::Newer.skip_reason(&src, &newer).is_some());
+        assert!(OverwritePolicy::Newer.skip_reason(&src, &src).is_some());
+        assert_eq!(OverwritePolicy::Newer.skip_reason(&src, &undated), None);
+        assert_eq!(OverwritePolicy::Newer.skip_reason(&undated, &older), None);
+
+        assert!(
+            OverwritePolicy::IfSizeDiffers
+                .skip_reason(&src, &older)
+                .is_some()
+        );
+        assert_eq!(
+            OverwritePolicy::IfSizeDiffers.skip_reason(&src, &newer),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_place_rewrite_streams_through_a_staging_key() {
+        let root = TempDir::new();
+        let operator = fs_operator(root.path());
+        // Several chunks, so a truncating writer would lose data.
+        let content: Vec<u8> = (0..DEFAULT_CHUNK_SIZE * 2 + 11)
+            .map(|i| (i % 253) as u8)
+            .collect();
+        operator
+            .write("dir/big.bin", content.clone())
+            .await
+            .unwrap();
+
+        let options = CopyOptions {
+            metadata_directive: MetadataDirective::Replace,
+            content_type: Some("application/octet-stream".to_string()),
+            ..Default::default()
+        };
+        let copier = OpenDalCopier::new(operator.clone(), operator.clone(), Default::default())
+            .with_same_backend(true)
+            .with_metadata_directive(&options);
+        copier.copy("dir/big.bin", "dir/big.bin").await.unwrap();
+
+        assert_eq!(
+            operator.read("dir/big.bin").await.unwrap().to_vec(),
+            content
+        );
+        let keys: Vec<String> = operator
+            .list("dir/")
+            .await
+            .unwrap()
+            .iter()
+            .map(|entry| entry.path().to_string())
+            .filter(|key| key != "dir/")
+            .collect();
+        assert_eq!(keys, ["dir/big.bin"]);
+    }
+}
@@ -1,5 +1,6 @@
 // Delete operation trait and implementation
 use crate::error::{DirectoryDeletionNotRecursiveSnafu, PartialDeletionSnafu, Result};
+use futures::stream::TryStreamExt;
 use opendal::Operator;
 
 /// Trait for deleting files and directories from storage.
@@ -42,6 +43,27 @@ impl OpenDalDeleter {
             Err(_) => Ok(false),
         }
     }
+
+    /// Delete every object under the directory `path`, printing each one, then sweep up
+    /// the directory markers left behind.
+    async fn delete_tree(&self, path: &str) -> Result<()> {
+        // Enumerate before deleting so backends that page through keys don't skip any.
+        let files: Vec<String> = self
+            .operator
+            .lister_with(path)
+            .recursive(true)
+            .await?
+            .try_filter(|entry| std::future::ready(entry.metadata().is_file()))
+            .map_ok(|entry| entry.path().to_string())
+            .try_collect()
+            .await?;
+        for file in files {
+            self.operator.delete(&file).await?;
+            println!("Deleted: {file}");
+        }
+        self.operator.remove_all(path).await?;
+        Ok(())
+    }
 }
 
 impl Deleter for OpenDalDeleter {
@@ -55,12 +77,18 @@ impl Deleter for OpenDalDeleter {
                 continue;
             }
 
-            if self.is_directory(path).await? && !recursive {
+            let is_dir = self.is_directory(path).await?;
+            if is_dir && !recursive {
                 return DirectoryDeletionNotRecursiveSnafu { path: path.clone() }.fail();
             }
 
-            match self.operator.remove_all(path).await {
-                Ok(_) => println!("Deleted: {path}"),
+            let deleted = if is_dir {
+                self.delete_tree(path).await
+            } else {
+                self.operator.delete(path).await.map_err(Into::into)
+            };
+            match deleted {
+                Ok(()) => println!("Deleted: {path}"),
                 Err(e) => {
                     eprintln!("Failed to delete {path}: {e}");
                     failed_paths.push(path.clone());
@@ -1,9 +1,64 @@
-use crate::error::{Error, Result};
+use crate::error::{ChecksumMismatchSnafu, Error, Result};
+use crate::storage::constants::{DEFAULT_BUFFER_SIZE, DOWNLOAD_LIST_AHEAD};
+use crate::storage::operations::stat::{ensure_exact_key, listed_size, path_exists};
+use crate::storage::utils::checksum::{
+    ChecksumAlgorithm, ChecksumHasher, retry_on_checksum_mismatch,
+};
+use crate::storage::utils::concurrency::{
+    AimdController, BreakerPolicy, CircuitBreaker, Concurrency, EndpointHealth, HealthPolicy,
+    read_ahead, run_bounded,
+};
+use crate::storage::utils::error::map_read_error;
+use crate::storage::utils::failure::{ErrorPolicy, FailureTracker};
 use crate::storage::utils::path::get_root_relative_path;
+use crate::storage::utils::size::{SizeRange, report_out_of_range};
+use crate::storage::utils::stats::TransferStats;
+use crate::storage::utils::transfer_log::{TransferLog, TransferOperation, log_transfer};
 use futures::stream::TryStreamExt;
+use futures::{AsyncRead, AsyncReadExt, future};
 use opendal::{EntryMode, Operator};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+/// Suffix of the file a download is written to before it replaces its target.
+const PARTIAL_SUFFIX: &str = ".ossify-part";
+
+/// Options controlling what `get` fetches.
+#[derive(Debug, Clone, Default)]
+pub struct DownloadOptions {
+    /// Fetch this specific object version instead of the current one
+    pub version_id: Option<String>,
+    /// Write identical content once and hard-link the duplicates to it
+    pub dedupe: bool,
+    /// Skip files whose existing local copy already has the remote size
+    pub if_size_differs: bool,
+    /// What to do when a file of a directory download fails
+    pub on_error: ErrorPolicy,
+    /// Files of a directory download fetched at once, or `auto` to tune it as it runs
+    pub concurrency: Concurrency,
+    /// Compare each file's MD5 with its ETag
+    pub verify: bool,
+    /// With `verify`, download a file again up to this many times while it mismatches
+    pub checksum_retries: u32,
+    /// Pause new transfers for a while after a run of 5xx responses
+    pub circuit_breaker: Option<BreakerPolicy>,
+    /// Wait for an unreachable endpoint to come back instead of failing its transfers
+    pub health_check: Option<HealthPolicy>,
+    /// Fail on keys that are not valid UTF-8 instead of fetching them by their lossy name
+    pub strict_utf8: bool,
+    /// Append one CSV row per file to this audit log
+    pub transfer_log: Option<TransferLog>,
+    /// Treat a remote path that does not exist as nothing to download instead of an error
+    pub lenient: bool,
+    /// Only download objects of a directory whose size lies in this band
+    pub size_range: SizeRange,
+}
 
 /// Trait for downloading files and directories from storage.
 pub trait Downloader {
@@ -12,10 +67,16 @@ pub trait Downloader {
     /// # Arguments
     /// * `remote_path` - Source path in storage (file or directory)
     /// * `local_path` - Destination path on local filesystem
+    /// * `options` - Download options
     ///
     /// # Returns
-    /// * `Result<()>` - Success or detailed error information
-    async fn download(&self, remote_path: &str, local_path: &str) -> Result<()>;
+    /// * `Result<TransferStats>` - Files and bytes written, or detailed error information
+    async fn download(
+        &self,
+        remote_path: &str,
+        local_path: &str,
+        options: &DownloadOptions,
+    ) -> Result<TransferStats>;
 }
 
 /// Implementation of Downloader for OpenDAL Operator.
@@ -28,11 +89,342 @@ impl OpenDalDownloader {
     pub fn new(operator: Operator) -> Self {
         Self { operator }
     }
+
+    /// Whether the endpoint gives any answer; a definite error (e.g. access denied) still
+    /// means it is up.
+    async fn endpoint_answers(&self) -> bool {
+        match self.operator.check().await {
+            Ok(()) => true,
+            Err(err) => !err.is_temporary(),
+        }
+    }
+
+    /// Download one specific version of a single object.
+    async fn download_version(
+        &self,
+        remote_path: &str,
+        local_path: &str,
+        version_id: &str,
+        options: &DownloadOptions,
+    ) -> Result<u64> {
+        let local_file_path =
+            Path::new(local_path).join(get_root_relative_path(remote_path, remote_path));
+        if let Some(parent) = local_file_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let digests = Digests {
+            md5: options.verify,
+            sha256: false,
+        };
+        let fetch = || async {
+            let reader = self
+                .operator
+                .reader_with(remote_path)
+                .version(version_id)
+                .await
+                .map_err(|e| map_read_error(e, remote_path))?;
+            write_part(
+                reader.into_futures_async_read(..).await?,
+                &local_file_path,
+                digests,
+            )
+            .await
+        };
+        let part = if options.verify {
+            let meta = self
+                .operator
+                .stat_with(remote_path)
+                .version(version_id)
+                .await
+                .map_err(|e| map_read_error(e, remote_path))?;
+            fetch_verified(remote_path, meta.etag(), options.checksum_retries, fetch).await?
+        } else {
+            fetch().await?
+        };
+
+        let bytes = part.persist(&local_file_path).await?;
+        println!(
+            "Downloaded: {remote_path} (version {version_id}) → {} ({bytes} bytes)",
+            local_file_path.display()
+        );
+        Ok(bytes)
+    }
+
+    /// Download one object of a directory download to `local_file_path`, returning the
+    /// bytes fetched, or `None` if the file was skipped.
+    async fn download_file(
+        &self,
+        remote_file_path: &str,
+        local_file_path: &Path,
+        remote_size: u64,
+        listed_etag: Option<&str>,
+        seen_content: Option<&Mutex<HashMap<String, PathBuf>>>,
+        options: &DownloadOptions,
+    ) -> Result<Option<u64>> {
+        if options.if_size_differs && local_size(local_file_path).await? == Some(remote_size) {
+            println!(
+                "Skipped (same size): {remote_file_path} → {}",
+                local_file_path.display()
+            );
+            return Ok(None);
+        }
+        if let Some(parent) = local_file_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let digests = Digests {
+            md5: options.verify,
+            sha256: seen_content.is_some(),
+        };
+        let fetch = || async {
+            let reader = self
+                .operator
+                .reader(remote_file_path)
+                .await
+                .map_err(|e| map_read_error(e, remote_file_path))?;
+            write_part(
+                reader.into_futures_async_read(..).await?,
+                local_file_path,
+                digests,
+            )
+            .await
+        };
+        let part = if options.verify {
+            // Listings usually carry the ETag; fall back to a stat for backends that don't.
+            let etag = match listed_etag {
+                Some(etag) => Some(etag.to_string()),
+                None => self
+                    .operator
+                    .stat(remote_file_path)
+                    .await?
+                    .etag()
+                    .map(str::to_string),
+            };
+            fetch_verified(
+                remote_file_path,
+                etag.as_deref(),
+                options.checksum_retries,
+                fetch,
+            )
+            .await?
+        } else {
+            fetch().await?
+        };
+        let fetched = part.bytes;
+        if let Some(seen_content) = seen_content
+            && let Some(digest) = part.sha256.clone()
+        {
+            let original = {
+                let mut seen = seen_content.lock().unwrap_or_else(PoisonError::into_inner);
+                let original = seen.get(&digest).cloned();
+                if original.is_none() {
+                    seen.insert(digest, local_file_path.to_path_buf());
+                }
+                original
+            };
+            if let Some(original) = original {
+                part.discard().await?;
+                link_or_copy(&original, local_file_path).await?;
+                println!(
+                    "Linked: {remote_file_path} → {} (same content as {})",
+                    local_file_path.display(),
+                    original.display()
+                );
+                return Ok(Some(fetched));
+            }
+        }
+        part.persist(local_file_path).await?;
+        println!(
+            "Downloaded: {remote_file_path} → {} ({fetched} bytes)",
+            local_file_path.display()
+        );
+        Ok(Some(fetched))
+    }
+}
+
+/// One object of a directory download.
+struct FileJob {
+    remote_path: String,
+    local_path: PathBuf,
+    size: u64,
+    etag: Option<String>,
+}
+
+/// `<path>.ossify-part`, next to `path` so the final rename stays on one filesystem.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(PARTIAL_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Digests computed while a download streams to disk.
+#[derive(Debug, Clone, Copy)]
+struct Digests {
+    /// MD5, to compare with the ETag
+    md5: bool,
+    /// SHA-256, to find duplicate content
+    sha256: bool,
+}
+
+/// A complete download sitting in its part file, not yet moved over its target.
+#[derive(Debug)]
+struct PartFile {
+    partial: PathBuf,
+    bytes: u64,
+    md5: Option<String>,
+    sha256: Option<String>,
+}
+
+impl PartFile {
+    /// Rename the part file over `path`, returning the bytes it holds.
+    async fn persist(self, path: &Path) -> Result<u64> {
+        fs::rename(&self.partial, path).await?;
+        Ok(self.bytes)
+    }
+
+    /// Delete the part file, leaving any previous copy of the target alone.
+    async fn discard(self) -> Result<()> {
+        fs::remove_file(&self.partial).await?;
+        Ok(())
+    }
+}
+
+/// Copy `reader` into `<path>.ossify-part` one buffer at a time, hashing the bytes on the
+/// way as `digests` asks, so memory stays flat whatever the object's size. A failure
+/// leaves the part file behind and any previous copy of `path` intact.
+async fn write_part(
+    mut reader: impl AsyncRead + Unpin,
+    path: &Path,
+    digests: Digests,
+) -> Result<PartFile> {
+    let partial = partial_path(path);
+    let mut file = fs::File::create(&partial).await?;
+    let mut md5 = digests
+        .md5
+        .then(|| ChecksumHasher::new(ChecksumAlgorithm::Md5));
+    let mut sha256 = digests
+        .sha256
+        .then(|| ChecksumHasher::new(ChecksumAlgorithm::Sha256));
+    let mut buffer = vec![0u8; DEFAULT_BUFFER_SIZE];
+    let mut bytes = 0u64;
+    loop {
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        let chunk = &buffer[..read];
+        for hasher in md5.iter_mut().chain(sha256.iter_mut()) {
+            hasher.update(chunk);
+        }
+        file.write_all(chunk).await?;
+        bytes += read as u64;
+    }
+    file.sync_all().await?;
+    Ok(PartFile {
+        partial,
+        bytes,
+        md5: md5.map(ChecksumHasher::finalize),
+        sha256: sha256.map(ChecksumHasher::finalize),
+    })
+}
+
+/// The MD5 digest an ETag stands for, or `None` when it is not a plain MD5. Multipart
+/// uploads (`<md5>-<parts>`) and some server-side encryption modes produce other ETags.
+fn etag_md5(etag: &str) -> Option<String> {
+    let etag = etag.trim_start_matches("W/").trim_matches('"');
+    (etag.len() == 32 && etag.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| etag.to_ascii_lowercase())
+}
+
+/// Fetch an object with `fetch`, which hashes the chunks as they stream to disk, and
+/// compare the MD5 with `etag`. A mismatch is discarded and fetched again up to `retries`
+/// times before it fails the download.
+async fn fetch_verified<F, Fut>(
+    remote_path: &str,
+    etag: Option<&str>,
+    retries: u32,
+    fetch: F,
+) -> Result<PartFile>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<PartFile>>,
+{
+    let Some(expected) = etag.and_then(etag_md5) else {
+        eprintln!("Note: {remote_path} has no MD5 ETag (multipart upload?); not verified");
+        return fetch().await;
+    };
+    let (fetch, expected) = (&fetch, expected.as_str());
+    retry_on_checksum_mismatch(remote_path, retries, || async move {
+        let part = fetch().await?;
+        let actual = part.md5.clone().unwrap_or_default();
+        if actual != expected {
+            part.discard().await?;
+            return ChecksumMismatchSnafu {
+                path: remote_path.to_string(),
+                expected,
+                actual,
+            }
+            .fail();
+        }
+        Ok(part)
+    })
+    .await
+}
+
+/// Size of an existing local file, or `None` if there is no regular file at `path`.
+async fn local_size(path: &Path) -> Result<Option<u64>> {
+    match fs::metadata(path).await {
+        Ok(meta) if meta.is_file() => Ok(Some(meta.len())),
+        Ok(_) => Ok(None),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Hard-link `target` to an already downloaded file with the same content, falling back
+/// to a copy on filesystems without hard link support.
+async fn link_or_copy(original: &Path, target: &Path) -> Result<()> {
+    if fs::try_exists(target).await? {
+        fs::remove_file(target).await?;
+    }
+    if let Err(err) = fs::hard_link(original, target).await {
+        log::debug!("hard link failed ({err}); copying {}", original.display());
+        fs::copy(original, target).await?;
+    }
+    Ok(())
 }
 
 impl Downloader for OpenDalDownloader {
-    async fn download(&self, remote_path: &str, local_path: &str) -> Result<()> {
-        if !self.operator.exists(remote_path).await? {
+    async fn download(
+        &self,
+        remote_path: &str,
+        local_path: &str,
+        options: &DownloadOptions,
+    ) -> Result<TransferStats> {
+        let started = Instant::now();
+        if let Some(version_id) = &options.version_id {
+            let result = self
+                .download_version(remote_path, local_path, version_id, options)
+                .await
+                .map(Some);
+            let bytes = log_transfer(
+                options.transfer_log.as_ref(),
+                TransferOperation::Get,
+                remote_path,
+                started,
+                result,
+            )?
+            .unwrap_or_default();
+            let mut stats = TransferStats::default();
+            stats.add_file(bytes);
+            return Ok(stats.finish(started));
+        }
+
+        // Checked before anything is created locally, so a typo leaves no empty directory.
+        if !path_exists(&self.operator, remote_path).await? {
+            if options.lenient {
+                eprintln!("Note: {remote_path} does not exist; nothing to download");
+                return Ok(TransferStats::default().finish(started));
+            }
             return Err(Error::PathNotFound {
                 path: PathBuf::from(remote_path),
             });
@@ -44,35 +436,316 @@ impl Downloader for OpenDalDownloader {
             .recursive(true)
             .await?;
 
-        let mut stream = lister;
-        while let Some(entry) = stream.try_next().await? {
+        // Content hash → first local file written with that content. Linking needs the
+        // original on disk already, so deduplicated downloads run one at a time.
+        let seen_content = options.dedupe.then(|| Mutex::new(HashMap::new()));
+        let concurrency = if options.dedupe {
+            if options.concurrency != Concurrency::default() {
+                eprintln!("Warning: --dedupe downloads one file at a time; ignoring --concurrency");
+            }
+            Concurrency::SERIAL
+        } else {
+            options.concurrency
+        };
+        let mut controller = AimdController::new(concurrency);
+        let mut breaker = CircuitBreaker::new(options.circuit_breaker);
+        let health = EndpointHealth::new(options.health_check);
+        let health = &health;
+        let mut failures = FailureTracker::new(options.on_error);
+        let out_of_range = Cell::new(0usize);
+        let out_of_range = &out_of_range;
+        let requested_path = remote_path.trim_matches('/');
+
+        // Directories are created as they are listed; files go to the transfer stream.
+        let files = lister.map_err(Error::from).try_filter_map(|entry| {
             let meta = entry.metadata();
             let remote_file_path = entry.path();
-            let mut relative_path = get_root_relative_path(remote_file_path, remote_path);
-            if relative_path.is_empty() {
-                // Fallback: use base name
-                relative_path = Path::new(remote_file_path)
-                    .file_name()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .unwrap_or_default();
-            }
+            let relative_path = get_root_relative_path(remote_file_path, remote_path);
             let local_file_path = Path::new(local_path).join(relative_path);
-
-            if meta.mode() == EntryMode::DIR {
-                fs::create_dir_all(&local_file_path).await?;
-            } else {
-                if let Some(parent) = local_file_path.parent() {
-                    fs::create_dir_all(parent).await?;
+            let is_dir = meta.mode() == EntryMode::DIR;
+            // A single requested object is fetched whatever its size.
+            let banded = options.size_range.is_bounded()
+                && remote_file_path.trim_matches('/') != requested_path;
+            let mut job = (!is_dir).then(|| FileJob {
+                remote_path: remote_file_path.to_string(),
+                local_path: local_file_path.clone(),
+                size: meta.content_length(),
+                etag: meta.etag().map(str::to_string),
+            });
+            async move {
+                ensure_exact_key(&self.operator, entry.path(), options.strict_utf8).await?;
+                if is_dir {
+                    fs::create_dir_all(&local_file_path).await?;
                 }
-                let data = self.operator.read(remote_file_path).await?;
-                fs::write(&local_file_path, data.to_vec()).await?;
-                println!(
-                    "Downloaded: {remote_file_path} → {}",
-                    local_file_path.display()
-                );
+                // Only size checks need a size the listing may have left out.
+                if let Some(file) = &mut job
+                    && (options.if_size_differs || banded)
+                {
+                    file.size = listed_size(&self.operator, &entry).await?;
+                    if banded && !options.size_range.contains(file.size) {
+                        out_of_range.set(out_of_range.get() + 1);
+                        return Ok(None);
+                    }
+                }
+                Ok(job)
             }
-        }
+        });
 
-        Ok(())
+        // Listing keeps going while every transfer slot is busy, so a long prefix neither
+        // delays the first file nor stalls between transfers.
+        let (listing, files) = read_ahead(files, DOWNLOAD_LIST_AHEAD);
+
+        let seen_content = seen_content.as_ref();
+        let stats = Cell::new(TransferStats::default());
+        let stats_ref = &stats;
+        let transfers = run_bounded(
+            files,
+            &mut controller,
+            &mut breaker,
+            |job: FileJob| async move {
+                let file_started = Instant::now();
+                let result = options
+                    .on_error
+                    .run(&job.remote_path, || {
+                        health.guard(
+                            || self.endpoint_answers(),
+                            || {
+                                self.download_file(
+                                    &job.remote_path,
+                                    &job.local_path,
+                                    job.size,
+                                    job.etag.as_deref(),
+                                    seen_content,
+                                    options,
+                                )
+                            },
+                        )
+                    })
+                    .await;
+                let result = log_transfer(
+                    options.transfer_log.as_ref(),
+                    TransferOperation::Get,
+                    &job.remote_path,
+                    file_started,
+                    result,
+                );
+                if let Ok(Some(bytes)) = result {
+                    let mut totals = stats_ref.get();
+                    totals.add_file(bytes);
+                    stats_ref.set(totals);
+                }
+                (job.remote_path, result.map(Option::unwrap_or_default))
+            },
+            |path, result| failures.record(&path, result.map(|_| ())),
+        );
+        let transferred = future::join(listing, transfers).await.1;
+        report_out_of_range(out_of_range.get());
+        transferred?;
+
+        failures.finish()?;
+        Ok(stats.get().finish(started))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::utils::checksum::checksum;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+
+    #[test]
+    fn test_etag_md5_only_accepts_plain_digests() {
+        assert_eq!(
+            etag_md5("\"900150983CD24FB0D6963F7D28E17F72\"").as_deref(),
+            Some("900150983cd24fb0d6963f7d28e17f72")
+        );
+        assert_eq!(etag_md5("\"900150983cd24fb0d6963f7d28e17f72-3\""), None);
+        assert_eq!(etag_md5("1a2b"), None);
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_corrupted_download() {
+        let dir = TempDir::new();
+        let target = dir.join("abc.txt");
+        let digests = Digests {
+            md5: true,
+            sha256: false,
+        };
+        let etag = "\"900150983cd24fb0d6963f7d28e17f72\"";
+        let fetches = Cell::new(0);
+        // The first transfer has one byte flipped; the second arrives intact.
+        let flaky = || {
+            fetches.set(fetches.get() + 1);
+            let body: &'static [u8] = if fetches.get() == 1 { b"abd" } else { b"abc" };
+            write_part(body, &target, digests)
+        };
+        let part = fetch_verified("dir/abc.txt", Some(etag), 1, flaky)
+            .await
+            .unwrap();
+        assert_eq!(part.persist(&target).await.unwrap(), 3);
+        assert_eq!(fs::read(&target).await.unwrap(), b"abc");
+        assert_eq!(fetches.get(), 2);
+
+        fetches.set(0);
+        let corrupt = || {
+            fetches.set(fetches.get() + 1);
+            write_part(&b"abd"[..], &target, digests)
+        };
+        let err = fetch_verified("dir/abc.txt", Some(etag), 2, corrupt)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ChecksumMismatch { .. }));
+        assert_eq!(fetches.get(), 3);
+        // Rejected copies are discarded; the good one stays.
+        assert!(!partial_path(&target).exists());
+        assert_eq!(fs::read(&target).await.unwrap(), b"abc");
+    }
+
+    #[tokio::test]
+    async fn test_write_part_streams_in_buffers() {
+        let dir = TempDir::new();
+        let target = dir.join("big.bin");
+        // Several buffers' worth, ending partway through one.
+        let content: Vec<u8> = (0..DEFAULT_BUFFER_SIZE * 3 + 7)
+            .map(|i| (i % 256) as u8)
+            .collect();
+        let digests = Digests {
+            md5: true,
+            sha256: true,
+        };
+        let part = write_part(content.as_slice(), &target, digests)
+            .await
+            .unwrap();
+        assert_eq!(part.bytes, content.len() as u64);
+        assert_eq!(
+            part.md5.as_deref(),
+            Some(checksum(ChecksumAlgorithm::Md5, &content).as_str())
+        );
+        assert_eq!(
+            part.sha256.as_deref(),
+            Some(checksum(ChecksumAlgorithm::Sha256, &content).as_str())
+        );
+        part.persist(&target).await.unwrap();
+        assert_eq!(fs::read(&target).await.unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_failed_download_keeps_previous_copy() {
+        let root = TempDir::new();
+        let local_dir = root.join("local");
+        fs::create_dir_all(&local_dir).await.unwrap();
+        let operator = fs_operator(&root.join("remote"));
+        operator.write("dir/data.txt", "new copy").await.unwrap();
+
+        let target = local_dir.join("data.txt");
+        fs::write(&target, "good copy").await.unwrap();
+        // A directory in the part file's place makes the write fail partway.
+        let partial = partial_path(&target);
+        fs::create_dir_all(&partial).await.unwrap();
+
+        let downloader = OpenDalDownloader::new(operator);
+        let local = local_dir.to_string_lossy();
+        let options = DownloadOptions::default();
+        assert!(downloader.download("dir/", &local, &options).await.is_err());
+        assert_eq!(fs::read(&target).await.unwrap(), b"good copy");
+
+        fs::remove_dir(&partial).await.unwrap();
+        downloader.download("dir/", &local, &options).await.unwrap();
+        assert_eq!(fs::read(&target).await.unwrap(), b"new copy");
+        assert!(!partial.exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_returns_transfer_stats() {
+        let root = TempDir::new();
+        let operator = fs_operator(&root.join("remote"));
+        operator.write("dir/a.txt", "12345").await.unwrap();
+        operator
+            .write("dir/sub/b.txt", vec![0u8; 100])
+            .await
+            .unwrap();
+
+        let downloader = OpenDalDownloader::new(operator);
+        let local = root.join("local");
+        let local = local.to_string_lossy();
+        let stats = downloader
+            .download("dir/", &local, &DownloadOptions::default())
+            .await
+            .unwrap();
+        assert_eq!((stats.files, stats.bytes), (2, 105));
+
+        // Skipped files are not counted as transferred.
+        let options = DownloadOptions {
+            if_size_differs: true,
+            ..Default::default()
+        };
+        let stats = downloader.download("dir/", &local, &options).await.unwrap();
+        assert_eq!((stats.files, stats.bytes), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_if_size_differs_sizes_files_the_listing_does_not() {
+        // Fs listings carry no sizes, so only a stat tells these files apart.
+        let root = TempDir::new();
+        let operator = fs_operator(&root.join("remote"));
+        operator.write("dir/a.txt", "12345").await.unwrap();
+        let local_dir = root.join("local");
+        fs::create_dir_all(&local_dir).await.unwrap();
+        fs::write(local_dir.join("a.txt"), "").await.unwrap();
+
+        let downloader = OpenDalDownloader::new(operator);
+        let local = local_dir.to_string_lossy();
+        let options = DownloadOptions {
+            if_size_differs: true,
+            ..Default::default()
+        };
+        let stats = downloader.download("dir/", &local, &options).await.unwrap();
+        assert_eq!((stats.files, stats.bytes), (1, 5));
+        assert_eq!(fs::read(local_dir.join("a.txt")).await.unwrap(), b"12345");
+
+        let stats = downloader.download("dir/", &local, &options).await.unwrap();
+        assert_eq!((stats.files, stats.bytes), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_size_band_sizes_listed_files_and_spares_a_requested_object() {
+        let root = TempDir::new();
+        let operator = fs_operator(&root.join("remote"));
+        operator
+            .write("dir/small.bin", vec![1u8; 100])
+            .await
+            .unwrap();
+        operator
+            .write("dir/fits.bin", vec![2u8; 2048])
+            .await
+            .unwrap();
+        operator
+            .write("dir/large.bin", vec![3u8; 5000])
+            .await
+            .unwrap();
+
+        let downloader = OpenDalDownloader::new(operator);
+        let local_dir = root.join("local");
+        let local = local_dir.to_string_lossy();
+        let options = DownloadOptions {
+            size_range: SizeRange {
+                min: Some(1024),
+                max: Some(4096),
+            },
+            ..Default::default()
+        };
+        let stats = downloader.download("dir/", &local, &options).await.unwrap();
+        assert_eq!((stats.files, stats.bytes), (1, 2048));
+        assert!(local_dir.join("fits.bin").exists());
+        assert!(!local_dir.join("small.bin").exists());
+        assert!(!local_dir.join("large.bin").exists());
+
+        // An object named outright is fetched even though it is outside the band.
+        let stats = downloader
+            .download("dir/small.bin", &local, &options)
+            .await
+            .unwrap();
+        assert_eq!((stats.files, stats.bytes), (1, 100));
+        assert!(local_dir.join("small.bin").exists());
     }
 }
@@ -0,0 +1,147 @@
+// Recursive search for objects matching metadata predicates
+use crate::error::{Error, Result};
+use crate::storage::operations::stat::listed_size;
+use crate::storage::utils::path::escape_control_chars;
+use crate::storage::utils::time::{TimeRange, report_undated};
+use futures::stream::TryStreamExt;
+use opendal::{Entry, Operator};
+use std::borrow::Cow;
+use std::io::Write;
+
+/// Predicates an object must satisfy to be reported by `find`.
+#[derive(Debug, Clone, Default)]
+pub struct FindOptions {
+    /// Only zero-byte objects (directory markers are never reported)
+    pub empty: bool,
+    /// Only objects modified inside this window
+    pub time_range: TimeRange,
+    /// Separate keys with NUL bytes instead of newlines (for `xargs -0`)
+    pub null_separated: bool,
+}
+
+impl FindOptions {
+    /// Whether an entry satisfies every requested predicate. Listings that carry no sizes
+    /// report every file as empty, so a file listed as empty is confirmed with a stat.
+    async fn matches(&self, operator: &Operator, entry: &Entry) -> Result<bool> {
+        let meta = entry.metadata();
+        if meta.is_dir() || !self.time_range.contains(meta.last_modified()) {
+            return Ok(false);
+        }
+        Ok(!self.empty || listed_size(operator, entry).await? == 0)
+    }
+
+    /// Character printed after each key.
+    fn terminator(&self) -> char {
+        if self.null_separated { '\0' } else { '\n' }
+    }
+}
+
+/// Trait for finding objects under a prefix.
+pub trait Finder {
+    /// Recursively walk a prefix and print the objects matching the options.
+    ///
+    /// # Arguments
+    /// * `path` - Prefix to search
+    /// * `options` - Predicates objects must satisfy
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success or detailed error information
+    async fn find(&self, path: &str, options: &FindOptions) -> Result<()>;
+}
+
+/// Implementation of Finder for OpenDAL Operator.
+pub struct OpenDalFinder {
+    operator: Operator,
+}
+
+impl OpenDalFinder {
+    /// Create a new finder with the given OpenDAL operator.
+    pub fn new(operator: Operator) -> Self {
+        Self { operator }
+    }
+
+    /// Write the key of every matching object under `path` to `out`. Keys are escaped
+    /// onto one line when newline-separated, and passed through untouched with NULs.
+    async fn find_to(&self, path: &str, options: &FindOptions, out: &mut impl Write) -> Result<()> {
+        let mut lister = self
+            .operator
+            .lister_with(path)
+            .recursive(true)
+            .await?
+            .map_err(Error::from);
+        let mut undated = 0usize;
+        while let Some(entry) = lister.try_next().await? {
+            if options.matches(&self.operator, &entry).await? {
+                let key = if options.null_separated {
+                    Cow::Borrowed(entry.path())
+                } else {
+                    escape_control_chars(entry.path())
+                };
+                write!(out, "{key}{}", options.terminator())?;
+            } else if !options.time_range.is_unbounded()
+                && !entry.metadata().is_dir()
+                && entry.metadata().last_modified().is_none()
+            {
+                undated += 1;
+            }
+        }
+        out.flush()?;
+        report_undated(undated);
+        Ok(())
+    }
+}
+
+impl Finder for OpenDalFinder {
+    async fn find(&self, path: &str, options: &FindOptions) -> Result<()> {
+        self.find_to(path, options, &mut std::io::stdout().lock())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+
+    #[tokio::test]
+    async fn test_empty_confirms_sizes_the_listing_does_not_carry() {
+        // Fs listings report every file as zero bytes, so only a stat tells these apart.
+        let dir = TempDir::new();
+        let operator = fs_operator(dir.path());
+        operator.write("logs/empty.txt", "").await.unwrap();
+        operator.write("logs/full.txt", "12345").await.unwrap();
+
+        let options = FindOptions {
+            empty: true,
+            ..Default::default()
+        };
+        let mut found = Vec::new();
+        for entry in operator.list_with("logs/").recursive(true).await.unwrap() {
+            if options.matches(&operator, &entry).await.unwrap() {
+                found.push(entry.path().to_string());
+            }
+        }
+        assert_eq!(found, ["logs/empty.txt"]);
+    }
+
+    #[tokio::test]
+    async fn test_null_separates_keys_with_spaces() {
+        let dir = TempDir::new();
+        let operator = fs_operator(dir.path());
+        operator.write("logs/a b.txt", "").await.unwrap();
+        operator.write("logs/c.txt", "").await.unwrap();
+        let finder = OpenDalFinder::new(operator);
+
+        let options = FindOptions {
+            null_separated: true,
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        finder.find_to("logs/", &options, &mut out).await.unwrap();
+        let mut keys: Vec<&[u8]> = out.split(|&b| b == 0).collect();
+        // Every key, the last included, ends with a NUL.
+        assert_eq!(keys.pop(), Some(&b""[..]));
+        keys.sort();
+        assert_eq!(keys, [&b"logs/a b.txt"[..], b"logs/c.txt"]);
+    }
+}
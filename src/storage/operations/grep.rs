@@ -0,0 +1,167 @@
+// Line-oriented search over remote text objects, streamed without touching local disk
+use crate::error::{InvalidPatternSnafu, Result};
+use crate::storage::operations::stat::stat_if_exists;
+use crate::storage::utils::error::{map_io_read_error, map_read_error};
+use futures::stream::TryStreamExt;
+use opendal::Operator;
+use regex::{Regex, RegexBuilder};
+use snafu::ResultExt;
+
+/// Options controlling how `grep` matches and what it prints.
+#[derive(Debug, Clone, Default)]
+pub struct GrepOptions {
+    /// Search every object under the prefix, not just its direct children
+    pub recursive: bool,
+    /// Match without regard to case
+    pub ignore_case: bool,
+    /// Print only the keys of objects containing a match
+    pub files_with_matches: bool,
+}
+
+impl GrepOptions {
+    /// Compile `pattern` with these options.
+    pub fn regex(&self, pattern: &str) -> Result<Regex> {
+        RegexBuilder::new(pattern)
+            .case_insensitive(self.ignore_case)
+            .build()
+            .context(InvalidPatternSnafu { pattern })
+    }
+}
+
+/// Trait for searching the contents of objects in storage.
+pub trait Grepper {
+    /// Print the lines of each object under `path` that match `regex`, prefixed with the
+    /// object key.
+    ///
+    /// # Arguments
+    /// * `path` - Object key, or prefix whose objects are searched
+    /// * `regex` - Compiled pattern to search for
+    /// * `options` - Traversal and output options
+    ///
+    /// # Returns
+    /// * `Result<bool>` - Whether any line matched, or detailed error information
+    async fn grep(&self, path: &str, regex: &Regex, options: &GrepOptions) -> Result<bool>;
+}
+
+/// Implementation of Grepper for OpenDAL Operator.
+pub struct OpenDalGrepper {
+    operator: Operator,
+}
+
+impl OpenDalGrepper {
+    /// Create a new grepper with the given OpenDAL operator.
+    pub fn new(operator: Operator) -> Self {
+        Self { operator }
+    }
+
+    /// Stream one object and print its matching lines. Objects whose first chunk
+    /// contains a NUL byte are treated as binary and skipped.
+    async fn grep_object(&self, path: &str, regex: &Regex, options: &GrepOptions) -> Result<bool> {
+        let reader = self
+            .operator
+            .reader(path)
+            .await
+            .map_err(|e| map_read_error(e, path))?;
+        let mut chunks = reader
+            .into_bytes_stream(..)
+            .await
+            .map_err(|e| map_read_error(e, path))?
+            .map_err(|e| map_io_read_error(e, path));
+        let mut pending: Vec<u8> = Vec::new();
+        let mut first_chunk = true;
+        let mut matched = false;
+
+        while let Some(chunk) = chunks.try_next().await? {
+            if first_chunk && chunk.contains(&0) {
+                log::debug!("grep skipping binary object {path}");
+                return Ok(false);
+            }
+            first_chunk = false;
+            pending.extend_from_slice(&chunk);
+
+            let mut start = 0;
+            while let Some(offset) = pending[start..].iter().position(|&b| b == b'\n') {
+                let line = &pending[start..start + offset];
+                start += offset + 1;
+                if let Some(text) = matching_line(regex, line) {
+                    matched = true;
+                    if options.files_with_matches {
+                        println!("{path}");
+                        return Ok(true);
+                    }
+                    println!("{path}:{text}");
+                }
+            }
+            pending.drain(..start);
+        }
+
+        // A final line without a trailing newline.
+        if let Some(text) = matching_line(regex, &pending) {
+            matched = true;
+            if options.files_with_matches {
+                println!("{path}");
+            } else {
+                println!("{path}:{text}");
+            }
+        }
+        Ok(matched)
+    }
+}
+
+/// The line as text if it matches; invalid UTF-8 is replaced rather than rejected.
+fn matching_line(regex: &Regex, line: &[u8]) -> Option<String> {
+    if line.is_empty() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(line);
+    let text = text.strip_suffix('\r').unwrap_or(&text);
+    regex.is_match(text).then(|| text.to_string())
+}
+
+impl Grepper for OpenDalGrepper {
+    async fn grep(&self, path: &str, regex: &Regex, options: &GrepOptions) -> Result<bool> {
+        if let Some(meta) = stat_if_exists(&self.operator, path).await?
+            && !meta.is_dir()
+        {
+            return self.grep_object(path, regex, options).await;
+        }
+
+        let mut lister = self
+            .operator
+            .lister_with(path)
+            .recursive(options.recursive)
+            .await?;
+        let mut matched = false;
+        while let Some(entry) = lister.try_next().await? {
+            if entry.metadata().is_dir() {
+                continue;
+            }
+            matched |= self.grep_object(entry.path(), regex, options).await?;
+        }
+        Ok(matched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_line() {
+        let options = GrepOptions {
+            ignore_case: true,
+            ..Default::default()
+        };
+        let regex = options.regex("error").unwrap();
+        assert_eq!(
+            matching_line(&regex, b"2024 ERROR disk full\r"),
+            Some("2024 ERROR disk full".to_string())
+        );
+        assert_eq!(matching_line(&regex, b"2024 INFO ok"), None);
+        assert_eq!(matching_line(&regex, b""), None);
+
+        let strict = GrepOptions::default().regex("error").unwrap();
+        assert_eq!(matching_line(&strict, b"ERROR"), None);
+        assert!(GrepOptions::default().regex("(unclosed").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+// Printing the start or end of an object with ranged reads, without fetching all of it
+use crate::error::{InvalidLimitSnafu, NotAFileSnafu, Result};
+use crate::storage::constants::LINE_SCAN_CHUNK_SIZE;
+use crate::storage::operations::cat::write_chunk;
+use crate::storage::utils::error::map_read_error;
+use opendal::Operator;
+use snafu::ensure;
+use std::io::Write;
+
+/// Lines printed when neither a byte nor a line count is given, as with head(1)/tail(1).
+const DEFAULT_LINES: usize = 10;
+
+/// How much of an object `head` or `tail` prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeekLimit {
+    Bytes(u64),
+    Lines(usize),
+}
+
+impl PeekLimit {
+    /// Build the limit from `-c`/`-n`, defaulting to ten lines. Zero counts are rejected.
+    pub fn new(bytes: Option<u64>, lines: Option<usize>) -> Result<Self> {
+        ensure!(
+            bytes != Some(0) && lines != Some(0),
+            InvalidLimitSnafu {
+                reason: "byte and line counts must be greater than zero"
+            }
+        );
+        Ok(match (bytes, lines) {
+            (Some(bytes), _) => Self::Bytes(bytes),
+            (None, lines) => Self::Lines(lines.unwrap_or(DEFAULT_LINES)),
+        })
+    }
+}
+
+/// Trait for printing part of an object in storage.
+pub trait Peeker {
+    /// Print the first bytes or lines of the object at `path` to stdout.
+    ///
+    /// # Arguments
+    /// * `path` - Key of the object to print
+    /// * `limit` - How many bytes or lines to print
+    ///
+    /// # Returns
+    /// * `Result<u64>` - Bytes written, or detailed error information
+    async fn head(&self, path: &str, limit: PeekLimit) -> Result<u64>;
+
+    /// Print the last bytes or lines of the object at `path` to stdout.
+    ///
+    /// # Arguments
+    /// * `path` - Key of the object to print
+    /// * `limit` - How many bytes or lines to print
+    ///
+    /// # Returns
+    /// * `Result<u64>` - Bytes written, or detailed error information
+    async fn tail(&self, path: &str, limit: PeekLimit) -> Result<u64>;
+}
+
+/// Implementation of Peeker for OpenDAL Operator.
+pub struct OpenDalPeeker {
+    operator: Operator,
+}
+
+impl OpenDalPeeker {
+    /// Create a new peeker with the given OpenDAL operator.
+    pub fn new(operator: Operator) -> Self {
+        Self { operator }
+    }
+
+    /// The object's size, rejecting directories.
+    async fn content_length(&self, path: &str) -> Result<u64> {
+        let meta = self.operator.stat(path).await?;
+        ensure!(
+            !meta.is_dir(),
+            NotAFileSnafu {
+                path: path.to_string()
+            }
+        );
+        Ok(meta.content_length())
+    }
+
+    async fn read_range(&self, path: &str, start: u64, end: u64) -> Result<Vec<u8>> {
+        if start >= end {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .operator
+            .read_with(path)
+            .range(start..end)
+            .await
+            .map_err(|e| map_read_error(e, path))?
+            .to_vec())
+    }
+
+    async fn head_to(&self, path: &str, limit: PeekLimit, out: &mut impl Write) -> Result<u64> {
+        let len = self.content_length(path).await?;
+        let mut remaining = match limit {
+            PeekLimit::Bytes(bytes) => {
+                let data = self.read_range(path, 0, bytes.min(len)).await?;
+                write_chunk(out, &data)?;
+                return Ok(data.len() as u64);
+            }
+            PeekLimit::Lines(lines) => lines,
+        };
+
+        // Fetch a chunk at a time until enough line endings have gone by.
+        let mut offset = 0;
+        while offset < len && remaining > 0 {
+            let end = (offset + LINE_SCAN_CHUNK_SIZE as u64).min(len);
+            let data = self.read_range(path, offset, end).await?;
+            let keep = take_lines(&data, &mut remaining);
+            if !write_chunk(out, &data[..keep])? {
+                break;
+            }
+            offset += keep as u64;
+        }
+        Ok(offset)
+    }
+
+    async fn tail_to(&self, path: &str, limit: PeekLimit, out: &mut impl Write) -> Result<u64> {
+        let len = self.content_length(path).await?;
+        let lines = match limit {
+            PeekLimit::Bytes(bytes) => {
+                let data = self
+                    .read_range(path, len.saturating_sub(bytes), len)
+                    .await?;
+                write_chunk(out, &data)?;
+                return Ok(data.len() as u64);
+            }
+            PeekLimit::Lines(lines) => lines,
+        };
+
+        // Fetch chunks backwards from the end until the wanted lines are all in hand.
+        let mut start = len;
+        let mut data = Vec::new();
+        let keep_from = loop {
+            if start == 0 {
+                break 0;
+            }
+            let chunk_start = start.saturating_sub(LINE_SCAN_CHUNK_SIZE as u64);
+            let mut chunk = self.read_range(path, chunk_start, start).await?;
+            chunk.extend_from_slice(&data);
+            data = chunk;
+            start = chunk_start;
+            if let Some(index) = last_lines_start(&data, lines) {
+                break index;
+            }
+        };
+        write_chunk(out, &data[keep_from..])?;
+        Ok((data.len() - keep_from) as u64)
+    }
+}
+
+impl Peeker for OpenDalPeeker {
+    async fn head(&self, path: &str, limit: PeekLimit) -> Result<u64> {
+        self.head_to(path, limit, &mut std::io::stdout()).await
+    }
+
+    async fn tail(&self, path: &str, limit: PeekLimit) -> Result<u64> {
+        self.tail_to(path, limit, &mut std::io::stdout()).await
+    }
+}
+
+/// How many leading bytes of `data` to print while `remaining` lines are still wanted,
+/// counting down `remaining` for each line ending passed. Splitting on the `\n` byte is
+/// safe for UTF-8 text: it never occurs inside a multi-byte character.
+fn take_lines(data: &[u8], remaining: &mut usize) -> usize {
+    for (index, _) in data.iter().enumerate().filter(|(_, byte)| **byte == b'\n') {
+        *remaining -= 1;
+        if *remaining == 0 {
+            return index + 1;
+        }
+    }
+    data.len()
+}
+
+/// Where the last `lines` lines of `data` begin, or `None` if `data` may not hold them all
+/// yet. A trailing newline ends the last line rather than starting an empty one.
+fn last_lines_start(data: &[u8], lines: usize) -> Option<usize> {
+    let body = data.strip_suffix(b"\n").unwrap_or(data);
+    body.iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, byte)| **byte == b'\n')
+        .nth(lines - 1)
+        .map(|(index, _)| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+
+    #[test]
+    fn test_peek_limit_defaults_and_rejects_zero() {
+        assert_eq!(PeekLimit::new(None, None).unwrap(), PeekLimit::Lines(10));
+        assert_eq!(PeekLimit::new(Some(5), None).unwrap(), PeekLimit::Bytes(5));
+        assert_eq!(PeekLimit::new(None, Some(3)).unwrap(), PeekLimit::Lines(3));
+        assert!(matches!(
+            PeekLimit::new(Some(0), None),
+            Err(Error::InvalidLimit { .. })
+        ));
+        assert!(matches!(
+            PeekLimit::new(None, Some(0)),
+            Err(Error::InvalidLimit { .. })
+        ));
+    }
+
+    #[test]
+    fn test_line_boundaries() {
+        let mut remaining = 2;
+        assert_eq!(take_lines(b"a\nb\nc\n", &mut remaining), 4);
+        assert_eq!(remaining, 0);
+        let mut remaining = 5;
+        assert_eq!(take_lines(b"a\nb", &mut remaining), 3);
+        assert_eq!(remaining, 4);
+
+        assert_eq!(last_lines_start(b"a\nb\nc\n", 2), Some(2));
+        assert_eq!(last_lines_start(b"a\nb\nc", 1), Some(4));
+        assert_eq!(last_lines_start(b"a\nb\n", 2), None);
+    }
+
+    #[tokio::test]
+    async fn test_head_and_tail_across_chunks() {
+        let root = TempDir::new();
+        let operator = fs_operator(root.path());
+        let peeker = OpenDalPeeker::new(operator.clone());
+
+        // Enough lines that line scans need several ranged reads in both directions.
+        let lines: Vec<String> = (0..20_000).map(|i| format!("line {i}")).collect();
+        let content = lines.join("\n") + "\n";
+        operator
+            .write("logs/app.log", content.clone())
+            .await
+            .unwrap();
+
+        let run = async |tail: bool, limit: PeekLimit| {
+            let mut out = Vec::new();
+            if tail {
+                peeker
+                    .tail_to("logs/app.log", limit, &mut out)
+                    .await
+                    .unwrap();
+            } else {
+                peeker
+                    .head_to("logs/app.log", limit, &mut out)
+                    .await
+                    .unwrap();
+            }
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(run(false, PeekLimit::Bytes(6)).await, "line 0");
+        assert_eq!(run(true, PeekLimit::Bytes(6)).await, "19999\n");
+        assert_eq!(run(false, PeekLimit::Lines(2)).await, "line 0\nline 1\n");
+        assert_eq!(
+            run(false, PeekLimit::Lines(15_000)).await,
+            lines[..15_000].join("\n") + "\n"
+        );
+        assert_eq!(
+            run(true, PeekLimit::Lines(15_000)).await,
+            lines[5_000..].join("\n") + "\n"
+        );
+        assert_eq!(run(true, PeekLimit::Lines(50_000)).await, content);
+        assert_eq!(run(false, PeekLimit::Bytes(1 << 30)).await, content);
+
+        let err = peeker
+            .head_to("logs/", PeekLimit::Lines(1), &mut Vec::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NotAFile { .. }));
+    }
+}
@@ -1,9 +1,107 @@
 use crate::error::Result;
+use crate::storage::constants::{DEFAULT_LIST_PAGE_SIZE, DEFAULT_STAT_CONCURRENCY};
+use crate::storage::operations::stat::{ensure_exact_key, listed_size};
 use crate::storage::utils::error::IntoStorifyError;
+use crate::storage::utils::json::JsonStyle;
+use crate::storage::utils::path::{basename, escape_control_chars};
+use crate::storage::utils::sort::{SortKey, natural_cmp};
+use crate::storage::utils::template::Template;
+use crate::storage::utils::time::{TimeRange, TimeStyle, report_undated};
 use crate::wrap_err;
-use futures::stream::TryStreamExt;
-use opendal::Operator;
+use chrono::{DateTime, Utc};
+use futures::future;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use opendal::{EntryMode, ErrorKind, Metadata, Operator, Scheme};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::future::Future;
+use std::num::NonZeroUsize;
+
+/// Layout of listing entries when no template is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ListFormat {
+    /// Paths, or aligned columns with `-L`
+    #[default]
+    Text,
+    /// `type<TAB>size<TAB>mtime<TAB>path` with byte sizes and RFC3339 times
+    Tsv,
+    /// One JSON array of `{path, size, modified, is_dir}` objects
+    Json,
+}
+
+/// Options controlling what a listing includes and how entries are rendered.
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Show detailed information (long format)
+    pub long: bool,
+    /// List directories recursively
+    pub recursive: bool,
+    /// Timestamp rendering used by the long format
+    pub time_style: TimeStyle,
+    /// Collect and sort entries before printing; `None` streams in backend order
+    pub sort: Option<SortKey>,
+    /// Custom per-entry output; overrides the short and long formats
+    pub template: Option<Template>,
+    /// Terminate entries with NUL instead of newline (for `xargs -0`)
+    pub null_separated: bool,
+    /// List every stored version of each object, with its version ID
+    pub versions: bool,
+    /// Only list objects modified inside this window; directories are left out when set
+    pub time_range: TimeRange,
+    /// Fill in metadata the listing lacks with concurrent `stat` calls (keeps entry order)
+    pub parallel_stat: bool,
+    /// Walk recursively and print per-directory totals instead of entries
+    pub recursive_summary: bool,
+    /// Entry layout used when no template is given
+    pub format: ListFormat,
+    /// Print bare keys only, ignoring the long format
+    pub keys_only: bool,
+    /// Print only the last path segment of each key (directories keep their `/`)
+    pub basename_only: bool,
+    /// In recursive listings, report and skip subdirectories that may not be listed
+    pub skip_denied: bool,
+    /// Print directory keys without their trailing `/` (the long format still says DIR)
+    pub strip_trailing_slash: bool,
+    /// Entries requested per backend listing call; `None` uses the default page size
+    pub page_size: Option<NonZeroUsize>,
+    /// Fail on keys that are not valid UTF-8 instead of printing them lossily
+    pub strict_utf8: bool,
+    /// Add each object's ETag (or stored Content-MD5) to the long format, fetching it with
+    /// concurrent `stat` calls where the listing has none
+    pub checksums: bool,
+    /// Print recursive listings as a header per directory followed by its children
+    pub grouped: bool,
+    /// Layout of `--format json` output
+    pub json_style: JsonStyle,
+}
+
+impl ListOptions {
+    /// Character printed after each entry.
+    fn terminator(&self) -> char {
+        if self.null_separated { '\0' } else { '\n' }
+    }
+
+    /// Entries asked for in each listing request.
+    fn page_size(&self) -> usize {
+        self.page_size
+            .map_or(DEFAULT_LIST_PAGE_SIZE, NonZeroUsize::get)
+    }
+}
+
+/// Placeholders available to listing templates.
+pub const LIST_TEMPLATE_FIELDS: &[&str] = &[
+    "type",
+    "size",
+    "human_size",
+    "mtime",
+    "path",
+    "etag",
+    "content_type",
+    "version",
+];
 
 /// Trait for listing directory contents in object storage.
 pub trait Lister {
@@ -11,12 +109,11 @@ pub trait Lister {
     ///
     /// # Arguments
     /// * `path` - Directory path to list
-    /// * `long` - Whether to show detailed information
-    /// * `recursive` - Whether to list recursively
+    /// * `options` - Listing and rendering options
     ///
     /// # Returns
     /// * `Result<()>` - Success or detailed error information
-    async fn list(&self, path: &str, long: bool, recursive: bool) -> Result<()>;
+    async fn list(&self, path: &str, options: &ListOptions) -> Result<()>;
 }
 
 /// Implementation of Lister for OpenDAL Operator.
@@ -30,68 +127,944 @@ impl OpenDalLister {
         Self { operator }
     }
 
+    /// Entry details from the listing, completed by a `stat` call when `complete_metadata`
+    /// is set and the listing left fields out. With `sized`, a file the listing gives no
+    /// size is stat'ed for one.
+    async fn file_info(
+        &self,
+        entry: opendal::Entry,
+        complete_metadata: bool,
+        sized: bool,
+    ) -> Result<FileInfo> {
+        let mut info = FileInfo::from_entry(&entry);
+        if complete_metadata && info.is_incomplete() {
+            let meta = self.operator.stat(entry.path()).await?;
+            return Ok(FileInfo::from_metadata(entry.path(), &meta));
+        }
+        if sized {
+            info.size = listed_size(&self.operator, &entry).await?;
+        }
+        Ok(info)
+    }
+
+    /// Whether a listing returns keys in lexicographic order, as object stores do.
+    /// Filesystems list in directory order, and a walk that lists one directory at a
+    /// time visits subdirectories after their parent's files.
+    fn lists_in_order(&self, walk_by_directory: bool) -> bool {
+        !walk_by_directory && !matches!(self.operator.info().scheme(), Scheme::Fs | Scheme::Hdfs)
+    }
+
     /// Print a single entry with optional detailed formatting.
-    fn print_entry(&self, entry: &opendal::Entry, long: bool) {
-        if long {
-            let file_info = FileInfo::from_entry(entry);
-            println!("{file_info}");
+    ///
+    /// Keys with control characters are escaped in newline-separated output so they cannot
+    /// break line-based consumers; NUL-separated output passes them through untouched,
+    /// except in TSV where a raw tab would shift the columns.
+    fn print_entry(&self, file_info: &FileInfo, options: &ListOptions) {
+        let tsv = options.format == ListFormat::Tsv;
+        let file_info = display_info(file_info, options);
+
+        let bare = options.keys_only || options.basename_only;
+        let line = if options.basename_only {
+            file_info.basename()
+        } else if options.keys_only {
+            file_info.path.clone()
+        } else if let Some(template) = &options.template {
+            file_info.render(template, options.time_style)
+        } else if tsv {
+            file_info.tsv()
+        } else if options.long {
+            file_info
+                .display(options.time_style, options.checksums)
+                .to_string()
         } else {
-            println!("{}", entry.path());
+            file_info.path.clone()
+        };
+        if options.versions && options.template.is_none() && !bare {
+            let version = file_info.version.as_deref().unwrap_or("null");
+            let separator = if tsv { '\t' } else { ' ' };
+            print!("{line}{separator}{version}{}", options.terminator());
+        } else {
+            print!("{line}{}", options.terminator());
         }
     }
 }
 
 impl Lister for OpenDalLister {
-    async fn list(&self, path: &str, long: bool, recursive: bool) -> Result<()> {
+    async fn list(&self, path: &str, options: &ListOptions) -> Result<()> {
+        let recursive = options.recursive || options.recursive_summary;
+        // One listing per directory, so a denied subtree fails on its own.
+        let walk_by_directory = recursive && options.skip_denied;
         let lister = wrap_err!(
-            self.operator.lister_with(path).recursive(recursive).await,
+            self.operator
+                .lister_with(path)
+                .recursive(recursive && !walk_by_directory)
+                .versions(options.versions)
+                .limit(options.page_size())
+                .await,
             ListDirectoryFailed {
                 path: path.to_string()
             }
         )?;
+        let lister = if walk_by_directory {
+            walk_skipping_denied(
+                lister,
+                path,
+                |dir| {
+                    let operator = self.operator.clone();
+                    async move {
+                        operator
+                            .lister_with(&dir)
+                            .versions(options.versions)
+                            .limit(options.page_size())
+                            .await
+                    }
+                },
+                |entry: &opendal::Entry| {
+                    entry
+                        .metadata()
+                        .mode()
+                        .is_dir()
+                        .then(|| entry.path().to_string())
+                },
+            )
+            .boxed_local()
+        } else {
+            lister.boxed_local()
+        };
 
-        lister
+        let undated = Cell::new(0usize);
+        let multipart = Cell::new(0usize);
+        let in_range = |file_info: &FileInfo| {
+            let range = &options.time_range;
+            if range.is_unbounded() {
+                return true;
+            }
+            if file_info.is_dir {
+                return false;
+            }
+            if file_info.modified.is_none() {
+                undated.set(undated.get() + 1);
+            }
+            range.contains(file_info.modified)
+        };
+        // `stat` describes the latest version only, so version listings keep their own data.
+        let complete_metadata = (options.parallel_stat || options.checksums) && !options.versions;
+        let concurrency = if complete_metadata {
+            DEFAULT_STAT_CONCURRENCY
+        } else {
+            1
+        };
+        let entries = lister
+            .map_err(crate::error::Error::from)
+            .map_ok(|entry| self.file_info(entry, complete_metadata, options.recursive_summary))
+            .try_buffered(concurrency)
             .map_err(|e| crate::error::Error::ListDirectoryFailed {
                 path: path.to_string(),
                 source: Box::new(e.into_error()),
             })
-            .try_for_each(|entry| async move {
-                self.print_entry(&entry, long);
-                Ok(())
+            .and_then(|file_info| async move {
+                ensure_exact_key(&self.operator, &file_info.path, options.strict_utf8).await?;
+                Ok(file_info)
             })
-            .await
+            .try_filter(|file_info| future::ready(in_range(file_info)));
+
+        let result = if options.recursive_summary {
+            let mut rollup = SubtreeRollup::new(path);
+            let mut add = |file_info: FileInfo| {
+                if !file_info.is_dir {
+                    for total in rollup.add(&file_info.path, file_info.size) {
+                        println!("{total}");
+                    }
+                }
+            };
+            let walked = if self.lists_in_order(walk_by_directory) {
+                entries
+                    .try_for_each(|file_info| {
+                        add(file_info);
+                        future::ready(Ok(()))
+                    })
+                    .await
+            } else {
+                // The rollup closes a directory at the first key outside it, so keys that
+                // arrive out of order are sorted first.
+                entries.try_collect::<Vec<_>>().await.map(|mut file_infos| {
+                    file_infos.sort_by(|a, b| a.path.cmp(&b.path));
+                    file_infos.into_iter().for_each(&mut add);
+                })
+            };
+            if walked.is_ok() {
+                for total in rollup.finish() {
+                    println!("{total}");
+                }
+            }
+            walked
+        } else {
+            let print = |file_info: &FileInfo| {
+                self.print_entry(file_info, options);
+                if options.checksums && file_info.has_multipart_etag() {
+                    multipart.set(multipart.get() + 1);
+                }
+            };
+            let json = options.format == ListFormat::Json;
+            match options.sort {
+                None if !options.grouped && !json => {
+                    entries
+                        .try_for_each(|file_info| {
+                            print(&file_info);
+                            future::ready(Ok(()))
+                        })
+                        .await
+                }
+                sort => {
+                    let mut file_infos: Vec<FileInfo> = entries.try_collect().await?;
+                    if let Some(key) = sort {
+                        sort_file_infos(&mut file_infos, key);
+                    }
+                    if json {
+                        let records: Vec<_> = file_infos
+                            .iter()
+                            .map(|file_info| display_info(file_info, options))
+                            .collect();
+                        let records: Vec<_> = records.iter().map(|info| info.record()).collect();
+                        println!("{}", options.json_style.render(&records)?);
+                    } else if options.grouped {
+                        let groups = group_by_parent(file_infos, path);
+                        for (index, (dir, children)) in groups.iter().enumerate() {
+                            if index > 0 {
+                                println!();
+                            }
+                            println!("{}:", if dir.is_empty() { "/" } else { dir });
+                            for child in children {
+                                print!("  ");
+                                print(child);
+                            }
+                        }
+                    } else {
+                        file_infos.iter().for_each(print);
+                    }
+                    Ok(())
+                }
+            }
+        };
+        report_undated(undated.get());
+        if multipart.get() > 0 {
+            eprintln!(
+                "Note: {} checksums are multipart ETags (a hash of the part hashes, not an MD5 of the content)",
+                multipart.get()
+            );
+        }
+        result
+    }
+}
+
+/// The entry as printed: control characters escaped where they would break the output,
+/// and directory keys without their trailing `/` when requested. Only the printed copy
+/// changes; traversal keeps working with the real keys.
+fn display_info<'a>(file_info: &'a FileInfo, options: &ListOptions) -> Cow<'a, FileInfo> {
+    let mut info = Cow::Borrowed(file_info);
+    let tsv = options.format == ListFormat::Tsv;
+    // JSON escapes control characters itself.
+    if options.format != ListFormat::Json
+        && let Cow::Owned(path) = escape_control_chars(&file_info.path)
+        && (!options.null_separated || tsv)
+    {
+        eprintln!("Warning: key contains control characters, shown escaped: {path}");
+        info = Cow::Owned(file_info.with_path(path));
+    }
+    if options.strip_trailing_slash
+        && info.is_dir
+        && let Some(path) = info.path.strip_suffix('/')
+        && !path.is_empty()
+    {
+        info = Cow::Owned(info.with_path(path.to_string()));
+    }
+    info
+}
+
+/// One directory of a [`walk_skipping_denied`] walk.
+struct WalkLevel<S> {
+    dir: String,
+    entries: S,
+    root: bool,
+}
+
+/// Recursive listing assembled from one listing per directory, in the same order as a
+/// flat recursive listing. A subdirectory that answers with a permission error is
+/// reported on stderr and skipped; the walk carries on with its siblings.
+///
+/// `open` lists one directory; `dir_path` returns an entry's path if it is a directory.
+fn walk_skipping_denied<'a, T, S, F, Fut>(
+    root: S,
+    root_path: &str,
+    open: F,
+    dir_path: impl Fn(&T) -> Option<String> + 'a,
+) -> impl Stream<Item = opendal::Result<T>> + 'a
+where
+    T: 'a,
+    S: Stream<Item = opendal::Result<T>> + Unpin + 'a,
+    F: Fn(String) -> Fut + 'a,
+    Fut: Future<Output = opendal::Result<S>> + 'a,
+{
+    // Listed paths come back without a leading `/`, except the bucket root itself.
+    let root_dir = root_path.trim_start_matches('/');
+    let root = WalkLevel {
+        dir: if root_dir.is_empty() {
+            "/".to_string()
+        } else if root_dir.ends_with('/') {
+            root_dir.to_string()
+        } else {
+            format!("{root_dir}/")
+        },
+        entries: root,
+        root: true,
+    };
+    stream::unfold(
+        (vec![root], open, dir_path),
+        |(mut stack, open, dir_path)| async move {
+            loop {
+                let level = stack.last_mut()?;
+                let item = match level.entries.next().await {
+                    None => {
+                        stack.pop();
+                        continue;
+                    }
+                    Some(Err(err)) if err.kind() == ErrorKind::PermissionDenied && !level.root => {
+                        eprintln!("Warning: access denied, skipping {}", level.dir);
+                        stack.pop();
+                        continue;
+                    }
+                    Some(Err(err)) => return Some((Err(err), (stack, open, dir_path))),
+                    Some(Ok(item)) => item,
+                };
+                if let Some(dir) = dir_path(&item) {
+                    if dir == level.dir {
+                        // The listed directory itself: shown once, by its parent's listing.
+                        if !level.root {
+                            continue;
+                        }
+                    } else {
+                        match open(dir.clone()).await {
+                            Ok(entries) => stack.push(WalkLevel {
+                                dir,
+                                entries,
+                                root: false,
+                            }),
+                            Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+                                eprintln!("Warning: access denied, skipping {dir}");
+                            }
+                            Err(err) => return Some((Err(err), (stack, open, dir_path))),
+                        }
+                    }
+                }
+                return Some((Ok(item), (stack, open, dir_path)));
+            }
+        },
+    )
+}
+
+/// Group a recursive listing under the directories that hold each entry, `ls -R` style.
+/// Children are renamed to their last path segment and keep their listed order. Every
+/// directory gets a group, including empty ones and those the listing only implies
+/// through deeper keys; the listed path itself is only a header.
+fn group_by_parent(file_infos: Vec<FileInfo>, root: &str) -> BTreeMap<String, Vec<FileInfo>> {
+    let root = root.trim_start_matches('/');
+    let root = if root.is_empty() || root.ends_with('/') {
+        root.to_string()
+    } else {
+        format!("{root}/")
+    };
+    let mut groups = BTreeMap::from([(root.clone(), Vec::new())]);
+    for file_info in file_infos {
+        let path = file_info.path.trim_start_matches('/').to_string();
+        if file_info.is_dir {
+            if groups.contains_key(&path) {
+                continue;
+            }
+            groups.insert(path.clone(), Vec::new());
+        }
+        let parent = parent_dir(&path);
+        add_group(&mut groups, &parent, &root);
+        let name = file_info.basename();
+        groups
+            .entry(parent)
+            .or_default()
+            .push(file_info.with_path(name));
+    }
+    groups
+}
+
+/// Give `dir` a group, listing it under its parent (and so on upwards) when the listing
+/// had no marker object for it.
+fn add_group(groups: &mut BTreeMap<String, Vec<FileInfo>>, dir: &str, root: &str) {
+    if groups.contains_key(dir) {
+        return;
+    }
+    groups.insert(dir.to_string(), Vec::new());
+    if dir.len() <= root.len() || !dir.starts_with(root) {
+        return;
+    }
+    let parent = parent_dir(dir);
+    add_group(groups, &parent, root);
+    let implied = FileInfo::from_metadata(dir, &Metadata::new(EntryMode::DIR));
+    let name = implied.basename();
+    groups
+        .entry(parent)
+        .or_default()
+        .push(implied.with_path(name));
+}
+
+/// The directory holding `path`, with a trailing `/`; empty at the bucket root.
+fn parent_dir(path: &str) -> String {
+    match path.trim_end_matches('/').rsplit_once('/') {
+        Some((parent, _)) => format!("{parent}/"),
+        None => String::new(),
+    }
+}
+
+/// Sort collected entries in place by the requested key.
+fn sort_file_infos(file_infos: &mut [FileInfo], key: SortKey) {
+    match key {
+        SortKey::Name => file_infos.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortKey::Size => file_infos.sort_by(|a, b| b.size.cmp(&a.size).then(a.path.cmp(&b.path))),
+        SortKey::Time => {
+            file_infos.sort_by(|a, b| b.modified.cmp(&a.modified).then(a.path.cmp(&b.path)))
+        }
+        SortKey::Version => file_infos.sort_by(|a, b| natural_cmp(&a.path, &b.path)),
+    }
+}
+
+/// Files and bytes under one directory, counted through all of its subdirectories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DirTotal {
+    path: String,
+    files: u64,
+    bytes: u64,
+}
+
+impl DirTotal {
+    fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            files: 0,
+            bytes: 0,
+        }
+    }
+}
+
+impl fmt::Display for DirTotal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(
+            f,
+            "{path}: {} files, {}",
+            self.files,
+            crate::storage::utils::size::format_size(self.bytes)
+        )
+    }
+}
+
+/// Running per-directory totals for `ls --recursive-summary`.
+///
+/// Keys must be added in lexicographic order, as object store listings return them, so
+/// each directory's keys arrive together and a directory is complete as soon as a key
+/// outside it shows up.
+#[derive(Debug)]
+struct SubtreeRollup {
+    /// Directories still receiving files, outermost (the listed path) first
+    open: Vec<DirTotal>,
+}
+
+impl SubtreeRollup {
+    fn new(root: &str) -> Self {
+        let root = root.trim_start_matches('/');
+        let root = if root.is_empty() || root.ends_with('/') {
+            root.to_string()
+        } else {
+            format!("{root}/")
+        };
+        Self {
+            open: vec![DirTotal::new(&root)],
+        }
+    }
+
+    /// Count a file, returning the directories it shows to be complete, innermost first.
+    fn add(&mut self, path: &str, size: u64) -> Vec<DirTotal> {
+        let mut done = Vec::new();
+        while self.open.len() > 1
+            && !self
+                .open
+                .last()
+                .is_some_and(|dir| path.starts_with(&dir.path))
+        {
+            done.extend(self.open.pop());
+        }
+        let base = self.open.last().map_or(0, |dir| dir.path.len());
+        if let Some(rest) = path.get(base..) {
+            for (index, _) in rest.match_indices('/') {
+                self.open.push(DirTotal::new(&path[..base + index + 1]));
+            }
+        }
+        for dir in &mut self.open {
+            dir.files += 1;
+            dir.bytes += size;
+        }
+        done
+    }
+
+    /// Totals of the directories still open, innermost first, ending with the listed path.
+    fn finish(self) -> Vec<DirTotal> {
+        self.open.into_iter().rev().collect()
     }
 }
 
+/// One entry of `ls --format json`.
+#[derive(Debug, Serialize)]
+struct ListRecord<'a> {
+    path: &'a str,
+    size: u64,
+    /// RFC3339, or null where the backend reports none
+    modified: Option<String>,
+    is_dir: bool,
+}
+
 /// File information for detailed listing output.
+#[derive(Clone)]
 struct FileInfo {
     path: String,
     size: u64,
-    modified: Option<String>,
+    modified: Option<DateTime<Utc>>,
     is_dir: bool,
+    etag: Option<String>,
+    content_md5: Option<String>,
+    content_type: Option<String>,
+    version: Option<String>,
 }
 
 impl FileInfo {
     fn from_entry(entry: &opendal::Entry) -> Self {
-        let meta = entry.metadata();
+        Self::from_metadata(entry.path(), entry.metadata())
+    }
+
+    fn from_metadata(path: &str, meta: &Metadata) -> Self {
         Self {
-            path: entry.path().to_string(),
+            path: path.to_string(),
             size: meta.content_length(),
-            modified: meta.last_modified().map(|t| t.to_rfc3339()),
+            modified: meta.last_modified(),
             is_dir: meta.mode().is_dir(),
+            etag: meta.etag().map(str::to_string),
+            content_md5: meta.content_md5().map(str::to_string),
+            content_type: meta.content_type().map(str::to_string),
+            version: meta.version().map(str::to_string),
+        }
+    }
+
+    /// The ETag, or the stored `Content-MD5` where the backend reports no ETag.
+    fn checksum(&self) -> Option<&str> {
+        self.etag.as_deref().or(self.content_md5.as_deref())
+    }
+
+    /// Whether the checksum is a multipart ETag (`<hash>-<parts>`), which is not an MD5
+    /// of the content.
+    fn has_multipart_etag(&self) -> bool {
+        self.etag
+            .as_deref()
+            .and_then(|etag| etag.trim_matches('"').rsplit_once('-'))
+            .is_some_and(|(_, parts)| {
+                !parts.is_empty() && parts.bytes().all(|b| b.is_ascii_digit())
+            })
+    }
+
+    /// Whether an object entry lacks fields that a `stat` call would provide.
+    fn is_incomplete(&self) -> bool {
+        !self.is_dir
+            && (self.modified.is_none() || self.etag.is_none() || self.content_type.is_none())
+    }
+
+    /// Copy of this entry with a different display path.
+    fn with_path(&self, path: String) -> Self {
+        Self {
+            path,
+            ..self.clone()
+        }
+    }
+
+    /// Render this entry through a user template; missing values print as `-`.
+    fn render(&self, template: &Template, time_style: TimeStyle) -> String {
+        template.render(|field| match field {
+            "type" => if self.is_dir { "DIR" } else { "FILE" }.to_string(),
+            "size" => self.size.to_string(),
+            "human_size" => crate::storage::utils::size::format_size(self.size),
+            "mtime" => self
+                .modified
+                .as_ref()
+                .map(|t| time_style.format(t))
+                .unwrap_or_else(|| "-".to_string()),
+            "path" => self.path.clone(),
+            "etag" => self.etag.clone().unwrap_or_else(|| "-".to_string()),
+            "content_type" => self.content_type.clone().unwrap_or_else(|| "-".to_string()),
+            "version" => self.version.clone().unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        })
+    }
+
+    /// Last segment of the path, keeping a trailing `/`.
+    fn basename(&self) -> String {
+        let name = basename(&self.path);
+        if self.path.ends_with('/') && !name.is_empty() {
+            format!("{name}/")
+        } else {
+            name
+        }
+    }
+
+    /// Tab-separated `type size mtime path`; a missing time prints as `-`.
+    fn tsv(&self) -> String {
+        let file_type = if self.is_dir { "DIR" } else { "FILE" };
+        let modified = self
+            .modified
+            .as_ref()
+            .map(|t| TimeStyle::Rfc3339.format(t))
+            .unwrap_or_else(|| "-".to_string());
+        format!("{file_type}\t{}\t{modified}\t{}", self.size, self.path)
+    }
+
+    /// The `--format json` object for this entry.
+    fn record(&self) -> ListRecord<'_> {
+        ListRecord {
+            path: &self.path,
+            size: self.size,
+            modified: self.modified.as_ref().map(|t| TimeStyle::Rfc3339.format(t)),
+            is_dir: self.is_dir,
+        }
+    }
+
+    /// Render this entry with the given timestamp style, and its checksum when `checksums`
+    /// is set.
+    fn display(&self, time_style: TimeStyle, checksums: bool) -> FileInfoDisplay<'_> {
+        FileInfoDisplay {
+            info: self,
+            time_style,
+            checksums,
         }
     }
 }
 
-impl fmt::Display for FileInfo {
+/// Display adapter rendering a `FileInfo` as one long-format line.
+struct FileInfoDisplay<'a> {
+    info: &'a FileInfo,
+    time_style: TimeStyle,
+    checksums: bool,
+}
+
+impl fmt::Display for FileInfoDisplay<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let file_type = if self.is_dir { "DIR" } else { "FILE" };
-        let size_str = if self.is_dir {
+        let info = self.info;
+        let file_type = if info.is_dir { "DIR" } else { "FILE" };
+        let size_str = if info.is_dir {
             "-".to_string()
         } else {
-            crate::storage::utils::size::format_size(self.size)
+            crate::storage::utils::size::format_size(info.size)
+        };
+        let modified = info
+            .modified
+            .as_ref()
+            .map(|t| self.time_style.format(t))
+            .unwrap_or_else(|| "Unknown".to_string());
+        write!(f, "{file_type:<6} {size_str:>10} {modified} ")?;
+        if self.checksums {
+            write!(f, "{} ", info.checksum().unwrap_or("-"))?;
+        }
+        write!(f, "{}", info.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+
+    /// An entry with no ETag, checksum, content type or version; keys ending in `/` are
+    /// directories.
+    fn file_info(path: &str, size: u64, modified: Option<DateTime<Utc>>) -> FileInfo {
+        FileInfo {
+            path: path.to_string(),
+            size,
+            modified,
+            is_dir: path.ends_with('/'),
+            etag: None,
+            content_md5: None,
+            content_type: None,
+            version: None,
+        }
+    }
+
+    fn lines(totals: Vec<DirTotal>) -> Vec<String> {
+        totals.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_page_size_defaults_and_passes_through() {
+        assert_eq!(ListOptions::default().page_size(), DEFAULT_LIST_PAGE_SIZE);
+        let options = ListOptions {
+            page_size: NonZeroUsize::new(2),
+            ..Default::default()
+        };
+        assert_eq!(options.page_size(), 2);
+    }
+
+    #[test]
+    fn test_tsv_fields() {
+        let modified = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let info = file_info("dir/a b.txt", 1536, Some(modified));
+        let line = info.tsv();
+        let fields: Vec<&str> = line.split('\t').collect();
+        assert_eq!(
+            fields,
+            ["FILE", "1536", "2024-01-02T03:04:05+00:00", "dir/a b.txt"]
+        );
+
+        let dir = file_info("dir/", 0, None);
+        assert_eq!(dir.tsv(), "DIR\t0\t-\tdir/");
+    }
+
+    #[test]
+    fn test_json_records() {
+        let modified = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let file = file_info("dir/a\tb.txt", 1536, Some(modified));
+        let dir = file_info("dir/", 0, None);
+        let json = JsonStyle::Compact
+            .render(&[file.record(), dir.record()])
+            .unwrap();
+        assert_eq!(
+            json,
+            r#"[{"path":"dir/a\tb.txt","size":1536,"modified":"2024-01-02T03:04:05+00:00","is_dir":false},{"path":"dir/","size":0,"modified":null,"is_dir":true}]"#
+        );
+    }
+
+    #[test]
+    fn test_basename_of_nested_entries() {
+        let file = file_info("a/b/c.txt", 1, None);
+        assert_eq!(file.basename(), "c.txt");
+
+        let dir = file_info("a/b/", 0, None);
+        assert_eq!(dir.basename(), "b/");
+    }
+
+    type MockListing = stream::Iter<std::vec::IntoIter<opendal::Result<String>>>;
+
+    fn denied() -> opendal::Error {
+        opendal::Error::new(ErrorKind::PermissionDenied, "access denied")
+    }
+
+    /// Directory listings of a mock bucket: `private/` is refused when opened and
+    /// `public/locked/` on its first page.
+    fn open_mock(dir: String) -> future::Ready<opendal::Result<MockListing>> {
+        let entries: Vec<opendal::Result<String>> = match dir.as_str() {
+            "data/private/" => return future::ready(Err(denied())),
+            "data/public/" => ["data/public/", "data/public/b.txt", "data/public/locked/"]
+                .map(|path| Ok(path.to_string()))
+                .into(),
+            "data/public/locked/" => vec![Err(denied())],
+            _ => Vec::new(),
+        };
+        future::ready(Ok(stream::iter(entries)))
+    }
+
+    #[tokio::test]
+    async fn test_walk_skips_denied_subtrees() {
+        let mock_dir_path = |path: &String| path.ends_with('/').then(|| path.clone());
+        let root = stream::iter(Vec::from(
+            [
+                "data/",
+                "data/a.txt",
+                "data/private/",
+                "data/public/",
+                "data/z.txt",
+            ]
+            .map(|path| Ok(path.to_string())),
+        ));
+        let listed: Vec<String> = walk_skipping_denied(root, "/data", open_mock, mock_dir_path)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            listed,
+            [
+                "data/",
+                "data/a.txt",
+                "data/private/",
+                "data/public/",
+                "data/public/b.txt",
+                "data/public/locked/",
+                "data/z.txt",
+            ]
+        );
+
+        // The listed path itself being denied is still an error.
+        let root = stream::iter(vec![Err(denied())]);
+        let result: opendal::Result<Vec<String>> =
+            walk_skipping_denied(root, "data/", open_mock, mock_dir_path)
+                .try_collect()
+                .await;
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn test_checksum_column() {
+        let info = FileInfo {
+            etag: Some("\"9e107d9d372bb6826bd81d3542a419d6\"".to_string()),
+            content_md5: Some("nhB9nTcrtoJr2B01QqQZ1g==".to_string()),
+            ..file_info("a.txt", 1, None)
         };
-        let modified = self.modified.as_deref().unwrap_or("Unknown");
-        write!(f, "{file_type:<6} {size_str:>10} {modified} {}", self.path)
+        let line =
+            |info: &FileInfo, checksums| info.display(TimeStyle::Rfc3339, checksums).to_string();
+        assert!(line(&info, true).ends_with(" \"9e107d9d372bb6826bd81d3542a419d6\" a.txt"));
+        assert!(!line(&info, false).contains("9e107d9d"));
+        assert!(!info.has_multipart_etag());
+
+        // Without an ETag the stored Content-MD5 is shown; with neither, a dash.
+        let md5_only = FileInfo {
+            etag: None,
+            ..info.clone()
+        };
+        assert!(line(&md5_only, true).ends_with(" nhB9nTcrtoJr2B01QqQZ1g== a.txt"));
+        let bare = FileInfo {
+            content_md5: None,
+            ..md5_only
+        };
+        assert!(line(&bare, true).ends_with(" - a.txt"));
+
+        let multipart = FileInfo {
+            etag: Some("\"d41d8cd98f00b204e9800998ecf8427e-12\"".to_string()),
+            ..info
+        };
+        assert!(multipart.has_multipart_etag());
+    }
+
+    #[test]
+    fn test_strip_trailing_slash_only_changes_display() {
+        let dir = file_info("a/b/", 0, None);
+        let options = ListOptions {
+            strip_trailing_slash: true,
+            ..Default::default()
+        };
+        let shown = display_info(&dir, &options);
+        assert_eq!(shown.path, "a/b");
+        assert!(
+            shown
+                .display(TimeStyle::Rfc3339, false)
+                .to_string()
+                .starts_with("DIR")
+        );
+        assert_eq!(shown.basename(), "b");
+        assert_eq!(dir.path, "a/b/");
+
+        let root = dir.with_path("/".to_string());
+        assert_eq!(display_info(&root, &options).path, "/");
+        let file = file_info("a/c.txt", 0, None);
+        assert!(matches!(display_info(&file, &options), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_subtree_rollup_emits_completed_directories() {
+        let mut rollup = SubtreeRollup::new("logs");
+        assert!(rollup.add("logs/a/1.log", 100).is_empty());
+        assert!(rollup.add("logs/a/b/2.log", 200).is_empty());
+        assert_eq!(
+            lines(rollup.add("logs/c/3.log", 300)),
+            ["logs/a/b/: 1 files, 200B", "logs/a/: 2 files, 300B"]
+        );
+        assert_eq!(
+            lines(rollup.add("logs/top.log", 400)),
+            ["logs/c/: 1 files, 300B"]
+        );
+        assert_eq!(lines(rollup.finish()), ["logs/: 4 files, 1000B"]);
+
+        let mut root = SubtreeRollup::new("/");
+        assert!(root.add("x/1", 1).is_empty());
+        assert_eq!(lines(root.finish()), ["x/: 1 files, 1B", "/: 1 files, 1B"]);
+    }
+
+    #[tokio::test]
+    async fn test_summary_sizes_and_orders_an_fs_listing() {
+        // Fs listings carry no sizes and come in directory order, not key order.
+        let root = TempDir::new();
+        let operator = fs_operator(root.path());
+        operator.write("d/c/1.txt", vec![0u8; 10]).await.unwrap();
+        operator.write("d/a/2.txt", vec![0u8; 20]).await.unwrap();
+        operator.write("d/a/3.txt", vec![0u8; 30]).await.unwrap();
+
+        let lister = OpenDalLister::new(operator.clone());
+        assert!(!lister.lists_in_order(false));
+        let mut file_infos = Vec::new();
+        for entry in operator.list_with("d/").recursive(true).await.unwrap() {
+            let info = lister.file_info(entry, false, true).await.unwrap();
+            if !info.is_dir {
+                file_infos.push(info);
+            }
+        }
+        file_infos.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut rollup = SubtreeRollup::new("d/");
+        let mut totals = Vec::new();
+        for info in &file_infos {
+            totals.extend(lines(rollup.add(&info.path, info.size)));
+        }
+        totals.extend(lines(rollup.finish()));
+        assert_eq!(
+            totals,
+            [
+                "d/a/: 2 files, 50B",
+                "d/c/: 1 files, 10B",
+                "d/: 3 files, 60B"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_parent_nests_like_ls_r() {
+        let listing = [
+            "logs/",
+            "logs/a.log",
+            // No marker for logs/2024/: it is implied by the keys under it.
+            "logs/2024/01/",
+            "logs/2024/01/b.log",
+            "logs/empty/",
+            "logs/z.log",
+        ]
+        .map(|path| file_info(path, 0, None))
+        .to_vec();
+
+        let groups: Vec<(String, Vec<String>)> = group_by_parent(listing, "logs")
+            .into_iter()
+            .map(|(dir, children)| (dir, children.into_iter().map(|c| c.path).collect()))
+            .collect();
+        let expected = [
+            ("logs/", vec!["a.log", "2024/", "empty/", "z.log"]),
+            ("logs/2024/", vec!["01/"]),
+            ("logs/2024/01/", vec!["b.log"]),
+            ("logs/empty/", vec![]),
+        ];
+        assert_eq!(
+            groups,
+            expected.map(|(dir, children)| (
+                dir.to_string(),
+                children.into_iter().map(str::to_string).collect()
+            ))
+        );
+
+        let root = group_by_parent(
+            vec![file_info("top.txt", 0, None), file_info("d/x", 0, None)],
+            "/",
+        );
+        assert_eq!(root.keys().collect::<Vec<_>>(), ["", "d/"]);
     }
 }
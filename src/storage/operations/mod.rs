@@ -1,19 +1,31 @@
 // Storage operation traits and implementations
+pub mod cat;
 pub mod copy;
 pub mod delete;
 pub mod download;
+pub mod find;
+pub mod grep;
+pub mod head;
 pub mod list;
 pub mod mkdir;
+pub mod rename;
+pub mod select;
 pub mod stat;
 pub mod upload;
 pub mod usage;
 
 // Re-export all operation traits - all are now implemented
+pub use cat::Catter;
 pub use copy::Copier;
 pub use delete::Deleter;
 pub use download::Downloader;
+pub use find::Finder;
+pub use grep::Grepper;
+pub use head::Peeker;
 pub use list::Lister;
 pub use mkdir::Mkdirer;
+pub use rename::Mover;
+pub use select::Selector;
 pub use stat::Stater;
 pub use upload::Uploader;
 pub use usage::UsageCalculator;
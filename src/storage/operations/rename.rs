@@ -0,0 +1,168 @@
+// Move (rename) operation trait and implementation
+use crate::error::{InvalidPathSnafu, Result};
+use crate::storage::operations::stat::stat_if_exists;
+use crate::storage::utils::path::{
+    basename, build_remote_path, ensure_trailing_slash, get_root_relative_path,
+};
+use futures::stream::TryStreamExt;
+use opendal::Operator;
+use snafu::ensure;
+
+/// Trait for moving files and directories within storage.
+pub trait Mover {
+    /// Move a file or directory, replacing whatever already exists at the destination.
+    ///
+    /// # Arguments
+    /// * `src_path` - Path of the object or directory to move
+    /// * `dest_path` - New path; an existing directory receives the source by name
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success or detailed error information
+    async fn move_path(&self, src_path: &str, dest_path: &str) -> Result<()>;
+}
+
+/// Implementation of Mover for OpenDAL Operator.
+pub struct OpenDalMover {
+    operator: Operator,
+}
+
+impl OpenDalMover {
+    /// Create a new mover with the given OpenDAL operator.
+    pub fn new(operator: Operator) -> Self {
+        Self { operator }
+    }
+
+    /// Whether `path` is a directory, either by its marker or by having children.
+    async fn is_directory(&self, path: &str) -> Result<bool> {
+        if let Some(meta) = stat_if_exists(&self.operator, path).await? {
+            return Ok(meta.is_dir());
+        }
+        let entries = self
+            .operator
+            .list_with(&ensure_trailing_slash(path))
+            .limit(1)
+            .await?;
+        Ok(!entries.is_empty())
+    }
+
+    /// Move one object, renaming it natively when the backend can and otherwise copying
+    /// it through the client and deleting the source.
+    async fn move_object(&self, src_path: &str, dest_path: &str) -> Result<()> {
+        if self.operator.info().full_capability().rename {
+            self.operator.rename(src_path, dest_path).await?;
+        } else {
+            // The writer truncates any existing destination, so an overwrite needs no
+            // special handling.
+            let mut chunks = self
+                .operator
+                .reader(src_path)
+                .await?
+                .into_bytes_stream(..)
+                .await?;
+            let mut writer = self.operator.writer(dest_path).await?;
+            while let Some(chunk) = chunks.try_next().await? {
+                writer.write(chunk).await?;
+            }
+            writer.close().await?;
+            self.operator.delete(src_path).await?;
+        }
+        println!("✅ Moved: {src_path} → {dest_path}");
+        Ok(())
+    }
+
+    /// Move every object under `src_path` to the same relative path under `dest_root`,
+    /// then remove the directory markers left behind.
+    async fn move_directory(&self, src_path: &str, dest_root: &str) -> Result<()> {
+        // Enumerate before moving so new keys under an overlapping prefix aren't revisited.
+        let files: Vec<String> = self
+            .operator
+            .lister_with(src_path)
+            .recursive(true)
+            .await?
+            .try_filter(|entry| std::future::ready(entry.metadata().is_file()))
+            .map_ok(|entry| entry.path().to_string())
+            .try_collect()
+            .await?;
+        for file in files {
+            let relative_path = get_root_relative_path(&file, src_path);
+            self.move_object(&file, &build_remote_path(dest_root, &relative_path))
+                .await?;
+        }
+        self.operator
+            .remove_all(&ensure_trailing_slash(src_path))
+            .await?;
+        Ok(())
+    }
+}
+
+impl Mover for OpenDalMover {
+    async fn move_path(&self, src_path: &str, dest_path: &str) -> Result<()> {
+        let src_is_dir = self.is_directory(src_path).await?;
+        ensure!(
+            src_is_dir || self.operator.exists(src_path).await?,
+            InvalidPathSnafu {
+                path: src_path.to_string()
+            }
+        );
+        // Like mv(1), an existing directory (or a path ending in '/') receives the source
+        // under its own name.
+        let final_dest = if dest_path.ends_with('/') || self.is_directory(dest_path).await? {
+            build_remote_path(dest_path, &basename(src_path))
+        } else {
+            dest_path.to_string()
+        };
+
+        if src_is_dir {
+            let src_dir = ensure_trailing_slash(src_path);
+            ensure!(
+                !ensure_trailing_slash(&final_dest).starts_with(&src_dir),
+                InvalidPathSnafu { path: final_dest }
+            );
+            self.move_directory(&src_dir, &final_dest).await
+        } else {
+            self.move_object(src_path, &final_dest).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+
+    #[tokio::test]
+    async fn test_move_overwrites_and_removes_source() {
+        let root = TempDir::new();
+        let operator = fs_operator(root.path());
+        let mover = OpenDalMover::new(operator.clone());
+
+        operator.write("a.txt", b"new".to_vec()).await.unwrap();
+        operator.write("b.txt", b"old".to_vec()).await.unwrap();
+        mover.move_path("a.txt", "b.txt").await.unwrap();
+        assert!(!operator.exists("a.txt").await.unwrap());
+        assert_eq!(operator.read("b.txt").await.unwrap().to_vec(), b"new");
+
+        operator.write("src/one.txt", b"1".to_vec()).await.unwrap();
+        operator
+            .write("src/nested/two.txt", b"2".to_vec())
+            .await
+            .unwrap();
+        mover.move_path("src", "dst").await.unwrap();
+        assert!(!operator.exists("src/").await.unwrap());
+        assert_eq!(operator.read("dst/one.txt").await.unwrap().to_vec(), b"1");
+        assert_eq!(
+            operator.read("dst/nested/two.txt").await.unwrap().to_vec(),
+            b"2"
+        );
+
+        // An existing directory receives the source by name.
+        mover.move_path("b.txt", "dst/").await.unwrap();
+        assert!(operator.exists("dst/b.txt").await.unwrap());
+
+        let err = mover.move_path("dst/", "dst/nested/").await.unwrap_err();
+        assert!(matches!(err, Error::InvalidPath { .. }));
+        let err = mover.move_path("missing.txt", "x.txt").await.unwrap_err();
+        assert!(matches!(err, Error::InvalidPath { .. }));
+    }
+}
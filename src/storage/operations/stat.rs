@@ -1,5 +1,7 @@
-use crate::error::Result;
-use opendal::{EntryMode, Operator};
+use crate::error::{LossyKeySnafu, Result};
+use crate::storage::utils::path::ensure_trailing_slash;
+use crate::storage::utils::template::Template;
+use opendal::{Entry, EntryMode, ErrorKind, Metadata, Operator};
 
 /// Object metadata used by `stat` command output.
 ///
@@ -9,6 +11,8 @@ use opendal::{EntryMode, Operator};
 /// - `last_modified`: RFC3339 string if available
 /// - `etag`: Backend provided entity tag if available
 /// - `content_type`: MIME type if available
+/// - `content_disposition`: Content-Disposition header if set
+/// - `cache_control`: Cache-Control header if set
 #[derive(Debug, Clone)]
 pub struct ObjectMeta {
     pub path: String,
@@ -17,6 +21,86 @@ pub struct ObjectMeta {
     pub last_modified: Option<String>,
     pub etag: Option<String>,
     pub content_type: Option<String>,
+    pub content_disposition: Option<String>,
+    pub cache_control: Option<String>,
+}
+
+/// Placeholders available to `stat --format` templates.
+pub const STAT_TEMPLATE_FIELDS: &[&str] = &[
+    "path",
+    "type",
+    "size",
+    "last_modified",
+    "etag",
+    "content_type",
+    "content_disposition",
+    "cache_control",
+];
+
+impl ObjectMeta {
+    /// Render this metadata through a user template; missing values print as `-`.
+    pub fn render(&self, template: &Template) -> String {
+        let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+        template.render(|field| match field {
+            "path" => self.path.clone(),
+            "type" => self.entry_type.clone(),
+            "size" => self.size.to_string(),
+            "last_modified" => or_dash(&self.last_modified),
+            "etag" => or_dash(&self.etag),
+            "content_type" => or_dash(&self.content_type),
+            "content_disposition" => or_dash(&self.content_disposition),
+            "cache_control" => or_dash(&self.cache_control),
+            _ => String::new(),
+        })
+    }
+}
+
+/// Stat a path, returning `None` instead of an error when it does not exist.
+pub async fn stat_if_exists(operator: &Operator, path: &str) -> Result<Option<Metadata>> {
+    match operator.stat(path).await {
+        Ok(meta) => Ok(Some(meta)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Size of a listed file. Some listings, such as the local filesystem's, carry no size and
+/// read as 0, so a file listed as empty is confirmed with a stat.
+pub async fn listed_size(operator: &Operator, entry: &Entry) -> Result<u64> {
+    let meta = entry.metadata();
+    if meta.content_length() > 0 || !meta.is_file() {
+        return Ok(meta.content_length());
+    }
+    Ok(operator.stat(entry.path()).await?.content_length())
+}
+
+/// With `strict`, fail on keys that were decoded lossily instead of using them as-is.
+///
+/// Backends hand listed keys over already decoded, with bytes that are not valid UTF-8
+/// replaced by U+FFFD. Such a key no longer names the object it was listed for, while a key
+/// that genuinely contains U+FFFD still does, so the two are told apart by a stat.
+pub async fn ensure_exact_key(operator: &Operator, key: &str, strict: bool) -> Result<()> {
+    if strict
+        && key.contains(char::REPLACEMENT_CHARACTER)
+        && stat_if_exists(operator, key).await?.is_none()
+    {
+        return LossyKeySnafu { key }.fail();
+    }
+    Ok(())
+}
+
+/// Whether `path` is an object, or a prefix with something under it. Prefixes often have
+/// no marker object and some backends report any directory path as present, so
+/// directories are confirmed by listing.
+pub async fn path_exists(operator: &Operator, path: &str) -> Result<bool> {
+    if let Some(meta) = stat_if_exists(operator, path).await?
+        && !meta.mode().is_dir()
+    {
+        return Ok(true);
+    }
+    let probe = ensure_trailing_slash(path);
+    let entries = operator.list_with(&probe).limit(1).await?;
+    Ok(!entries.is_empty())
 }
 
 /// Trait for fetching object metadata from storage.
@@ -59,6 +143,8 @@ impl Stater for OpenDalStater {
         let last_modified = meta.last_modified().map(|t| t.to_string());
         let etag = meta.etag().map(|s| s.to_string());
         let content_type = meta.content_type().map(|s| s.to_string());
+        let content_disposition = meta.content_disposition().map(|s| s.to_string());
+        let cache_control = meta.cache_control().map(|s| s.to_string());
 
         Ok(ObjectMeta {
             path: path.as_ref().to_owned(),
@@ -67,6 +153,45 @@ impl Stater for OpenDalStater {
             last_modified,
             etag,
             content_type,
+            content_disposition,
+            cache_control,
         })
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    #[tokio::test]
+    async fn test_ensure_exact_key_rejects_only_lossily_decoded_keys() {
+        let dir = TempDir::new();
+        // "café" written by a Latin-1 client: 0xE9 alone is not valid UTF-8.
+        std::fs::write(dir.join(OsStr::from_bytes(b"caf\xe9.txt")), "latin-1").unwrap();
+        // A name that really contains U+FFFD is addressable as listed.
+        std::fs::write(dir.join("caf\u{fffd}-real.txt"), "utf-8").unwrap();
+        let operator = fs_operator(dir.path());
+
+        let mut keys: Vec<String> = operator
+            .list("/")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path().to_string())
+            .filter(|key| key.ends_with(".txt"))
+            .collect();
+        keys.sort();
+        assert_eq!(keys, ["caf\u{fffd}-real.txt", "caf\u{fffd}.txt"]);
+
+        ensure_exact_key(&operator, &keys[0], true).await.unwrap();
+        ensure_exact_key(&operator, &keys[1], false).await.unwrap();
+        let err = ensure_exact_key(&operator, &keys[1], true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::LossyKey { ref key } if *key == keys[1]));
+    }
+}
@@ -1,14 +1,83 @@
-use crate::error::{DirectoryUploadNotRecursiveSnafu, PathNotFoundSnafu, Result};
-use crate::storage::constants::{DEFAULT_BUFFER_SIZE, PROGRESS_UPDATE_INTERVAL};
-use crate::storage::utils::path::build_remote_path;
-use crate::storage::utils::progress::ConsoleProgressReporter;
+use crate::error::{
+    ChecksumMismatchSnafu, DirectoryUploadNotRecursiveSnafu, LocalFileChangedSnafu,
+    PathNotFoundSnafu, Result,
+};
+use crate::storage::constants::{DEFAULT_BUFFER_SIZE, DEFAULT_PART_SIZE, PROGRESS_UPDATE_INTERVAL};
+use crate::storage::operations::stat::stat_if_exists;
+use crate::storage::utils::checksum::{
+    ChecksumAlgorithm, ChecksumHasher, retry_on_checksum_mismatch,
+};
+use crate::storage::utils::content_type::{ContentTypeDetection, detect_content_type};
+use crate::storage::utils::failure::{ErrorPolicy, FailureTracker};
+use crate::storage::utils::manifest::Manifest;
+use crate::storage::utils::path::{UnicodeForm, build_remote_path, normalize_unicode};
+use crate::storage::utils::progress::{ConsoleProgressReporter, ProgressOptions};
+use crate::storage::utils::size::{SizeRange, report_out_of_range};
+use crate::storage::utils::stats::TransferStats;
+use crate::storage::utils::transfer_log::{TransferLog, TransferOperation, log_transfer};
 use async_recursion::async_recursion;
-use opendal::Operator;
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use opendal::{Metadata, Operator};
 use snafu::ensure;
 use std::ffi::OsStr;
 use std::path::Path;
+use std::time::{Instant, SystemTime};
 use tokio::fs;
-use tokio::io::{AsyncReadExt, BufReader};
+use tokio::io::{AsyncRead, AsyncReadExt, BufReader};
+
+/// Options controlling which files are uploaded and how.
+#[derive(Debug, Clone, Default)]
+pub struct UploadOptions {
+    /// Upload directories recursively
+    pub recursive: bool,
+    /// Skip files whose remote object was modified after the local file
+    pub no_overwrite_newer: bool,
+    /// Upload only files modified after their existing remote object
+    pub only_newer: bool,
+    /// With `only_newer`, skip remote objects without a modification time instead of
+    /// overwriting them
+    pub skip_undated: bool,
+    /// Checksum each file while uploading and make sure the stored object matches
+    pub checksum_algorithm: Option<ChecksumAlgorithm>,
+    /// With `checksum_algorithm`, upload a file again up to this many times while the
+    /// stored object's checksum mismatches
+    pub checksum_retries: u32,
+    /// `Content-Disposition` stored with each uploaded object
+    pub content_disposition: Option<String>,
+    /// `Cache-Control` stored with each uploaded object
+    pub cache_control: Option<String>,
+    /// Normalize local file names to this Unicode form when building remote keys
+    pub normalize_unicode: Option<UnicodeForm>,
+    /// Upload up to this many parts of each file in parallel
+    pub part_concurrency: Option<usize>,
+    /// Size of each part in a multipart upload
+    pub part_size: Option<usize>,
+    /// Set each object's `Content-Type` from its file name or leading bytes
+    pub detect_content_type: Option<ContentTypeDetection>,
+    /// What to do when a file of a directory upload fails
+    pub on_error: ErrorPolicy,
+    /// Record the ETag, key and size of every uploaded object
+    pub manifest: Option<Manifest>,
+    /// Discard an upload whose local file changed size or modification time meanwhile
+    pub fail_if_changed: bool,
+    /// Append one CSV row per file to this audit log
+    pub transfer_log: Option<TransferLog>,
+    /// Only upload files of a directory whose size lies in this band
+    pub size_range: SizeRange,
+}
+
+impl UploadOptions {
+    /// Part size when files are written as multipart uploads, or `None` for a plain
+    /// streaming write.
+    fn multipart_part_size(&self) -> Option<usize> {
+        if self.part_concurrency.is_none() && self.part_size.is_none() {
+            return None;
+        }
+        Some(self.part_size.unwrap_or(DEFAULT_PART_SIZE))
+    }
+}
 
 /// Trait for uploading files and directories to storage.
 pub trait Uploader {
@@ -17,81 +86,383 @@ pub trait Uploader {
     /// # Arguments
     /// * `local_path` - Source path on local filesystem (file or directory)
     /// * `remote_path` - Destination path in storage
-    /// * `recursive` - Whether to upload directories recursively
+    /// * `options` - Upload behavior such as recursion and overwrite guards
     ///
     /// # Returns
-    /// * `Result<()>` - Success or detailed error information
-    async fn upload(&self, local_path: &str, remote_path: &str, recursive: bool) -> Result<()>;
+    /// * `Result<TransferStats>` - Files and bytes written, or detailed error information
+    async fn upload(
+        &self,
+        local_path: &str,
+        remote_path: &str,
+        options: &UploadOptions,
+    ) -> Result<TransferStats>;
 }
 
 /// Implementation of Uploader for OpenDAL Operator.
 pub struct OpenDalUploader {
     operator: Operator,
+    progress: ProgressOptions,
+    server_checksum: bool,
 }
 
 impl OpenDalUploader {
-    /// Create a new uploader with the given OpenDAL operator.
-    pub fn new(operator: Operator) -> Self {
-        Self { operator }
+    /// Create a new uploader with the given OpenDAL operator and progress settings.
+    pub fn new(operator: Operator, progress: ProgressOptions) -> Self {
+        Self {
+            operator,
+            progress,
+            server_checksum: false,
+        }
+    }
+
+    /// Trust the backend to validate the requested checksum instead of reading each
+    /// object back. Callers must only enable this when the operator sends checksums.
+    pub fn with_server_checksum(mut self, server_checksum: bool) -> Self {
+        self.server_checksum = server_checksum;
+        self
     }
 
-    /// Upload a single file with streaming progress.
-    async fn upload_file_streaming(&self, local_path: &Path, remote_path: &str) -> Result<()> {
+    /// Upload a single file unless an overwrite guard says to skip it. Returns the bytes
+    /// written, or `None` if the file was skipped.
+    async fn upload_file(
+        &self,
+        local_path: &Path,
+        remote_path: &str,
+        options: &UploadOptions,
+    ) -> Result<Option<u64>> {
+        if let Some(reason) = self.skip_reason(local_path, remote_path, options).await? {
+            eprintln!("Skipped {}: {reason}", local_path.display());
+            return Ok(None);
+        }
+        retry_on_checksum_mismatch(
+            &local_path.to_string_lossy(),
+            options.checksum_retries,
+            || self.upload_file_streaming(local_path, remote_path, options),
+        )
+        .await
+        .map(Some)
+    }
+
+    /// Why the modification-time guards skip this file, or `None` to upload it.
+    async fn skip_reason(
+        &self,
+        local_path: &Path,
+        remote_path: &str,
+        options: &UploadOptions,
+    ) -> Result<Option<String>> {
+        if !options.no_overwrite_newer && !options.only_newer {
+            return Ok(None);
+        }
+        let Some(remote_meta) = stat_if_exists(&self.operator, remote_path).await? else {
+            return Ok(None);
+        };
+        let local_modified: DateTime<Utc> = fs::metadata(local_path).await?.modified()?.into();
+        Ok(
+            compare_modified(local_modified, remote_meta.last_modified(), options)
+                .map(|reason| format!("remote {remote_path} {reason}")),
+        )
+    }
+
+    /// Upload a single file with streaming progress, returning the bytes written.
+    ///
+    /// With a part size set, the file is read one part at a time and parts are uploaded
+    /// concurrently; progress advances as each part is handed to the writer, which waits
+    /// for a free upload slot once `part_concurrency` parts are in flight.
+    async fn upload_file_streaming(
+        &self,
+        local_path: &Path,
+        remote_path: &str,
+        options: &UploadOptions,
+    ) -> Result<u64> {
+        let checksum_algorithm = options.checksum_algorithm;
         let file = fs::File::open(local_path).await?;
-        let file_size = file.metadata().await?.len();
+        let file_meta = file.metadata().await?;
+        let file_size = file_meta.len();
+        let snapshot = options
+            .fail_if_changed
+            .then(|| FileSnapshot::of(&file_meta));
         let mut reader = BufReader::new(file);
-        let mut buffer = vec![0u8; DEFAULT_BUFFER_SIZE];
+        let part_size = options.multipart_part_size();
+        let mut buffer = PartBuffer::new(part_size.unwrap_or(DEFAULT_BUFFER_SIZE));
         let mut total_bytes = 0u64;
-        let mut writer = self.operator.writer(remote_path).await?;
+        // The first block is read before opening the writer so content detection can see it.
+        let mut block = buffer.fill(&mut reader).await?;
+        let mut write = self.operator.writer_with(remote_path);
+        if let Some(detection) = options.detect_content_type {
+            write = write.content_type(&detect_content_type(detection, local_path, &block));
+        }
+        // An empty file is always sent as one zero-length request: a multipart upload of it
+        // would have no part to complete, and some backends then create no object at all.
+        if let Some(part_size) = part_size.filter(|_| !block.is_empty()) {
+            write = write
+                .chunk(part_size)
+                .concurrent(options.part_concurrency.unwrap_or(1));
+        }
+        if let Some(content_disposition) = &options.content_disposition {
+            write = write.content_disposition(content_disposition);
+        }
+        if let Some(cache_control) = &options.cache_control {
+            write = write.cache_control(cache_control);
+        }
+        let mut writer = write.await?;
 
-        let step_bytes = DEFAULT_BUFFER_SIZE as u64 * PROGRESS_UPDATE_INTERVAL;
+        let step_bytes = part_size.map_or(
+            DEFAULT_BUFFER_SIZE as u64 * PROGRESS_UPDATE_INTERVAL,
+            |part_size| part_size as u64,
+        );
         let reporter = ConsoleProgressReporter::new(
             format!("Uploading {}", local_path.display()),
             Some(file_size),
             step_bytes,
-        );
+        )
+        .with_options(&self.progress, remote_path);
+        let mut hasher = checksum_algorithm.map(ChecksumHasher::new);
 
-        loop {
-            let bytes_read = reader.read(&mut buffer).await?;
-            if bytes_read == 0 {
-                break;
+        let written: Result<Metadata> = async {
+            while !block.is_empty() {
+                if let Some(hasher) = hasher.as_mut() {
+                    hasher.update(&block);
+                }
+                total_bytes += block.len() as u64;
+                writer.write(block).await?;
+                reporter.maybe_report(total_bytes);
+                block = buffer.fill(&mut reader).await?;
             }
-            writer.write(buffer[..bytes_read].to_vec()).await?;
-            total_bytes += bytes_read as u64;
-            reporter.maybe_report(total_bytes);
+            // Checked before close so a torn upload is aborted rather than committed.
+            if let Some(snapshot) = &snapshot {
+                snapshot.ensure_unchanged(local_path).await?;
+            }
+            Ok(writer.close().await?)
+        }
+        .await;
+        let written = match written {
+            Ok(written) => written,
+            Err(err) => {
+                // Cancels parts still in flight instead of waiting for them to finish.
+                if let Err(abort_err) = writer.abort().await {
+                    log::debug!("aborting upload of {remote_path} failed: {abort_err}");
+                }
+                return Err(err);
+            }
+        };
+        reporter.finish(total_bytes);
+
+        if let (Some(algorithm), Some(hasher)) = (checksum_algorithm, hasher)
+            && !self.server_checksum
+        {
+            verify_remote_checksum(&self.operator, remote_path, algorithm, &hasher.finalize())
+                .await?;
+        }
+        if let Some(manifest) = &options.manifest {
+            // Not every backend reports the ETag when a write completes.
+            let etag = match written.etag() {
+                Some(etag) => Some(etag.to_string()),
+                None => self
+                    .operator
+                    .stat(remote_path)
+                    .await?
+                    .etag()
+                    .map(str::to_string),
+            };
+            manifest.record(etag.as_deref(), remote_path, total_bytes)?;
         }
-        writer.close().await?;
         println!(
-            "\n✅ Upload: {} → {remote_path} ({total_bytes} bytes)",
+            "✅ Upload: {} → {remote_path} ({total_bytes} bytes)",
             local_path.display(),
         );
-        Ok(())
+        Ok(total_bytes)
     }
 
     /// Upload a directory recursively.
     #[async_recursion]
-    async fn upload_recursive(&self, local_path: &str, remote_path: &str) -> Result<()> {
+    async fn upload_recursive(
+        &self,
+        local_path: &str,
+        remote_path: &str,
+        options: &UploadOptions,
+        failures: &mut FailureTracker,
+        stats: &mut TransferStats,
+        out_of_range: &mut usize,
+    ) -> Result<()> {
         let mut entries = fs::read_dir(local_path).await?;
         while let Some(entry) = entries.next_entry().await? {
             let local_file_path = entry.path();
             let file_name = local_file_path.file_name().unwrap_or_default();
             let file_name_str = file_name.to_string_lossy();
-            let new_remote_path = build_remote_path(remote_path, &file_name_str);
+            let key_name = normalize_unicode(&file_name_str, options.normalize_unicode);
+            let new_remote_path = build_remote_path(remote_path, &key_name);
 
             if local_file_path.is_dir() {
-                self.upload_recursive(&local_file_path.to_string_lossy(), &new_remote_path)
-                    .await?;
+                self.upload_recursive(
+                    &local_file_path.to_string_lossy(),
+                    &new_remote_path,
+                    options,
+                    failures,
+                    stats,
+                    out_of_range,
+                )
+                .await?;
+            } else if !options
+                .size_range
+                .contains(fs::metadata(&local_file_path).await?.len())
+            {
+                *out_of_range += 1;
             } else {
-                self.upload_file_streaming(&local_file_path, &new_remote_path)
-                    .await?;
+                let display_path = local_file_path.to_string_lossy();
+                let file_started = Instant::now();
+                let result = options
+                    .on_error
+                    .run(&display_path, || {
+                        self.upload_file(&local_file_path, &new_remote_path, options)
+                    })
+                    .await;
+                let result = log_transfer(
+                    options.transfer_log.as_ref(),
+                    TransferOperation::Put,
+                    &new_remote_path,
+                    file_started,
+                    result,
+                );
+                if let Ok(Some(bytes)) = result {
+                    stats.add_file(bytes);
+                }
+                failures.record(&display_path, result.map(|_| ()))?;
             }
         }
         Ok(())
     }
 }
 
+/// Size and modification time of a local file, to notice writes during its upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileSnapshot {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileSnapshot {
+    fn of(meta: &std::fs::Metadata) -> Self {
+        Self {
+            size: meta.len(),
+            modified: meta.modified().ok(),
+        }
+    }
+
+    /// Fail with `LocalFileChanged` if the file no longer matches this snapshot.
+    async fn ensure_unchanged(&self, path: &Path) -> Result<()> {
+        let current = Self::of(&fs::metadata(path).await?);
+        ensure!(
+            current == *self,
+            LocalFileChangedSnafu {
+                path: path.display().to_string()
+            }
+        );
+        Ok(())
+    }
+}
+
+/// Read buffer reused across the blocks of an upload.
+///
+/// Each filled block is handed to the writer as `Bytes` without copying. Once the writer
+/// has dropped a block, the next `fill` reclaims its allocation instead of allocating anew,
+/// so a transfer settles on the handful of buffers it has in flight.
+struct PartBuffer {
+    buf: BytesMut,
+    block_size: usize,
+}
+
+impl PartBuffer {
+    fn new(block_size: usize) -> Self {
+        Self {
+            buf: BytesMut::with_capacity(block_size),
+            block_size,
+        }
+    }
+
+    /// Read the next block; empty once the input ends.
+    async fn fill(&mut self, reader: &mut (impl AsyncRead + Unpin)) -> Result<Bytes> {
+        self.buf.reserve(self.block_size);
+        self.buf.resize(self.block_size, 0);
+        let filled = fill_buffer(reader, &mut self.buf).await?;
+        self.buf.truncate(filled);
+        Ok(self.buf.split().freeze())
+    }
+}
+
+/// Read until `buffer` is full or the input ends, so each write is a whole part.
+async fn fill_buffer(reader: &mut (impl AsyncRead + Unpin), buffer: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        let bytes_read = reader.read(&mut buffer[filled..]).await?;
+        if bytes_read == 0 {
+            break;
+        }
+        filled += bytes_read;
+    }
+    Ok(filled)
+}
+
+/// Decide whether an existing remote object should be kept, given both modification
+/// times. Returns the reason to skip, or `None` to upload.
+fn compare_modified(
+    local: DateTime<Utc>,
+    remote: Option<DateTime<Utc>>,
+    options: &UploadOptions,
+) -> Option<&'static str> {
+    let Some(remote) = remote else {
+        return (options.only_newer && options.skip_undated).then_some("has no modification time");
+    };
+    if options.only_newer && local <= remote {
+        Some("is at least as new as the local file")
+    } else if options.no_overwrite_newer && remote > local {
+        Some("is newer than the local file")
+    } else {
+        None
+    }
+}
+
+/// Read an uploaded object back and compare its checksum with the one computed while
+/// uploading, hashing it chunk by chunk so memory stays flat however large it is. A
+/// corrupted object is removed so it cannot be mistaken for a good copy.
+async fn verify_remote_checksum(
+    operator: &Operator,
+    remote_path: &str,
+    algorithm: ChecksumAlgorithm,
+    expected: &str,
+) -> Result<()> {
+    let mut chunks = operator
+        .reader(remote_path)
+        .await?
+        .into_bytes_stream(..)
+        .await?;
+    let mut hasher = ChecksumHasher::new(algorithm);
+    while let Some(chunk) = chunks.try_next().await? {
+        hasher.update(&chunk);
+    }
+    let actual = hasher.finalize();
+    if actual != expected {
+        operator.delete(remote_path).await?;
+    }
+    ensure!(
+        actual == expected,
+        ChecksumMismatchSnafu {
+            path: remote_path.to_string(),
+            expected: expected.to_string(),
+            actual
+        }
+    );
+    Ok(())
+}
+
 impl Uploader for OpenDalUploader {
-    async fn upload(&self, local_path: &str, remote_path: &str, recursive: bool) -> Result<()> {
+    async fn upload(
+        &self,
+        local_path: &str,
+        remote_path: &str,
+        options: &UploadOptions,
+    ) -> Result<TransferStats> {
+        let started = Instant::now();
+        let mut stats = TransferStats::default();
         let path = Path::new(local_path);
         ensure!(
             path.exists(),
@@ -103,17 +474,405 @@ impl Uploader for OpenDalUploader {
         if path.is_file() {
             let file_name = path.file_name().unwrap_or(OsStr::new(local_path));
             let file_name_str = file_name.to_string_lossy();
-            let remote_file_path = build_remote_path(remote_path, &file_name_str);
-            self.upload_file_streaming(Path::new(local_path), &remote_file_path)
-                .await?;
+            let key_name = normalize_unicode(&file_name_str, options.normalize_unicode);
+            let remote_file_path = build_remote_path(remote_path, &key_name);
+            let result = self
+                .upload_file(Path::new(local_path), &remote_file_path, options)
+                .await;
+            if let Some(bytes) = log_transfer(
+                options.transfer_log.as_ref(),
+                TransferOperation::Put,
+                &remote_file_path,
+                started,
+                result,
+            )? {
+                stats.add_file(bytes);
+            }
         } else if path.is_dir() {
-            if recursive {
-                self.upload_recursive(local_path, remote_path).await?;
+            if options.recursive {
+                let mut failures = FailureTracker::new(options.on_error);
+                let mut out_of_range = 0;
+                let walked = self
+                    .upload_recursive(
+                        local_path,
+                        remote_path,
+                        options,
+                        &mut failures,
+                        &mut stats,
+                        &mut out_of_range,
+                    )
+                    .await;
+                report_out_of_range(out_of_range);
+                walked?;
+                failures.finish()?;
             } else {
                 return DirectoryUploadNotRecursiveSnafu.fail();
             }
         }
 
-        Ok(())
+        Ok(stats.finish(started))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::storage::utils::checksum::checksum;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+    use opendal::Buffer;
+    use opendal::raw::{
+        Access, Layer, LayeredAccess, OpList, OpRead, OpWrite, RpDelete, RpList, RpRead, RpWrite,
+        oio,
+    };
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Flips the first byte of the first object written through it, like a network that
+    /// corrupts one transfer.
+    #[derive(Debug, Clone, Default)]
+    struct CorruptFirstWrite(Arc<AtomicBool>);
+
+    impl<A: Access> Layer<A> for CorruptFirstWrite {
+        type LayeredAccess = CorruptFirstWriteAccessor<A>;
+
+        fn layer(&self, inner: A) -> Self::LayeredAccess {
+            CorruptFirstWriteAccessor {
+                inner,
+                corrupted: self.0.clone(),
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct CorruptFirstWriteAccessor<A> {
+        inner: A,
+        corrupted: Arc<AtomicBool>,
+    }
+
+    impl<A: Access> LayeredAccess for CorruptFirstWriteAccessor<A> {
+        type Inner = A;
+        type Reader = A::Reader;
+        type Writer = CorruptingWriter<A::Writer>;
+        type Lister = A::Lister;
+        type Deleter = A::Deleter;
+
+        fn inner(&self) -> &A {
+            &self.inner
+        }
+
+        async fn read(&self, path: &str, args: OpRead) -> opendal::Result<(RpRead, A::Reader)> {
+            self.inner.read(path, args).await
+        }
+
+        async fn write(
+            &self,
+            path: &str,
+            args: OpWrite,
+        ) -> opendal::Result<(RpWrite, Self::Writer)> {
+            let corrupt = !self.corrupted.swap(true, Ordering::Relaxed);
+            let (rp, inner) = self.inner.write(path, args).await?;
+            Ok((rp, CorruptingWriter { inner, corrupt }))
+        }
+
+        async fn delete(&self) -> opendal::Result<(RpDelete, A::Deleter)> {
+            self.inner.delete().await
+        }
+
+        async fn list(&self, path: &str, args: OpList) -> opendal::Result<(RpList, A::Lister)> {
+            self.inner.list(path, args).await
+        }
+    }
+
+    struct CorruptingWriter<W> {
+        inner: W,
+        corrupt: bool,
+    }
+
+    impl<W: oio::Write> oio::Write for CorruptingWriter<W> {
+        async fn write(&mut self, bs: Buffer) -> opendal::Result<()> {
+            if !bs.is_empty() && std::mem::take(&mut self.corrupt) {
+                let mut bytes = bs.to_vec();
+                bytes[0] ^= 0xff;
+                return self.inner.write(Buffer::from(bytes)).await;
+            }
+            self.inner.write(bs).await
+        }
+
+        async fn close(&mut self) -> opendal::Result<opendal::Metadata> {
+            self.inner.close().await
+        }
+
+        async fn abort(&mut self) -> opendal::Result<()> {
+            self.inner.abort().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_retries_checksum_mismatch_within_budget() {
+        let root = TempDir::new();
+        let local = root.join("data.bin");
+        fs::write(&local, b"payload").await.unwrap();
+        let options = |checksum_retries| UploadOptions {
+            checksum_algorithm: Some(ChecksumAlgorithm::Sha256),
+            checksum_retries,
+            ..Default::default()
+        };
+
+        // The first attempt is stored corrupted; the retry uploads it intact.
+        let operator = fs_operator(&root.join("remote")).layer(CorruptFirstWrite::default());
+        OpenDalUploader::new(operator.clone(), Default::default())
+            .upload(&local.to_string_lossy(), "dst/", &options(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            operator.read("dst/data.bin").await.unwrap().to_vec(),
+            b"payload"
+        );
+
+        // Without a retry budget the mismatch is reported.
+        let operator = fs_operator(&root.join("remote")).layer(CorruptFirstWrite::default());
+        let err = OpenDalUploader::new(operator.clone(), Default::default())
+            .upload(&local.to_string_lossy(), "other/", &options(0))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ChecksumMismatch { .. }));
+        assert!(!operator.exists("other/data.bin").await.unwrap());
+    }
+
+    #[test]
+    fn test_compare_modified_guards() {
+        let now = Utc::now();
+        let hour = chrono::Duration::hours(1);
+        let only_newer = UploadOptions {
+            only_newer: true,
+            ..Default::default()
+        };
+        assert_eq!(compare_modified(now, Some(now - hour), &only_newer), None);
+        assert!(compare_modified(now, Some(now), &only_newer).is_some());
+        assert!(compare_modified(now, Some(now + hour), &only_newer).is_some());
+        assert_eq!(compare_modified(now, None, &only_newer), None);
+
+        let skip_undated = UploadOptions {
+            skip_undated: true,
+            ..only_newer
+        };
+        assert!(compare_modified(now, None, &skip_undated).is_some());
+
+        let no_overwrite_newer = UploadOptions {
+            no_overwrite_newer: true,
+            ..Default::default()
+        };
+        assert_eq!(compare_modified(now, Some(now), &no_overwrite_newer), None);
+        assert!(compare_modified(now, Some(now + hour), &no_overwrite_newer).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_part_buffer_reuses_released_blocks() {
+        let data: Vec<u8> = (0..10u8).collect();
+        let mut reader = data.as_slice();
+        let mut buffer = PartBuffer::new(4);
+
+        let first = buffer.fill(&mut reader).await.unwrap();
+        assert_eq!(first.as_ref(), &[0, 1, 2, 3]);
+        let first_ptr = first.as_ptr();
+        drop(first);
+
+        // The released block's memory backs the next one.
+        let second = buffer.fill(&mut reader).await.unwrap();
+        assert_eq!(second.as_ref(), &[4, 5, 6, 7]);
+        assert_eq!(second.as_ptr(), first_ptr);
+
+        // A block still held by the writer is never overwritten.
+        let third = buffer.fill(&mut reader).await.unwrap();
+        assert_eq!(third.as_ref(), &[8, 9]);
+        assert_eq!(second.as_ref(), &[4, 5, 6, 7]);
+        assert!(buffer.fill(&mut reader).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_multipart_part_size() {
+        assert_eq!(UploadOptions::default().multipart_part_size(), None);
+        let concurrent = UploadOptions {
+            part_concurrency: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(concurrent.multipart_part_size(), Some(DEFAULT_PART_SIZE));
+        let sized = UploadOptions {
+            part_size: Some(5 << 20),
+            ..concurrent
+        };
+        assert_eq!(sized.multipart_part_size(), Some(5 << 20));
+    }
+
+    #[tokio::test]
+    async fn test_verify_remote_checksum_detects_corruption() {
+        let dir = TempDir::new();
+        let operator = fs_operator(dir.path());
+        let algorithm = ChecksumAlgorithm::Sha256;
+
+        operator
+            .write("good.txt", b"payload".to_vec())
+            .await
+            .unwrap();
+        let expected = checksum(algorithm, b"payload");
+        verify_remote_checksum(&operator, "good.txt", algorithm, &expected)
+            .await
+            .unwrap();
+
+        // Simulate a backend that stored different bytes than were sent.
+        operator
+            .write("bad.txt", b"pay1oad".to_vec())
+            .await
+            .unwrap();
+        let err = verify_remote_checksum(&operator, "bad.txt", algorithm, &expected)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ChecksumMismatch { .. }));
+        assert!(!operator.exists("bad.txt").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_file_snapshot_flags_changes_during_upload() {
+        let root = TempDir::new();
+        let local = root.join("app.log");
+        fs::write(&local, "line 1\n").await.unwrap();
+
+        let snapshot = FileSnapshot::of(&fs::metadata(&local).await.unwrap());
+        snapshot.ensure_unchanged(&local).await.unwrap();
+
+        // Same size, but touched while the upload was reading it.
+        let touched = SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&local)
+            .unwrap()
+            .set_modified(touched)
+            .unwrap();
+        let err = snapshot.ensure_unchanged(&local).await.unwrap_err();
+        assert!(matches!(err, Error::LocalFileChanged { .. }));
+
+        // An unchanged file uploads normally with the check enabled.
+        let operator = fs_operator(&root.join("remote"));
+        let options = UploadOptions {
+            fail_if_changed: true,
+            ..Default::default()
+        };
+        OpenDalUploader::new(operator.clone(), Default::default())
+            .upload(&local.to_string_lossy(), "logs/", &options)
+            .await
+            .unwrap();
+        assert!(operator.exists("logs/app.log").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_transfer_log_has_row_per_file() {
+        let root = TempDir::new();
+        let local = root.join("local");
+        fs::create_dir_all(local.join("sub")).await.unwrap();
+        fs::write(local.join("a.txt"), "hello").await.unwrap();
+        fs::write(local.join("sub/b.bin"), vec![7u8; 1024])
+            .await
+            .unwrap();
+
+        let log_path = root.join("log.csv");
+        let options = UploadOptions {
+            recursive: true,
+            transfer_log: Some(TransferLog::open(&log_path).unwrap()),
+            ..Default::default()
+        };
+        let uploader = OpenDalUploader::new(fs_operator(&root.join("remote")), Default::default());
+        uploader
+            .upload(&local.to_string_lossy(), "dst/", &options)
+            .await
+            .unwrap();
+
+        let log = std::fs::read_to_string(&log_path).unwrap();
+        let mut rows: Vec<Vec<&str>> = log
+            .lines()
+            .skip(1)
+            .map(|l| l.split(',').collect())
+            .collect();
+        rows.sort_by_key(|fields| fields[2]);
+        assert_eq!(rows.len(), 2);
+        for (fields, (key, bytes)) in rows
+            .iter()
+            .zip([("dst/a.txt", "5"), ("dst/sub/b.bin", "1024")])
+        {
+            assert_eq!(fields.len(), 6);
+            assert_eq!(&fields[1..5], ["put", key, bytes, "ok"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_empty_file_creates_empty_object() {
+        let root = TempDir::new();
+        let local = root.join("empty.txt");
+        fs::write(&local, "").await.unwrap();
+
+        let operator = fs_operator(&root.join("remote"));
+        let uploader = OpenDalUploader::new(operator.clone(), Default::default());
+        let multipart = UploadOptions {
+            part_size: Some(5 * 1024 * 1024),
+            part_concurrency: Some(2),
+            ..Default::default()
+        };
+        for (prefix, options) in [
+            ("one-shot/", UploadOptions::default()),
+            ("parts/", multipart),
+        ] {
+            let stats = uploader
+                .upload(&local.to_string_lossy(), prefix, &options)
+                .await
+                .unwrap();
+            assert_eq!((stats.files, stats.bytes), (1, 0));
+            let meta = operator.stat(&format!("{prefix}empty.txt")).await.unwrap();
+            assert!(meta.is_file());
+            assert_eq!(meta.content_length(), 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manifest_lists_every_uploaded_object() {
+        let root = TempDir::new();
+        let local = root.join("local");
+        fs::create_dir_all(local.join("sub")).await.unwrap();
+        fs::write(local.join("a.txt"), "hello").await.unwrap();
+        fs::write(local.join("sub/b.bin"), vec![7u8; 1024])
+            .await
+            .unwrap();
+        fs::write(local.join("sub/empty"), "").await.unwrap();
+
+        let manifest_path = root.join("manifest.txt");
+        let options = UploadOptions {
+            recursive: true,
+            manifest: Some(Manifest::create(&manifest_path).unwrap()),
+            ..Default::default()
+        };
+        let uploader = OpenDalUploader::new(fs_operator(&root.join("remote")), Default::default());
+        let stats = uploader
+            .upload(&local.to_string_lossy(), "dst/", &options)
+            .await
+            .unwrap();
+        assert_eq!((stats.files, stats.bytes), (3, 1029));
+
+        let manifest = std::fs::read_to_string(&manifest_path).unwrap();
+        let mut entries: Vec<(&str, &str)> = manifest
+            .lines()
+            .map(|line| {
+                let fields: Vec<&str> = line.split("  ").collect();
+                assert_eq!(fields.len(), 3, "{line}");
+                (fields[1], fields[2])
+            })
+            .collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            [
+                ("dst/a.txt", "5"),
+                ("dst/sub/b.bin", "1024"),
+                ("dst/sub/empty", "0")
+            ]
+        );
     }
 }
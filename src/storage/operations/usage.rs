@@ -1,6 +1,58 @@
-use crate::error::Result;
-use futures::stream::TryStreamExt;
+use crate::error::{Error, Result};
+use crate::storage::constants::DEFAULT_USAGE_CONCURRENCY;
+use crate::storage::operations::stat::listed_size;
+use crate::storage::utils::json::JsonStyle;
+use crate::storage::utils::path::{build_remote_path, get_root_relative_path};
+use crate::storage::utils::size::format_size;
+use crate::storage::utils::time::{TimeRange, report_undated};
+use futures::stream::{Stream, TryStreamExt};
 use opendal::Operator;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+
+/// Layout of `du` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum UsageFormat {
+    /// Human-readable sizes, one path per line, then the total
+    #[default]
+    Text,
+    /// One JSON array of `{path, size, files}` records in bytes, the total last
+    Json,
+}
+
+/// Options controlling how `du` aggregates and prints sizes.
+#[derive(Debug, Clone, Default)]
+pub struct UsageOptions {
+    /// Show summary only
+    pub summary: bool,
+    /// In summary mode, also print a rollup line for every prefix up to this depth
+    pub max_depth: Option<usize>,
+    /// Print one total per `/`-delimited key prefix instead of one line per object,
+    /// whether or not the backend stores directory markers
+    pub by_prefix: bool,
+    /// Only count objects modified inside this window
+    pub time_range: TimeRange,
+    /// Print one `<bytes> <files> <path>` line and nothing else
+    pub compact: bool,
+    /// Total sizes and counts per file extension, largest first
+    pub by_extension: bool,
+    /// Text lines or JSON records
+    pub format: UsageFormat,
+    /// Layout of JSON output
+    pub json_style: JsonStyle,
+}
+
+impl UsageOptions {
+    /// Prefix depth to roll sizes up to, if any; `by_prefix` defaults to one level.
+    fn rollup_depth(&self) -> Option<usize> {
+        if self.by_prefix {
+            Some(self.max_depth.unwrap_or(1))
+        } else {
+            self.max_depth.filter(|_| self.summary)
+        }
+    }
+}
 
 /// Trait for calculating disk usage in storage.
 pub trait UsageCalculator {
@@ -8,11 +60,11 @@ pub trait UsageCalculator {
     ///
     /// # Arguments
     /// * `path` - Path to calculate usage for
-    /// * `summary` - Whether to show summary only or detailed listing
+    /// * `options` - Aggregation and output options
     ///
     /// # Returns
     /// * `Result<()>` - Success or detailed error information
-    async fn calculate_usage(&self, path: &str, summary: bool) -> Result<()>;
+    async fn calculate_usage(&self, path: &str, options: &UsageOptions) -> Result<()>;
 }
 
 /// Implementation of UsageCalculator for OpenDAL Operator.
@@ -27,30 +79,441 @@ impl OpenDalUsageCalculator {
     }
 }
 
-impl UsageCalculator for OpenDalUsageCalculator {
-    async fn calculate_usage(&self, path: &str, summary: bool) -> Result<()> {
-        let lister = self.operator.lister_with(path).recursive(true).await?;
-        let (total_size, total_files) = lister
-            .try_fold((0, 0), |(size, count), entry| async move {
+/// Total size and object count under `path`, from a single recursive listing.
+/// Directory entries are not counted.
+pub async fn calculate_total_usage(operator: &Operator, path: &str) -> Result<(u64, usize)> {
+    let lister = operator.lister_with(path).recursive(true).await?;
+    let totals = lister
+        .map_err(Error::from)
+        .try_fold((0u64, 0usize), |(size, count), entry| async move {
+            if entry.metadata().is_dir() {
+                Ok((size, count))
+            } else {
+                Ok((size + listed_size(operator, &entry).await?, count + 1))
+            }
+        })
+        .await?;
+    Ok(totals)
+}
+
+/// Same totals as [`calculate_total_usage`], but each top-level child prefix is listed
+/// recursively in parallel (up to `concurrency` at a time).
+pub async fn calculate_total_usage_concurrent(
+    operator: &Operator,
+    path: &str,
+    concurrency: usize,
+) -> Result<(u64, usize)> {
+    let root = path.trim_start_matches('/');
+    let children = operator
+        .lister(path)
+        .await?
+        .map_err(Error::from)
+        .try_filter_map(|entry| async move {
+            let child = if !entry.metadata().is_dir() {
+                Some(UsageChild::File(listed_size(operator, &entry).await?))
+            } else if entry.path().trim_start_matches('/') != root {
+                Some(UsageChild::Dir(entry.path().to_string()))
+            } else {
+                None
+            };
+            Ok(child)
+        });
+    sum_children(children, concurrency, |dir| async move {
+        calculate_total_usage(operator, &dir).await
+    })
+    .await
+}
+
+/// Size and object count of one group in `du --by-extension`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExtensionUsage {
+    pub size: u64,
+    pub files: usize,
+}
+
+/// Object sizes under `path` grouped by extension, sorted by total size descending (ties
+/// by extension). Objects outside `time_range` are left out.
+pub async fn calculate_usage_by_extension(
+    operator: &Operator,
+    path: &str,
+    time_range: TimeRange,
+) -> Result<Vec<(String, ExtensionUsage)>> {
+    let lister = operator.lister_with(path).recursive(true).await?;
+    let (groups, undated) = lister
+        .map_err(Error::from)
+        .try_fold(
+            (BTreeMap::<String, ExtensionUsage>::new(), 0usize),
+            |(mut groups, undated), entry| async move {
                 let meta = entry.metadata();
-                if !summary {
-                    println!(
-                        "{} {}",
-                        crate::storage::utils::size::format_size(meta.content_length()),
-                        entry.path()
-                    );
+                if meta.is_dir() {
+                    return Ok((groups, undated));
                 }
-                Ok((size + meta.content_length(), count + 1))
-            })
-            .await?;
+                if !time_range.contains(meta.last_modified()) {
+                    let undated = undated + usize::from(meta.last_modified().is_none());
+                    return Ok((groups, undated));
+                }
+                let group = groups.entry(extension_of(entry.path())).or_default();
+                group.size += listed_size(operator, &entry).await?;
+                group.files += 1;
+                Ok((groups, undated))
+            },
+        )
+        .await?;
+    report_undated(undated);
 
-        if summary {
-            println!(
-                "{} {path}",
-                crate::storage::utils::size::format_size(total_size)
-            );
-            println!("Total files: {total_files}");
+    let mut ranked: Vec<_> = groups.into_iter().collect();
+    ranked.sort_by(|(a_ext, a), (b_ext, b)| b.size.cmp(&a.size).then_with(|| a_ext.cmp(b_ext)));
+    Ok(ranked)
+}
+
+/// The `du --by-extension` group of a key: the lowercased extension of its last component
+/// with the dot (`.log`), or `(none)`. Dotfiles such as `.env` have no extension.
+fn extension_of(key: &str) -> String {
+    let name = key.rsplit('/').next().unwrap_or(key);
+    std::path::Path::new(name)
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy().to_lowercase()))
+        .unwrap_or_else(|| "(none)".to_string())
+}
+
+/// One `du --format json` record.
+#[derive(Debug, Serialize)]
+struct UsageRecord {
+    path: String,
+    size: u64,
+    files: usize,
+}
+
+/// Where `du` lines go: printed as they come in text mode, or collected into one JSON
+/// array printed with the total.
+struct UsageReport {
+    json_style: Option<JsonStyle>,
+    records: Vec<UsageRecord>,
+}
+
+impl UsageReport {
+    fn new(options: &UsageOptions) -> Self {
+        Self {
+            json_style: (options.format == UsageFormat::Json).then_some(options.json_style),
+            records: Vec::new(),
+        }
+    }
+
+    /// Report one object or prefix.
+    fn line(&mut self, path: &str, size: u64, files: usize) {
+        if self.json_style.is_some() {
+            self.records.push(UsageRecord {
+                path: path.to_string(),
+                size,
+                files,
+            });
+        } else {
+            println!("{} {path}", format_size(size));
+        }
+    }
+
+    /// Report the total for the `du` root and flush any collected records.
+    fn total(mut self, path: &str, size: u64, files: usize) -> Result<()> {
+        self.line(path, size, files);
+        match self.json_style {
+            Some(style) => println!("{}", style.render(&self.records)?),
+            None => println!("Total files: {files}"),
         }
         Ok(())
     }
 }
+
+/// One entry of the top-level listing summed by [`calculate_total_usage_concurrent`].
+enum UsageChild {
+    File(u64),
+    Dir(String),
+}
+
+/// Fold a listing into `(bytes, files)` as it arrives: files count at once and each
+/// directory is totalled by `dir_usage`, at most `concurrency` at a time. Only those
+/// in-flight directories are held, however long the listing is.
+async fn sum_children<F, Fut>(
+    children: impl Stream<Item = Result<UsageChild>>,
+    concurrency: usize,
+    dir_usage: F,
+) -> Result<(u64, usize)>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<(u64, usize)>>,
+{
+    let dir_usage = &dir_usage;
+    children
+        .map_ok(move |child| async move {
+            match child {
+                UsageChild::File(size) => Ok((size, 1)),
+                UsageChild::Dir(dir) => dir_usage(dir).await,
+            }
+        })
+        .try_buffer_unordered(concurrency.max(1))
+        .try_fold(
+            (0, 0),
+            |(size, count), (child_size, child_count)| async move {
+                Ok((size + child_size, count + child_count))
+            },
+        )
+        .await
+}
+
+impl UsageCalculator for OpenDalUsageCalculator {
+    async fn calculate_usage(&self, path: &str, options: &UsageOptions) -> Result<()> {
+        let summary = options.summary || options.by_prefix;
+        let max_depth = options.rollup_depth();
+        let time_range = options.time_range;
+        if options.by_extension {
+            let groups =
+                calculate_usage_by_extension(&self.operator, path, options.time_range).await?;
+            for (extension, usage) in &groups {
+                println!("{extension}: {} / {}", format_size(usage.size), usage.files);
+            }
+            let total_size: u64 = groups.iter().map(|(_, usage)| usage.size).sum();
+            let total_files: usize = groups.iter().map(|(_, usage)| usage.files).sum();
+            println!("{} {path}", format_size(total_size));
+            println!("Total files: {total_files}");
+            return Ok(());
+        }
+        if options.compact {
+            let (total_size, total_files) =
+                calculate_total_usage_concurrent(&self.operator, path, DEFAULT_USAGE_CONCURRENCY)
+                    .await?;
+            println!("{}", compact_line(total_size, total_files, path));
+            return Ok(());
+        }
+        // The concurrent fast path cannot filter by date, so ranged queries list serially.
+        if summary && max_depth.is_none() && time_range.is_unbounded() {
+            let (total_size, total_files) =
+                calculate_total_usage_concurrent(&self.operator, path, DEFAULT_USAGE_CONCURRENCY)
+                    .await?;
+            return UsageReport::new(options).total(path, total_size, total_files);
+        }
+
+        let operator = &self.operator;
+        let lister = operator.lister_with(path).recursive(true).await?;
+        let (total_size, total_files, prefixes, undated, mut report) = lister
+            .map_err(Error::from)
+            .try_fold(
+                (
+                    0,
+                    0,
+                    BTreeMap::<String, (u64, usize)>::new(),
+                    0usize,
+                    UsageReport::new(options),
+                ),
+                |(size, count, mut prefixes, undated, mut report), entry| async move {
+                    let meta = entry.metadata();
+                    if !time_range.is_unbounded()
+                        && (meta.is_dir() || !time_range.contains(meta.last_modified()))
+                    {
+                        let undated =
+                            undated + usize::from(!meta.is_dir() && meta.last_modified().is_none());
+                        return Ok((size, count, prefixes, undated, report));
+                    }
+                    let (entry_size, files) = if meta.is_dir() {
+                        (0, 0)
+                    } else {
+                        (listed_size(operator, &entry).await?, 1)
+                    };
+                    if !summary {
+                        report.line(entry.path(), entry_size, files);
+                    }
+                    if let Some(depth) = max_depth {
+                        let relative = get_root_relative_path(entry.path(), path);
+                        for prefix in rollup_prefixes(&relative, depth) {
+                            let (prefix_size, prefix_files) = prefixes.entry(prefix).or_default();
+                            *prefix_size += entry_size;
+                            *prefix_files += files;
+                        }
+                    }
+                    Ok((size + entry_size, count + files, prefixes, undated, report))
+                },
+            )
+            .await?;
+        report_undated(undated);
+
+        if !summary && report.json_style.is_none() {
+            return Ok(());
+        }
+        for (prefix, (size, files)) in &prefixes {
+            report.line(&build_remote_path(path, prefix), *size, *files);
+        }
+        report.total(path, total_size, total_files)
+    }
+}
+
+/// The `du --compact` line: raw byte count, file count and path, space-separated.
+fn compact_line(size: u64, files: usize, path: &str) -> String {
+    format!("{size} {files} {path}")
+}
+
+/// Directory prefixes (relative to the `du` root) an entry's size rolls up into,
+/// limited to `max_depth` levels. Content deeper than the limit is attributed to its
+/// ancestor at the boundary.
+fn rollup_prefixes(relative_path: &str, max_depth: usize) -> Vec<String> {
+    let mut components: Vec<&str> = relative_path.split('/').filter(|c| !c.is_empty()).collect();
+    // The last component names the entry itself; only directory entries count as a level.
+    if !relative_path.ends_with('/') {
+        components.pop();
+    }
+
+    let mut prefix = String::new();
+    components
+        .into_iter()
+        .take(max_depth)
+        .map(|component| {
+            prefix.push_str(component);
+            prefix.push('/');
+            prefix.clone()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::utils::fixture::{TempDir, fs_operator};
+
+    #[test]
+    fn rollup_prefixes_stops_at_depth() {
+        assert_eq!(rollup_prefixes("a/b/c.txt", 1), vec!["a/"]);
+        assert_eq!(rollup_prefixes("a/b/c.txt", 2), vec!["a/", "a/b/"]);
+        assert_eq!(rollup_prefixes("a/b/c.txt", 5), vec!["a/", "a/b/"]);
+        assert_eq!(rollup_prefixes("a/b/", 5), vec!["a/", "a/b/"]);
+    }
+
+    #[tokio::test]
+    async fn concurrent_total_matches_serial_total() {
+        let dir = TempDir::new();
+        let operator = fs_operator(dir.path());
+        for (path, size) in [
+            ("tree/top.bin", 7),
+            ("tree/a/one.bin", 100),
+            ("tree/a/b/two.bin", 200),
+            ("tree/c/three.bin", 3),
+            ("tree/c/empty.bin", 0),
+        ] {
+            operator.write(path, vec![b'x'; size]).await.unwrap();
+        }
+        operator.create_dir("tree/d/").await.unwrap();
+
+        let serial = calculate_total_usage(&operator, "tree/").await.unwrap();
+        let concurrent = calculate_total_usage_concurrent(&operator, "tree/", 2)
+            .await
+            .unwrap();
+        assert_eq!(serial, (310, 5));
+        assert_eq!(concurrent, serial);
+        assert_eq!(
+            compact_line(concurrent.0, concurrent.1, "tree/"),
+            "310 5 tree/"
+        );
+    }
+
+    #[test]
+    fn rollup_depth_defaults_to_one_level_by_prefix() {
+        let by_prefix = UsageOptions {
+            by_prefix: true,
+            ..Default::default()
+        };
+        assert_eq!(by_prefix.rollup_depth(), Some(1));
+        let deeper = UsageOptions {
+            max_depth: Some(3),
+            ..by_prefix
+        };
+        assert_eq!(deeper.rollup_depth(), Some(3));
+        let unsummarized = UsageOptions {
+            max_depth: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(unsummarized.rollup_depth(), None);
+    }
+
+    #[tokio::test]
+    async fn sum_children_streams_large_listings() {
+        use futures::StreamExt;
+        use std::cell::Cell;
+
+        const ENTRIES: usize = 100_000;
+        const CONCURRENCY: usize = 4;
+        let listed = Cell::new(0usize);
+        let summed = Cell::new(0usize);
+        let peak_pending = Cell::new(0usize);
+        // A synthetic listing produced lazily, one directory of 10 bytes per entry.
+        let children = futures::stream::iter(0..ENTRIES).map(|i| {
+            listed.set(listed.get() + 1);
+            peak_pending.set(peak_pending.get().max(listed.get() - summed.get()));
+            Ok(UsageChild::Dir(format!("dir-{i}/")))
+        });
+        let totals = sum_children(children, CONCURRENCY, |_dir| {
+            let summed = &summed;
+            async move {
+                tokio::task::yield_now().await;
+                summed.set(summed.get() + 1);
+                Ok((10, 1))
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(totals, (ENTRIES as u64 * 10, ENTRIES));
+        // The listing is consumed incrementally: never more than the in-flight
+        // directories ahead of the totals.
+        assert!(peak_pending.get() <= CONCURRENCY, "{}", peak_pending.get());
+
+        let mixed = futures::stream::iter([
+            Ok(UsageChild::File(5)),
+            Ok(UsageChild::Dir("a/".to_string())),
+            Ok(UsageChild::File(0)),
+        ]);
+        let totals = sum_children(mixed, CONCURRENCY, |_dir| async { Ok((100, 2)) })
+            .await
+            .unwrap();
+        assert_eq!(totals, (105, 4));
+    }
+
+    #[test]
+    fn extension_of_groups_by_last_component() {
+        assert_eq!(extension_of("logs/app.LOG"), ".log");
+        assert_eq!(extension_of("data/archive.tar.gz"), ".gz");
+        assert_eq!(extension_of("conf/.env"), "(none)");
+        assert_eq!(extension_of("v1.2/README"), "(none)");
+    }
+
+    #[tokio::test]
+    async fn usage_by_extension_groups_and_ranks_by_size() {
+        let dir = TempDir::new();
+        let operator = fs_operator(dir.path());
+        for (path, size) in [
+            ("mix/a.log", 40),
+            ("mix/sub/b.log", 60),
+            ("mix/sub/c.LOG", 5),
+            ("mix/d.parquet", 500),
+            ("mix/e.csv", 1),
+            ("mix/f.csv", 1),
+            ("mix/README", 7),
+        ] {
+            operator.write(path, vec![b'x'; size]).await.unwrap();
+        }
+
+        let groups = calculate_usage_by_extension(&operator, "mix/", TimeRange::default())
+            .await
+            .unwrap();
+        let usage = |size, files| ExtensionUsage { size, files };
+        assert_eq!(
+            groups,
+            vec![
+                (".parquet".to_string(), usage(500, 1)),
+                (".log".to_string(), usage(105, 3)),
+                ("(none)".to_string(), usage(7, 1)),
+                (".csv".to_string(), usage(2, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rollup_prefixes_skips_top_level_files() {
+        assert!(rollup_prefixes("c.txt", 3).is_empty());
+        assert!(rollup_prefixes("a/c.txt", 0).is_empty());
+    }
+}
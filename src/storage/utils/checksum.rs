@@ -0,0 +1,170 @@
+// Content checksums used to verify transfers end to end
+use crate::error::{Error, Result};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::future::Future;
+
+/// Checksum algorithm used to verify object content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ChecksumAlgorithm {
+    /// CRC32C (Castagnoli); validated server-side by S3-compatible backends
+    Crc32c,
+    /// SHA-256
+    Sha256,
+    /// MD5; only used to check downloads against plain S3-style ETags
+    #[value(skip)]
+    Md5,
+}
+
+impl ChecksumAlgorithm {
+    /// Name understood by the S3 service's `checksum_algorithm` setting, if any.
+    pub fn s3_name(self) -> Option<&'static str> {
+        match self {
+            ChecksumAlgorithm::Crc32c => Some("crc32c"),
+            ChecksumAlgorithm::Sha256 | ChecksumAlgorithm::Md5 => None,
+        }
+    }
+}
+
+/// Incremental checksum over streamed chunks.
+pub enum ChecksumHasher {
+    Crc32c(u32),
+    Sha256(Sha256),
+    Md5(Md5),
+}
+
+impl ChecksumHasher {
+    pub fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Crc32c => Self::Crc32c(0),
+            ChecksumAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            ChecksumAlgorithm::Md5 => Self::Md5(Md5::new()),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Crc32c(crc) => *crc = crc32c::crc32c_append(*crc, data),
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Md5(hasher) => hasher.update(data),
+        }
+    }
+
+    /// Hex-encoded digest.
+    pub fn finalize(self) -> String {
+        match self {
+            Self::Crc32c(crc) => hex::encode(crc.to_be_bytes()),
+            Self::Sha256(hasher) => hex::encode(hasher.finalize()),
+            Self::Md5(hasher) => hex::encode(hasher.finalize()),
+        }
+    }
+}
+
+/// Checksum a complete buffer in one call.
+#[cfg(test)]
+pub fn checksum(algorithm: ChecksumAlgorithm, data: &[u8]) -> String {
+    let mut hasher = ChecksumHasher::new(algorithm);
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Run `transfer`, running it again up to `retries` more times while it fails with a
+/// checksum mismatch. Other errors are returned at once.
+pub async fn retry_on_checksum_mismatch<T, F, Fut>(
+    path: &str,
+    retries: u32,
+    mut transfer: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retried = 0;
+    loop {
+        match transfer().await {
+            Err(err @ Error::ChecksumMismatch { .. }) if retried < retries => {
+                retried += 1;
+                eprintln!("Retrying after checksum mismatch ({retried}/{retries}): {path}: {err}");
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn mismatch() -> Error {
+        Error::ChecksumMismatch {
+            path: "key".to_string(),
+            expected: "00".to_string(),
+            actual: "ff".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_checksum_mismatch_within_budget() {
+        // The first attempt arrives corrupted, the second intact.
+        let attempts = Cell::new(0);
+        let result = retry_on_checksum_mismatch("key", 2, || {
+            attempts.set(attempts.get() + 1);
+            let corrupted = attempts.get() == 1;
+            async move { if corrupted { Err(mismatch()) } else { Ok(42) } }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.get(), 2);
+
+        // Out of budget: the last mismatch is reported.
+        attempts.set(0);
+        let result: Result<()> = retry_on_checksum_mismatch("key", 2, || {
+            attempts.set(attempts.get() + 1);
+            async { Err(mismatch()) }
+        })
+        .await;
+        assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+        assert_eq!(attempts.get(), 3);
+
+        // Other failures are not retried.
+        attempts.set(0);
+        let result: Result<()> = retry_on_checksum_mismatch("key", 2, || {
+            attempts.set(attempts.get() + 1);
+            async { Err(Error::from(std::io::Error::other("disk full"))) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[test]
+    fn test_known_digests() {
+        assert_eq!(
+            checksum(ChecksumAlgorithm::Crc32c, b"123456789"),
+            "e3069283"
+        );
+        assert_eq!(
+            checksum(ChecksumAlgorithm::Sha256, b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            checksum(ChecksumAlgorithm::Md5, b"abc"),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+    }
+
+    #[test]
+    fn test_incremental_matches_one_shot() {
+        for algorithm in [
+            ChecksumAlgorithm::Crc32c,
+            ChecksumAlgorithm::Sha256,
+            ChecksumAlgorithm::Md5,
+        ] {
+            let mut hasher = ChecksumHasher::new(algorithm);
+            hasher.update(b"hello ");
+            hasher.update(b"world");
+            assert_eq!(hasher.finalize(), checksum(algorithm, b"hello world"));
+        }
+    }
+}
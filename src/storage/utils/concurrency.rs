@@ -0,0 +1,652 @@
+// Bounded (optionally self-tuning) execution of per-object transfers
+use crate::error::Result;
+use crate::storage::constants::DEFAULT_DOWNLOAD_CONCURRENCY;
+use crate::storage::utils::error::{is_server_error, is_transient_error};
+use futures::SinkExt;
+use futures::channel::mpsc;
+use futures::future::{self, Either};
+use futures::stream::{FuturesUnordered, Stream, StreamExt};
+use std::cell::Cell;
+use std::fmt;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Concurrency the adaptive tuner starts from.
+const AUTO_INITIAL: usize = 2;
+/// Upper bound the adaptive tuner never exceeds.
+const AUTO_MAX: usize = 64;
+/// A round must be this much faster than the last one to earn another slot.
+const AUTO_GAIN: f64 = 1.05;
+
+/// How many transfers run at once: a fixed number, or `auto` to tune it on the fly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Concurrency {
+    Fixed(NonZeroUsize),
+    Auto,
+}
+
+impl Concurrency {
+    /// One transfer at a time.
+    pub const SERIAL: Self = Concurrency::Fixed(NonZeroUsize::MIN);
+}
+
+impl Default for Concurrency {
+    fn default() -> Self {
+        NonZeroUsize::new(DEFAULT_DOWNLOAD_CONCURRENCY).map_or(Self::SERIAL, Concurrency::Fixed)
+    }
+}
+
+impl FromStr for Concurrency {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(Concurrency::Auto);
+        }
+        s.parse::<NonZeroUsize>()
+            .map(Concurrency::Fixed)
+            .map_err(|_| format!("expected a positive number or 'auto', got '{s}'"))
+    }
+}
+
+impl fmt::Display for Concurrency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Concurrency::Fixed(n) => write!(f, "{n}"),
+            Concurrency::Auto => f.write_str("auto"),
+        }
+    }
+}
+
+/// Additive-increase/multiplicative-decrease controller for the number of transfers in
+/// flight.
+///
+/// Work is measured in rounds of `limit` completed transfers. After each round the limit
+/// grows by one if throughput beat the previous round; any failure (throttling included)
+/// halves it. A fixed limit ignores all feedback.
+#[derive(Debug)]
+pub struct AimdController {
+    limit: usize,
+    adaptive: bool,
+    round_done: usize,
+    round_bytes: u64,
+    round_started: Instant,
+    last_rate: Option<f64>,
+}
+
+impl AimdController {
+    pub fn new(concurrency: Concurrency) -> Self {
+        let (limit, adaptive) = match concurrency {
+            Concurrency::Fixed(n) => (n.get(), false),
+            Concurrency::Auto => (AUTO_INITIAL, true),
+        };
+        Self {
+            limit,
+            adaptive,
+            round_done: 0,
+            round_bytes: 0,
+            round_started: Instant::now(),
+            last_rate: None,
+        }
+    }
+
+    /// Transfers allowed in flight right now.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Count a finished transfer of `bytes`, completed at `now`.
+    pub fn on_success(&mut self, bytes: u64, now: Instant) {
+        if !self.adaptive {
+            return;
+        }
+        self.round_done += 1;
+        self.round_bytes += bytes;
+        if self.round_done < self.limit {
+            return;
+        }
+        let elapsed = now
+            .saturating_duration_since(self.round_started)
+            .as_secs_f64()
+            .max(f64::EPSILON);
+        let rate = self.round_bytes as f64 / elapsed;
+        if self.last_rate.is_none_or(|last| rate > last * AUTO_GAIN) && self.limit < AUTO_MAX {
+            self.limit += 1;
+            log::debug!(
+                "concurrency auto: throughput {rate:.0} B/s, raising to {}",
+                self.limit
+            );
+        }
+        self.last_rate = Some(rate);
+        self.start_round(now);
+    }
+
+    /// Back off after a failed (e.g. throttled) transfer.
+    pub fn on_failure(&mut self, now: Instant) {
+        if !self.adaptive {
+            return;
+        }
+        let reduced = (self.limit / 2).max(1);
+        if reduced < self.limit {
+            log::debug!(
+                "concurrency auto: backing off from {} to {reduced}",
+                self.limit
+            );
+        }
+        self.limit = reduced;
+        // Let the smaller window prove itself before comparing rates again.
+        self.last_rate = None;
+        self.start_round(now);
+    }
+
+    fn start_round(&mut self, now: Instant) {
+        self.round_done = 0;
+        self.round_bytes = 0;
+        self.round_started = now;
+    }
+}
+
+/// When to stop starting transfers because the backend keeps failing with 5xx responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerPolicy {
+    /// Consecutive server errors that open the breaker
+    pub threshold: NonZeroUsize,
+    /// How long no new transfer starts once it is open
+    pub cooldown: Duration,
+}
+
+/// Circuit breaker over a run of transfers.
+///
+/// After `threshold` consecutive server errors it opens: transfers already in flight finish,
+/// but none start until the cooldown has passed. It then closes half-way: a success resets
+/// it, while the next server error opens it again straight away. Without a policy it never
+/// opens.
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    policy: Option<BreakerPolicy>,
+    consecutive: usize,
+    open_until: Option<Instant>,
+    trips: usize,
+}
+
+impl CircuitBreaker {
+    pub fn new(policy: Option<BreakerPolicy>) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// How many times the breaker has opened.
+    #[cfg(test)]
+    pub fn trips(&self) -> usize {
+        self.trips
+    }
+
+    /// Count a finished transfer; `server_error` marks a 5xx failure.
+    pub fn record(&mut self, server_error: bool, now: Instant) {
+        let Some(policy) = self.policy else {
+            return;
+        };
+        if !server_error {
+            self.consecutive = 0;
+            return;
+        }
+        self.consecutive += 1;
+        if self.consecutive >= policy.threshold.get() && self.open_until.is_none() {
+            self.open_until = Some(now + policy.cooldown);
+            self.trips += 1;
+            eprintln!(
+                "Warning: {} consecutive server errors; pausing new transfers for {}s",
+                self.consecutive,
+                policy.cooldown.as_secs_f64()
+            );
+        }
+    }
+
+    /// While open, the instant new transfers may start again. Closes the breaker once that
+    /// instant has passed.
+    pub fn paused_until(&mut self, now: Instant) -> Option<Instant> {
+        let until = self.open_until?;
+        if now < until {
+            return Some(until);
+        }
+        self.open_until = None;
+        // Half-open: one more server error is enough to trip it again.
+        self.consecutive = self.policy.map_or(0, |p| p.threshold.get() - 1);
+        eprintln!("Note: server errors cooled down; resuming transfers");
+        None
+    }
+}
+
+/// How `--endpoint-health-check` rides out an endpoint that stops answering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthPolicy {
+    /// Time between pings while the endpoint is down
+    pub interval: Duration,
+    /// Longest outage waited out before transfers fail
+    pub max_wait: Duration,
+}
+
+/// Endpoint health shared by the transfers of one job.
+///
+/// When a transfer fails with a temporary error and a ping confirms the endpoint is down,
+/// that transfer pings it every `interval` until it answers and then runs again.
+/// Transfers failing meanwhile queue behind it and retry once it is back, instead of each
+/// failing the job. An outage longer than `max_wait` is given up on, and transfers fail
+/// with their own errors from then on. Without a policy transfers run unguarded.
+#[derive(Debug, Default)]
+pub struct EndpointHealth {
+    policy: Option<HealthPolicy>,
+    /// Held by the transfer pinging the endpoint; the others wait their turn
+    watch: tokio::sync::Mutex<()>,
+    /// Outages ridden out so far, so a queued transfer can tell one ended meanwhile
+    recoveries: Cell<usize>,
+    gave_up: Cell<bool>,
+}
+
+impl EndpointHealth {
+    pub fn new(policy: Option<HealthPolicy>) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Run `transfer`, waiting out endpoint outages; `ping` reports whether the endpoint
+    /// answers at all.
+    pub async fn guard<T, F, Fut, P, PFut>(&self, ping: P, transfer: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+        P: Fn() -> PFut,
+        PFut: Future<Output = bool>,
+    {
+        loop {
+            let seen = self.recoveries.get();
+            let result = transfer().await;
+            match (self.policy, &result) {
+                (Some(policy), Err(err)) if is_transient_error(err) => {
+                    if !self.wait_for_recovery(policy, seen, &ping).await {
+                        return result;
+                    }
+                }
+                _ => return result,
+            }
+        }
+    }
+
+    /// Wait for the endpoint to answer again, returning whether to retry the transfer:
+    /// `false` when the endpoint was up all along (the failure was the transfer's own) or
+    /// the outage outlasted `max_wait`.
+    async fn wait_for_recovery<P, PFut>(&self, policy: HealthPolicy, seen: usize, ping: &P) -> bool
+    where
+        P: Fn() -> PFut,
+        PFut: Future<Output = bool>,
+    {
+        let _watch = self.watch.lock().await;
+        if self.gave_up.get() {
+            return false;
+        }
+        if self.recoveries.get() != seen {
+            // An outage ended while this transfer was failing or queued.
+            return true;
+        }
+        if ping().await {
+            return false;
+        }
+        eprintln!(
+            "Warning: endpoint is not answering; pausing transfers until it does (up to {}s)",
+            policy.max_wait.as_secs_f64()
+        );
+        let deadline = Instant::now() + policy.max_wait;
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            tokio::time::sleep(policy.interval.min(deadline - now)).await;
+            if ping().await {
+                self.recoveries.set(self.recoveries.get() + 1);
+                eprintln!("Note: endpoint is answering again; resuming transfers");
+                return true;
+            }
+        }
+        self.gave_up.set(true);
+        eprintln!(
+            "Warning: endpoint still not answering after {}s; failing the affected transfers",
+            policy.max_wait.as_secs_f64()
+        );
+        false
+    }
+}
+
+/// Pull `items` up to `capacity` entries ahead of whoever consumes the returned stream.
+///
+/// The producer half must be polled alongside the consumer (e.g. with `future::join`); it
+/// keeps listing while every transfer slot is busy instead of waiting for one to free up.
+/// Items, errors included, arrive in order; the producer stops after forwarding an error
+/// or once the stream is dropped.
+pub fn read_ahead<I>(
+    items: impl Stream<Item = Result<I>>,
+    capacity: usize,
+) -> (impl Future<Output = ()>, impl Stream<Item = Result<I>>) {
+    let (mut sender, receiver) = mpsc::channel(capacity);
+    let producer = async move {
+        let mut items = std::pin::pin!(items);
+        while let Some(item) = items.next().await {
+            let failed = item.is_err();
+            if sender.send(item).await.is_err() || failed {
+                break;
+            }
+        }
+    };
+    (producer, receiver)
+}
+
+/// Run `task` for every item, keeping at most `controller.limit()` tasks in flight.
+///
+/// Each task resolves to a label (used in messages) and the bytes it transferred.
+/// `on_done` sees every outcome in completion order; returning an error stops the run and
+/// drops the tasks still in flight, which cancels them. While `breaker` is open no new task
+/// is started.
+pub async fn run_bounded<I, F, Fut>(
+    items: impl Stream<Item = Result<I>>,
+    controller: &mut AimdController,
+    breaker: &mut CircuitBreaker,
+    task: F,
+    mut on_done: impl FnMut(String, Result<u64>) -> Result<()>,
+) -> Result<()>
+where
+    F: Fn(I) -> Fut,
+    Fut: Future<Output = (String, Result<u64>)>,
+{
+    let mut items = std::pin::pin!(items);
+    let mut in_flight = FuturesUnordered::new();
+    let mut exhausted = false;
+
+    loop {
+        let paused_until = if exhausted {
+            None
+        } else {
+            breaker.paused_until(Instant::now())
+        };
+        if let Some(until) = paused_until
+            && in_flight.is_empty()
+        {
+            tokio::time::sleep_until(until.into()).await;
+            continue;
+        }
+        let wants_item =
+            !exhausted && paused_until.is_none() && in_flight.len() < controller.limit();
+        // Keep running transfers moving while the next item is fetched (e.g. a listing page).
+        let event = if wants_item && in_flight.is_empty() {
+            Either::Left(items.next().await)
+        } else if wants_item {
+            match future::select(items.next(), in_flight.next()).await {
+                Either::Left((item, _)) => Either::Left(item),
+                Either::Right((done, _)) => Either::Right(done),
+            }
+        } else {
+            Either::Right(in_flight.next().await)
+        };
+
+        match event {
+            Either::Left(Some(item)) => in_flight.push(task(item?)),
+            Either::Left(None) => exhausted = true,
+            Either::Right(Some((label, result))) => {
+                let now = Instant::now();
+                match &result {
+                    Ok(bytes) => controller.on_success(*bytes, now),
+                    Err(_) => controller.on_failure(now),
+                }
+                breaker.record(result.as_ref().is_err_and(is_server_error), now);
+                on_done(label, result)?;
+            }
+            Either::Right(None) => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use futures::stream;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_parse_concurrency() {
+        assert_eq!("auto".parse::<Concurrency>(), Ok(Concurrency::Auto));
+        assert_eq!(
+            "8".parse::<Concurrency>(),
+            Ok(Concurrency::Fixed(NonZeroUsize::new(8).unwrap()))
+        );
+        assert!("0".parse::<Concurrency>().is_err());
+        assert!("fast".parse::<Concurrency>().is_err());
+        assert_eq!(Concurrency::default().to_string(), "8");
+    }
+
+    #[test]
+    fn test_aimd_ramps_up_while_throughput_improves() {
+        let start = Instant::now();
+        let mut controller = AimdController::new(Concurrency::Auto);
+        assert_eq!(controller.limit(), AUTO_INITIAL);
+
+        // Two transfers of 1 MiB in one second, then three in one second: faster each time.
+        controller.on_success(1 << 20, start);
+        controller.on_success(1 << 20, start + Duration::from_secs(1));
+        assert_eq!(controller.limit(), 3);
+        for _ in 0..3 {
+            controller.on_success(1 << 20, start + Duration::from_secs(2));
+        }
+        assert_eq!(controller.limit(), 4);
+
+        // A round no faster than the last one keeps the limit.
+        for _ in 0..4 {
+            controller.on_success(1 << 20, start + Duration::from_secs(4));
+        }
+        assert_eq!(controller.limit(), 4);
+
+        let mut fixed = AimdController::new(Concurrency::Fixed(NonZeroUsize::new(5).unwrap()));
+        fixed.on_success(1 << 20, start + Duration::from_secs(1));
+        fixed.on_failure(start);
+        assert_eq!(fixed.limit(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_throttling_reduces_concurrency() {
+        let start = Instant::now();
+        let mut controller = AimdController::new(Concurrency::Auto);
+        for second in [1, 1, 2, 2, 2] {
+            controller.on_success(1 << 20, start + Duration::from_secs(second));
+        }
+        let ramped = controller.limit();
+        assert_eq!(ramped, 4);
+
+        let in_flight = Cell::new(0usize);
+        let peak_after_throttle = Cell::new(0usize);
+        let throttled = Cell::new(false);
+        let items = stream::iter((0..40).map(Ok));
+        let result = run_bounded(
+            items,
+            &mut controller,
+            &mut CircuitBreaker::default(),
+            |i: usize| {
+                in_flight.set(in_flight.get() + 1);
+                if throttled.get() {
+                    peak_after_throttle.set(peak_after_throttle.get().max(in_flight.get()));
+                }
+                let in_flight = &in_flight;
+                let throttled = &throttled;
+                async move {
+                    tokio::task::yield_now().await;
+                    in_flight.set(in_flight.get() - 1);
+                    // Every transfer after the first few is answered with a throttling error.
+                    if i >= 4 {
+                        throttled.set(true);
+                        let err = opendal::Error::new(opendal::ErrorKind::RateLimited, "slow down");
+                        return (format!("key-{i}"), Err(Error::from(err)));
+                    }
+                    (format!("key-{i}"), Ok(1 << 20))
+                }
+            },
+            |_, _| Ok(()),
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(controller.limit(), 1);
+        assert!(peak_after_throttle.get() < ramped);
+    }
+
+    #[tokio::test]
+    async fn test_breaker_pauses_after_burst_of_server_errors() {
+        let cooldown = Duration::from_millis(50);
+        let mut breaker = CircuitBreaker::new(Some(BreakerPolicy {
+            threshold: NonZeroUsize::new(3).unwrap(),
+            cooldown,
+        }));
+        let mut controller = AimdController::new(Concurrency::SERIAL);
+        let started = RefCell::new(Vec::new());
+        let mut failed = 0;
+        let items = stream::iter((0..6).map(Ok));
+        let result = run_bounded(
+            items,
+            &mut controller,
+            &mut breaker,
+            |i: usize| {
+                started.borrow_mut().push(Instant::now());
+                async move {
+                    // The backend answers the first three requests with 503.
+                    if i < 3 {
+                        let err = opendal::Error::new(opendal::ErrorKind::Unexpected, "SlowDown")
+                            .with_context("response", "Parts { status: 503 }");
+                        return (format!("key-{i}"), Err(Error::from(err)));
+                    }
+                    (format!("key-{i}"), Ok(1))
+                }
+            },
+            |_, result| {
+                failed += usize::from(result.is_err());
+                Ok(())
+            },
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(failed, 3);
+        assert_eq!(breaker.trips(), 1);
+        let started = started.into_inner();
+        assert_eq!(started.len(), 6);
+        // Nothing started while the breaker was open, and it closed again afterwards.
+        assert!(started[3] - started[2] >= cooldown);
+        assert!(breaker.paused_until(Instant::now()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_ahead_lists_while_transfers_run() {
+        let events = RefCell::new(Vec::new());
+        let log = |event: String| events.borrow_mut().push(event);
+        let log = &log;
+        // A listing that takes a little time per entry.
+        let listing = stream::iter(0..10).then(move |i| async move {
+            tokio::task::yield_now().await;
+            log(format!("list {i}"));
+            Ok(i)
+        });
+        let (producer, items) = read_ahead(listing, 16);
+        let mut controller = AimdController::new(Concurrency::SERIAL);
+        let (_, result) = future::join(
+            producer,
+            run_bounded(
+                items,
+                &mut controller,
+                &mut CircuitBreaker::default(),
+                move |i: usize| async move {
+                    log(format!("fetch {i}"));
+                    // Transfers are much slower than listing.
+                    for _ in 0..50 {
+                        tokio::task::yield_now().await;
+                    }
+                    log(format!("done {i}"));
+                    (format!("key-{i}"), Ok(0))
+                },
+                |_, _| Ok(()),
+            ),
+        )
+        .await;
+        assert!(result.is_ok());
+
+        let events = events.into_inner();
+        let at = |event: &str| events.iter().position(|e| e == event).unwrap();
+        assert_eq!(events.len(), 30);
+        // The first download starts before the listing is finished, and the listing
+        // finishes while that single transfer slot is still busy.
+        assert!(at("fetch 0") < at("list 9"));
+        assert!(at("list 9") < at("done 0"));
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_health_waits_out_an_outage() {
+        let policy = HealthPolicy {
+            interval: Duration::from_millis(5),
+            max_wait: Duration::from_secs(5),
+        };
+        let unreachable = || {
+            Err::<u64, _>(Error::from(
+                opendal::Error::new(opendal::ErrorKind::Unexpected, "connection refused")
+                    .set_temporary(),
+            ))
+        };
+        // The endpoint is down for the first three pings, then comes back.
+        let pings = Cell::new(0);
+        let ping = || {
+            pings.set(pings.get() + 1);
+            future::ready(pings.get() > 3)
+        };
+        let attempts = Cell::new(0);
+        let transfer = || {
+            attempts.set(attempts.get() + 1);
+            future::ready(if pings.get() > 3 {
+                Ok(7)
+            } else {
+                unreachable()
+            })
+        };
+        let health = EndpointHealth::new(Some(policy));
+        assert_eq!(health.guard(ping, transfer).await.unwrap(), 7);
+        assert_eq!((pings.get(), attempts.get()), (4, 2));
+
+        // A temporary error while the endpoint answers is the transfer's own.
+        attempts.set(0);
+        let failing = || {
+            attempts.set(attempts.get() + 1);
+            future::ready(unreachable())
+        };
+        assert!(health.guard(ping, failing).await.is_err());
+        assert_eq!(attempts.get(), 1);
+
+        // An outage longer than the maximum wait fails the transfer.
+        let short = EndpointHealth::new(Some(HealthPolicy {
+            max_wait: Duration::from_millis(20),
+            ..policy
+        }));
+        let down = || future::ready(false);
+        assert!(short.guard(down, failing).await.is_err());
+        assert_eq!(attempts.get(), 2);
+        assert!(short.gave_up.get());
+
+        // Without a policy nothing is retried or pinged.
+        let pings_before = pings.get();
+        assert!(
+            EndpointHealth::default()
+                .guard(ping, failing)
+                .await
+                .is_err()
+        );
+        assert_eq!((pings.get(), attempts.get()), (pings_before, 3));
+    }
+}
@@ -0,0 +1,58 @@
+// Content-Type detection for uploaded objects
+use std::path::Path;
+
+/// Fallback when neither the content nor the file name identifies the type.
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// How `put` chooses the `Content-Type` stored with each object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ContentTypeDetection {
+    /// Guess from the file extension
+    Extension,
+    /// Sniff the leading bytes, then fall back to the file extension
+    Magic,
+}
+
+/// Content type for a file given its path and leading bytes; never fails, falling back
+/// to `application/octet-stream`.
+pub fn detect_content_type(detection: ContentTypeDetection, path: &Path, head: &[u8]) -> String {
+    let sniffed = match detection {
+        ContentTypeDetection::Magic => infer::get(head).map(|kind| kind.mime_type().to_string()),
+        ContentTypeDetection::Extension => None,
+    };
+    sniffed
+        .or_else(|| {
+            mime_guess::from_path(path)
+                .first()
+                .map(|mime| mime.essence_str().to_string())
+        })
+        .unwrap_or_else(|| OCTET_STREAM.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    #[test]
+    fn test_detect_content_type() {
+        let extensionless = Path::new("images/logo");
+        assert_eq!(
+            detect_content_type(ContentTypeDetection::Magic, extensionless, PNG_HEADER),
+            "image/png"
+        );
+        assert_eq!(
+            detect_content_type(ContentTypeDetection::Extension, extensionless, PNG_HEADER),
+            OCTET_STREAM
+        );
+        assert_eq!(
+            detect_content_type(ContentTypeDetection::Magic, Path::new("notes.txt"), b"hi"),
+            "text/plain"
+        );
+        assert_eq!(
+            detect_content_type(ContentTypeDetection::Magic, extensionless, b"hi"),
+            OCTET_STREAM
+        );
+    }
+}
@@ -0,0 +1,190 @@
+// Redacted JSON-lines trace of backend operations for support bundles
+use crate::error::Result;
+use chrono::Utc;
+use opendal::layers::LoggingInterceptor;
+use opendal::raw::{AccessorInfo, Operation};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Query parameters and headers that carry credentials in signed requests.
+const SENSITIVE_PARAMS: &[&str] = &[
+    "X-Amz-Signature",
+    "X-Amz-Credential",
+    "X-Amz-Security-Token",
+    "OSSAccessKeyId",
+    "Signature",
+    "security-token",
+];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// One line of the debug log.
+#[derive(Debug, Serialize)]
+struct DebugLogRecord<'a> {
+    ts: String,
+    operation: &'static str,
+    path: &'a str,
+    status: &'a str,
+    duration_ms: Option<u128>,
+    message: String,
+    error: Option<String>,
+}
+
+/// OpenDAL logging interceptor that appends redacted JSON lines to a file.
+#[derive(Clone)]
+pub struct DebugLogInterceptor {
+    file: Arc<Mutex<File>>,
+    secrets: Arc<Vec<String>>,
+    started: Arc<Mutex<HashMap<(&'static str, String), Instant>>>,
+}
+
+impl fmt::Debug for DebugLogInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebugLogInterceptor")
+            .finish_non_exhaustive()
+    }
+}
+
+impl DebugLogInterceptor {
+    /// Open (or create) `path` for appending; `secrets` are scrubbed from every record.
+    pub fn open(path: &Path, secrets: Vec<String>) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Arc::new(Mutex::new(file)),
+            secrets: Arc::new(secrets.into_iter().filter(|s| !s.is_empty()).collect()),
+            started: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    fn redact(&self, text: &str) -> String {
+        redact(text, &self.secrets)
+    }
+}
+
+impl LoggingInterceptor for DebugLogInterceptor {
+    fn log(
+        &self,
+        _info: &AccessorInfo,
+        operation: Operation,
+        context: &[(&str, &str)],
+        message: &str,
+        err: Option<&opendal::Error>,
+    ) {
+        let operation = operation.into_static();
+        let path = context
+            .iter()
+            .find(|(key, _)| *key == "path")
+            .map(|(_, value)| *value)
+            .unwrap_or_default();
+        let key = (operation, path.to_string());
+
+        let Ok(mut started) = self.started.lock() else {
+            return;
+        };
+        if message == "started" {
+            started.insert(key, Instant::now());
+            return;
+        }
+        let duration_ms = started.remove(&key).map(|t| t.elapsed().as_millis());
+        drop(started);
+
+        let path = self.redact(path);
+        let record = DebugLogRecord {
+            ts: Utc::now().to_rfc3339(),
+            operation,
+            path: &path,
+            status: if err.is_some() { "error" } else { "ok" },
+            duration_ms,
+            message: self.redact(message),
+            error: err.map(|e| self.redact(&e.to_string())),
+        };
+        if let (Ok(line), Ok(mut file)) = (serde_json::to_string(&record), self.file.lock()) {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+}
+
+/// Replace known secrets and the values of credential-bearing URL parameters with a marker.
+pub fn redact(text: &str, secrets: &[String]) -> String {
+    let mut out = text.to_string();
+    for secret in secrets.iter().filter(|s| !s.is_empty()) {
+        out = out.replace(secret.as_str(), REDACTED);
+    }
+    for param in SENSITIVE_PARAMS {
+        out = redact_param(&out, param);
+    }
+    out
+}
+
+/// Redact the value following every `param=` occurrence, up to the next delimiter.
+fn redact_param(text: &str, param: &str) -> String {
+    let needle = format!("{param}=");
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(&needle) {
+        let value_start = pos + needle.len();
+        out.push_str(&rest[..value_start]);
+        rest = &rest[value_start..];
+        let value_end = rest
+            .find(|c: char| matches!(c, '&' | '"' | '\'' | ',' | ';') || c.is_whitespace())
+            .unwrap_or(rest.len());
+        out.push_str(REDACTED);
+        rest = &rest[value_end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::utils::fixture::TempDir;
+
+    #[test]
+    fn test_redact_signed_url_and_secrets() {
+        let text = "GET https://b.s3.amazonaws.com/k?X-Amz-Credential=AKIAEXAMPLE%2F2024&X-Amz-Signature=abcdef123 failed for key sekret";
+        let redacted = redact(text, &["AKIAEXAMPLE".to_string(), "sekret".to_string()]);
+        assert!(!redacted.contains("AKIAEXAMPLE"));
+        assert!(!redacted.contains("abcdef123"));
+        assert!(!redacted.contains("sekret"));
+        assert!(redacted.contains("X-Amz-Signature=[REDACTED]"));
+        assert!(redacted.starts_with("GET https://b.s3.amazonaws.com/k?"));
+    }
+
+    #[test]
+    fn test_emitted_log_never_contains_secrets() {
+        let dir = TempDir::new();
+        let path = dir.join("debug.log");
+        let interceptor = DebugLogInterceptor::open(
+            &path,
+            vec!["my-access-key".to_string(), "my-secret".to_string()],
+        )
+        .unwrap();
+        let info = AccessorInfo::default();
+        let context = [("path", "data/file.txt")];
+        interceptor.log(&info, Operation::Read, &context, "started", None);
+        let err = opendal::Error::new(
+            opendal::ErrorKind::PermissionDenied,
+            "request https://h/data/file.txt?Signature=zzz&OSSAccessKeyId=my-access-key denied for my-secret",
+        );
+        interceptor.log(&info, Operation::Read, &context, "failed", Some(&err));
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(log.lines().count(), 1);
+        assert!(!log.contains("my-access-key"));
+        assert!(!log.contains("my-secret"));
+        assert!(!log.contains("zzz"));
+
+        let record: serde_json::Value = serde_json::from_str(log.trim()).unwrap();
+        assert_eq!(record["operation"], "read");
+        assert_eq!(record["path"], "data/file.txt");
+        assert_eq!(record["status"], "error");
+        assert!(record["duration_ms"].is_u64());
+    }
+}
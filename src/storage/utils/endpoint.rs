@@ -0,0 +1,217 @@
+// Validation and normalization of custom service endpoints
+use crate::error::{InvalidEndpointSnafu, Result};
+use snafu::ensure;
+
+/// A custom endpoint such as `https://gateway.example.com:8443/storage`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub scheme: String,
+    pub host: String,
+    pub port: Option<u16>,
+    /// Path prefix the service is mounted under, without a trailing slash (may be empty)
+    pub base_path: String,
+}
+
+impl Endpoint {
+    /// Parse and validate an endpoint URL, rejecting anything the backends would
+    /// misinterpret: missing or unknown schemes, empty hosts, bad ports, queries and
+    /// fragments.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            InvalidEndpointSnafu {
+                endpoint: raw.to_string(),
+                reason: reason.to_string(),
+            }
+            .build()
+        };
+
+        let (scheme, rest) = raw
+            .trim()
+            .split_once("://")
+            .ok_or_else(|| invalid("missing scheme, expected http:// or https://"))?;
+        let scheme = scheme.to_ascii_lowercase();
+        ensure!(
+            scheme == "http" || scheme == "https",
+            InvalidEndpointSnafu {
+                endpoint: raw,
+                reason: format!("unsupported scheme '{scheme}', expected http or https"),
+            }
+        );
+        ensure!(
+            !rest.contains(['?', '#']),
+            InvalidEndpointSnafu {
+                endpoint: raw,
+                reason: "query strings and fragments are not allowed",
+            }
+        );
+
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            // Bracketed IPv6 literals contain colons of their own.
+            Some((host, port)) if !port.contains(']') => {
+                let port = port
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|port| *port != 0)
+                    .ok_or_else(|| invalid(&format!("invalid port '{port}'")))?;
+                (host, Some(port))
+            }
+            _ => (authority, None),
+        };
+        ensure!(
+            !host.is_empty() && !host.contains('@'),
+            InvalidEndpointSnafu {
+                endpoint: raw,
+                reason: "missing host",
+            }
+        );
+
+        Ok(Self {
+            scheme,
+            host: host.to_string(),
+            port,
+            base_path: path.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// The endpoint as a URL the service builders accept, e.g. `https://host:8443/storage`.
+    pub fn to_url(&self) -> String {
+        let port = self.port.map(|port| format!(":{port}")).unwrap_or_default();
+        format!("{}://{}{port}{}", self.scheme, self.host, self.base_path)
+    }
+}
+
+/// The region a well-known provider endpoint names in its hostname, e.g. `cn-hangzhou`
+/// for `https://oss-cn-hangzhou.aliyuncs.com` or `eu-west-1` for
+/// `https://s3.eu-west-1.amazonaws.com`. Unknown hosts and malformed endpoints give `None`.
+pub fn region_from_endpoint(raw: &str) -> Option<String> {
+    let host = Endpoint::parse(raw).ok()?.host.to_ascii_lowercase();
+    if let Some(rest) = host
+        .strip_suffix(".amazonaws.com")
+        .or_else(|| host.strip_suffix(".amazonaws.com.cn"))
+    {
+        return aws_region(rest);
+    }
+    if let Some(rest) = host.strip_suffix(".aliyuncs.com") {
+        // `[<bucket>.]oss-<region>[-internal]`; the accelerate endpoints are global.
+        let label = rest.rsplit('.').next()?.strip_prefix("oss-")?;
+        let region = label.strip_suffix("-internal").unwrap_or(label);
+        return (!region.starts_with("accelerate")).then(|| region.to_string());
+    }
+    host.ends_with(".r2.cloudflarestorage.com")
+        .then(|| "auto".to_string())
+}
+
+/// Region from the labels of an AWS S3 host before `.amazonaws.com`: `s3`, `s3.<region>`,
+/// `s3-<region>`, `s3.dualstack.<region>` or any of them behind a bucket name.
+fn aws_region(labels: &str) -> Option<String> {
+    let mut labels = labels
+        .split('.')
+        .skip_while(|label| *label != "s3" && !label.starts_with("s3-"));
+    let service = labels.next()?;
+    // `s3-external-1` is the legacy global endpoint, not a region.
+    if let Some(region) = service
+        .strip_prefix("s3-")
+        .filter(|r| *r != "external-1" && looks_like_region(r))
+    {
+        return Some(region.to_string());
+    }
+    match labels.find(|label| *label != "dualstack") {
+        Some(region) if looks_like_region(region) => Some(region.to_string()),
+        Some(_) => None,
+        // The legacy global endpoints are served from us-east-1.
+        None => matches!(service, "s3" | "s3-external-1").then(|| "us-east-1".to_string()),
+    }
+}
+
+/// Whether a hostname label has the shape of a region code such as `ap-southeast-2`.
+fn looks_like_region(label: &str) -> bool {
+    label.contains('-')
+        && label.ends_with(|c: char| c.is_ascii_digit())
+        && label
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_from_endpoint() {
+        for (endpoint, region) in [
+            ("https://oss-cn-hangzhou.aliyuncs.com", Some("cn-hangzhou")),
+            (
+                "https://oss-cn-beijing-internal.aliyuncs.com",
+                Some("cn-beijing"),
+            ),
+            (
+                "https://my-bucket.oss-ap-southeast-1.aliyuncs.com",
+                Some("ap-southeast-1"),
+            ),
+            ("https://oss-accelerate.aliyuncs.com", None),
+            ("https://s3.eu-west-1.amazonaws.com", Some("eu-west-1")),
+            ("https://s3-us-west-2.amazonaws.com", Some("us-west-2")),
+            (
+                "https://bucket.s3.ap-northeast-1.amazonaws.com",
+                Some("ap-northeast-1"),
+            ),
+            (
+                "https://s3.dualstack.eu-central-1.amazonaws.com",
+                Some("eu-central-1"),
+            ),
+            ("https://s3.cn-north-1.amazonaws.com.cn", Some("cn-north-1")),
+            ("https://s3.amazonaws.com", Some("us-east-1")),
+            ("https://s3-external-1.amazonaws.com", Some("us-east-1")),
+            ("https://acct.r2.cloudflarestorage.com", Some("auto")),
+            ("http://127.0.0.1:9000", None),
+            ("https://minio.example.com", None),
+            ("not a url", None),
+        ] {
+            assert_eq!(
+                region_from_endpoint(endpoint).as_deref(),
+                region,
+                "{endpoint}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_endpoint_with_port_and_base_path() {
+        let endpoint = Endpoint::parse("https://gateway.example.com:8443/storage/").unwrap();
+        assert_eq!(endpoint.scheme, "https");
+        assert_eq!(endpoint.host, "gateway.example.com");
+        assert_eq!(endpoint.port, Some(8443));
+        assert_eq!(endpoint.base_path, "/storage");
+        assert_eq!(
+            endpoint.to_url(),
+            "https://gateway.example.com:8443/storage"
+        );
+
+        let plain = Endpoint::parse("http://127.0.0.1:9000").unwrap();
+        assert_eq!(plain.base_path, "");
+        assert_eq!(plain.to_url(), "http://127.0.0.1:9000");
+
+        let ipv6 = Endpoint::parse("http://[::1]:9000/s3").unwrap();
+        assert_eq!(ipv6.host, "[::1]");
+        assert_eq!(ipv6.port, Some(9000));
+        assert_eq!(Endpoint::parse("http://[::1]").unwrap().port, None);
+    }
+
+    #[test]
+    fn test_parse_endpoint_rejects_malformed() {
+        for raw in [
+            "gateway.example.com:8443",
+            "ftp://gateway.example.com",
+            "https://:8443/storage",
+            "https://gateway.example.com:port",
+            "https://gateway.example.com:70000",
+            "https://gateway.example.com/storage?x=1",
+        ] {
+            assert!(Endpoint::parse(raw).is_err(), "{raw} should be rejected");
+        }
+    }
+}
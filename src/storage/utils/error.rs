@@ -24,6 +24,103 @@ impl IntoStorifyError for std::io::Error {
     }
 }
 
+/// Whether a backend error was caused by reading an archived (cold-storage) object.
+/// S3 and OSS both report this as `InvalidObjectState`.
+pub fn is_archived_error(err: &opendal::Error) -> bool {
+    err.to_string().contains("InvalidObjectState")
+}
+
+/// Convert a read failure into our Error, surfacing archived objects with a clear message.
+pub fn map_read_error(err: opendal::Error, path: &str) -> Error {
+    if is_archived_error(&err) {
+        Error::ObjectArchived {
+            path: path.to_string(),
+        }
+    } else {
+        err.into()
+    }
+}
+
+/// [`map_read_error`] for failures that come through an `AsyncRead` or byte stream, which
+/// wrap the backend error in an `io::Error`.
+pub fn map_io_read_error(err: std::io::Error, path: &str) -> Error {
+    match err
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<opendal::Error>())
+    {
+        Some(inner) if is_archived_error(inner) => Error::ObjectArchived {
+            path: path.to_string(),
+        },
+        _ => err.into(),
+    }
+}
+
+/// Backend error codes that usually mean the request was signed for the wrong region
+/// (or with the wrong credentials) rather than a genuine permission problem.
+const REGION_AUTH_ERROR_CODES: &[&str] = &[
+    "SignatureDoesNotMatch",
+    "AuthorizationHeaderMalformed",
+    "InvalidRegion",
+    "PermanentRedirect",
+    "IllegalLocationConstraintException",
+];
+
+/// Whether a backend error looks like a signature/region mismatch.
+pub fn is_region_auth_error(err: &opendal::Error) -> bool {
+    let message = err.to_string();
+    REGION_AUTH_ERROR_CODES
+        .iter()
+        .any(|code| message.contains(code))
+}
+
+/// Rewrite errors caused by a signature/region mismatch anywhere in the source chain into
+/// `AuthenticationFailed`, naming the region in use. Other errors pass through unchanged.
+pub fn map_region_auth_error(err: Error, region: Option<&str>) -> Error {
+    let root: &(dyn std::error::Error + 'static) = &err;
+    let matched = std::iter::successors(Some(root), |e| e.source()).any(|e| {
+        e.downcast_ref::<opendal::Error>()
+            .is_some_and(is_region_auth_error)
+    });
+
+    if matched {
+        Error::AuthenticationFailed {
+            region: region.unwrap_or("none").to_string(),
+            source: Box::new(err),
+        }
+    } else {
+        err
+    }
+}
+
+/// Whether a backend error carries a 5xx HTTP status. OpenDAL records the response as
+/// `status: NNN` in the error context.
+pub fn is_server_status_error(err: &opendal::Error) -> bool {
+    let message = err.to_string();
+    message.match_indices("status: ").any(|(at, prefix)| {
+        let code = &message.as_bytes()[at + prefix.len()..];
+        code.len() >= 3 && code[0] == b'5' && code[1..3].iter().all(u8::is_ascii_digit)
+    })
+}
+
+/// Whether a 5xx backend error appears anywhere in the source chain.
+pub fn is_server_error(err: &Error) -> bool {
+    let root: &(dyn std::error::Error + 'static) = err;
+    std::iter::successors(Some(root), |e| e.source()).any(|e| {
+        e.downcast_ref::<opendal::Error>()
+            .is_some_and(is_server_status_error)
+    })
+}
+
+/// Whether a temporary backend failure (a refused or dropped connection, a timeout, ...)
+/// appears anywhere in the source chain.
+pub fn is_transient_error(err: &Error) -> bool {
+    let root: &(dyn std::error::Error + 'static) = err;
+    std::iter::successors(Some(root), |e| e.source()).any(|e| {
+        e.downcast_ref::<opendal::Error>()
+            .is_some_and(opendal::Error::is_temporary)
+    })
+}
+
 /// Macro to wrap a Result-producing expression into a Snafu variant with `source: Box<Error>`.
 /// Example:
 /// wrap_err!(op.await, DownloadFailed { remote_path: rp, local_path: lp })?
@@ -36,3 +133,84 @@ macro_rules! wrap_err {
         })
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opendal::ErrorKind;
+
+    #[test]
+    fn test_map_read_error_detects_archived_object() {
+        let err = opendal::Error::new(
+            ErrorKind::PermissionDenied,
+            "S3Error { code: InvalidObjectState, message: The operation is not valid for the object's storage class }",
+        );
+        let mapped = map_read_error(err, "cold/data.bin");
+        assert!(matches!(mapped, Error::ObjectArchived { ref path } if path == "cold/data.bin"));
+        assert_eq!(
+            mapped.to_string(),
+            "Object is archived; restore it first: cold/data.bin"
+        );
+    }
+
+    #[test]
+    fn test_map_region_auth_error_rewrites_signature_mismatch() {
+        let err = Error::ListDirectoryFailed {
+            path: "data/".to_string(),
+            source: Box::new(
+                opendal::Error::new(
+                    ErrorKind::PermissionDenied,
+                    "S3Error { code: SignatureDoesNotMatch, message: The request signature we calculated does not match the signature you provided }",
+                )
+                .into(),
+            ),
+        };
+        let mapped = map_region_auth_error(err, Some("us-east-1"));
+        assert!(
+            matches!(mapped, Error::AuthenticationFailed { ref region, .. } if region == "us-east-1")
+        );
+        assert!(
+            mapped.to_string().starts_with(
+                "Authentication failed — check region (got us-east-1) and credentials"
+            )
+        );
+    }
+
+    #[test]
+    fn test_map_region_auth_error_keeps_access_denied() {
+        let err: Error = opendal::Error::new(
+            ErrorKind::PermissionDenied,
+            "S3Error { code: AccessDenied }",
+        )
+        .into();
+        assert!(matches!(
+            map_region_auth_error(err, None),
+            Error::OpenDal { .. }
+        ));
+    }
+
+    #[test]
+    fn test_is_server_error_reads_response_status() {
+        let server = opendal::Error::new(ErrorKind::Unexpected, "S3Error { code: InternalError }")
+            .with_context("response", "Parts { status: 503, version: HTTP/1.1 }");
+        let wrapped = Error::DownloadFailed {
+            remote_path: "data/a.bin".to_string(),
+            local_path: "a.bin".to_string(),
+            source: Box::new(server.into()),
+        };
+        assert!(is_server_error(&wrapped));
+
+        let client = opendal::Error::new(ErrorKind::NotFound, "NoSuchKey")
+            .with_context("response", "Parts { status: 404, version: HTTP/1.1 }");
+        assert!(!is_server_error(&client.into()));
+    }
+
+    #[test]
+    fn test_map_read_error_passes_through_other_errors() {
+        let err = opendal::Error::new(ErrorKind::NotFound, "NoSuchKey");
+        assert!(matches!(
+            map_read_error(err, "missing"),
+            Error::OpenDal { .. }
+        ));
+    }
+}
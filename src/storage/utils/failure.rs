@@ -0,0 +1,256 @@
+// Per-object failure handling for recursive transfers (`--on-error`, `--fail-fast`)
+use crate::error::{PartialFailureSnafu, Result};
+use snafu::ensure;
+use std::fmt;
+use std::future::Future;
+use std::num::NonZeroU32;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Wait before the first retry of a failed object; doubles with each further attempt.
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
+/// Longest wait between two attempts.
+const RETRY_BACKOFF_MAX: Duration = Duration::from_secs(5);
+
+/// What a recursive transfer does when one of its objects fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// Stop the whole batch at the first failure
+    #[default]
+    Abort,
+    /// Report the failure and carry on
+    Skip,
+    /// Try the object again up to N more times with backoff, then skip it
+    Retry(NonZeroU32),
+}
+
+impl ErrorPolicy {
+    /// Run `attempt` for `path`, repeating it after failures as the policy allows.
+    pub async fn run<T, F, Fut>(self, path: &str, attempt: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.run_with_backoff(path, RETRY_BACKOFF, attempt).await
+    }
+
+    async fn run_with_backoff<T, F, Fut>(
+        self,
+        path: &str,
+        mut backoff: Duration,
+        mut attempt: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let retries = match self {
+            ErrorPolicy::Retry(retries) => retries.get(),
+            ErrorPolicy::Abort | ErrorPolicy::Skip => 0,
+        };
+        let mut retried = 0;
+        loop {
+            match attempt().await {
+                Err(err) if retried < retries => {
+                    retried += 1;
+                    eprintln!("Retrying ({retried}/{retries}): {path}: {err}");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(RETRY_BACKOFF_MAX);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl FromStr for ErrorPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "abort" => Ok(ErrorPolicy::Abort),
+            "skip" => Ok(ErrorPolicy::Skip),
+            policy => policy
+                .strip_prefix("retry:")
+                .and_then(|n| n.parse::<NonZeroU32>().ok())
+                .map(ErrorPolicy::Retry)
+                .ok_or_else(|| format!("expected abort, skip or retry:N (N > 0), got '{s}'")),
+        }
+    }
+}
+
+impl fmt::Display for ErrorPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorPolicy::Abort => f.write_str("abort"),
+            ErrorPolicy::Skip => f.write_str("skip"),
+            ErrorPolicy::Retry(n) => write!(f, "retry:{n}"),
+        }
+    }
+}
+
+/// Outcome tally of a recursive transfer.
+///
+/// Under `ErrorPolicy::Abort` (the default) the first failed object aborts the batch.
+/// Otherwise each failure is reported and the batch carries on; `finish` then fails if
+/// anything went wrong, so the command still exits nonzero.
+#[derive(Debug, Default)]
+pub struct FailureTracker {
+    policy: ErrorPolicy,
+    attempted: usize,
+    failed: usize,
+}
+
+impl FailureTracker {
+    pub fn new(policy: ErrorPolicy) -> Self {
+        Self {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    /// Record the result of transferring `path`; returns the error when it should abort
+    /// the batch.
+    pub fn record(&mut self, path: &str, result: Result<()>) -> Result<()> {
+        self.attempted += 1;
+        match result {
+            Ok(()) => Ok(()),
+            Err(err) if self.policy != ErrorPolicy::Abort => {
+                self.failed += 1;
+                eprintln!("Failed: {path}: {err}");
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fail with a summary if any recorded object failed.
+    pub fn finish(self) -> Result<()> {
+        ensure!(
+            self.failed == 0,
+            PartialFailureSnafu {
+                failed: self.failed,
+                total: self.attempted,
+            }
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use std::cell::Cell;
+
+    fn failure() -> Result<()> {
+        Err(Error::InvalidPath {
+            path: "b".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_fail_fast_stops_at_first_failure() {
+        let mut tracker = FailureTracker::new(ErrorPolicy::Abort);
+        assert!(tracker.record("a", Ok(())).is_ok());
+        assert!(matches!(
+            tracker.record("b", failure()),
+            Err(Error::InvalidPath { .. })
+        ));
+    }
+
+    #[test]
+    fn test_continue_on_error_reports_at_finish() {
+        let mut tracker = FailureTracker::new(ErrorPolicy::Skip);
+        assert!(tracker.record("a", Ok(())).is_ok());
+        assert!(tracker.record("b", failure()).is_ok());
+        assert!(tracker.record("c", Ok(())).is_ok());
+        let err = tracker.finish().unwrap_err();
+        assert!(matches!(
+            err,
+            Error::PartialFailure {
+                failed: 1,
+                total: 3
+            }
+        ));
+
+        assert!(FailureTracker::new(ErrorPolicy::Skip).finish().is_ok());
+    }
+
+    /// Push `keys` through `policy` like a recursive transfer; "flaky" fails its first two
+    /// attempts and "broken" always fails. Returns the batch result and attempts per key.
+    async fn run_batch(policy: ErrorPolicy) -> (Result<()>, Vec<u32>) {
+        let keys = ["flaky", "broken", "ok"];
+        let attempts: Vec<Cell<u32>> = keys.iter().map(|_| Cell::new(0)).collect();
+        let mut tracker = FailureTracker::new(policy);
+        let batch = async {
+            for (key, count) in keys.iter().zip(&attempts) {
+                let result = policy
+                    .run_with_backoff(key, Duration::ZERO, || async move {
+                        count.set(count.get() + 1);
+                        match *key {
+                            "flaky" if count.get() <= 2 => failure(),
+                            "broken" => failure(),
+                            _ => Ok(()),
+                        }
+                    })
+                    .await;
+                tracker.record(key, result)?;
+            }
+            Ok(())
+        }
+        .await;
+        let result = batch.and_then(|()| tracker.finish());
+        (result, attempts.iter().map(Cell::get).collect())
+    }
+
+    #[test]
+    fn test_parse_error_policy() {
+        assert_eq!("abort".parse(), Ok(ErrorPolicy::Abort));
+        assert_eq!("Skip".parse(), Ok(ErrorPolicy::Skip));
+        assert_eq!(
+            "retry:3".parse(),
+            Ok(ErrorPolicy::Retry(NonZeroU32::new(3).unwrap()))
+        );
+        for invalid in ["retry", "retry:0", "retry:x", "ignore"] {
+            assert!(invalid.parse::<ErrorPolicy>().is_err(), "{invalid}");
+        }
+        assert_eq!(
+            ErrorPolicy::Retry(NonZeroU32::new(2).unwrap()).to_string(),
+            "retry:2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_abort_policy_stops_at_first_failure() {
+        let (result, attempts) = run_batch(ErrorPolicy::Abort).await;
+        assert!(matches!(result, Err(Error::InvalidPath { .. })));
+        assert_eq!(attempts, [1, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_skip_policy_continues_without_retrying() {
+        let (result, attempts) = run_batch(ErrorPolicy::Skip).await;
+        assert!(matches!(
+            result,
+            Err(Error::PartialFailure {
+                failed: 2,
+                total: 3
+            })
+        ));
+        assert_eq!(attempts, [1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_retries_then_skips() {
+        let (result, attempts) = run_batch("retry:3".parse().unwrap()).await;
+        assert!(matches!(
+            result,
+            Err(Error::PartialFailure {
+                failed: 1,
+                total: 3
+            })
+        ));
+        assert_eq!(attempts, [3, 4, 1]);
+    }
+}
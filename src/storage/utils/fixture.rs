@@ -0,0 +1,44 @@
+// Temporary local directories and Fs operators for unit tests
+use opendal::Operator;
+use std::path::{Path, PathBuf};
+
+/// Fresh directory under the system temp dir, removed with its contents on drop, so a
+/// failing assertion does not leak it.
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("storify-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.path.join(name)
+    }
+
+    /// The directory as a `StorageConfig::fs` root.
+    pub fn root(&self) -> String {
+        self.path.to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Plain Fs operator rooted at `root`. Its listings carry no sizes, unlike most object
+/// stores, which keeps code that sizes listed files honest.
+pub fn fs_operator(root: &Path) -> Operator {
+    let builder = opendal::services::Fs::default().root(&root.to_string_lossy());
+    Operator::new(builder).unwrap().finish()
+}
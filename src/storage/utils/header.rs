@@ -0,0 +1,73 @@
+// Validation for HTTP header values set on uploaded objects
+
+/// Reject values that cannot be sent as a single header line.
+fn validate_header_value(value: &str) -> Result<&str, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("value must not be empty".to_string());
+    }
+    if value.chars().any(|c| c.is_control()) {
+        return Err("value must not contain control characters".to_string());
+    }
+    Ok(value)
+}
+
+/// Validate a `Content-Disposition` value such as `attachment; filename="report.pdf"`.
+pub fn validate_content_disposition(value: &str) -> Result<String, String> {
+    let value = validate_header_value(value)?;
+    let disposition = value.split(';').next().unwrap_or_default().trim();
+    if !disposition.eq_ignore_ascii_case("inline")
+        && !disposition.eq_ignore_ascii_case("attachment")
+    {
+        return Err(format!(
+            "disposition type must be 'inline' or 'attachment', got '{disposition}'"
+        ));
+    }
+    Ok(value.to_string())
+}
+
+/// Loosely validate a `Cache-Control` value: comma-separated directives, each a token
+/// optionally followed by `=value` (e.g. `public, max-age=3600`).
+pub fn validate_cache_control(value: &str) -> Result<String, String> {
+    let value = validate_header_value(value)?;
+    for directive in value.split(',').map(str::trim) {
+        let name = directive.split('=').next().unwrap_or_default().trim();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("invalid cache-control directive '{directive}'"));
+        }
+    }
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_content_disposition() {
+        assert_eq!(
+            validate_content_disposition(" attachment; filename=\"report.pdf\" ").as_deref(),
+            Ok("attachment; filename=\"report.pdf\"")
+        );
+        assert!(validate_content_disposition("inline").is_ok());
+        assert!(validate_content_disposition("").is_err());
+        assert!(validate_content_disposition("download; filename=a").is_err());
+        assert!(validate_content_disposition("attachment\r\nX-Injected: 1").is_err());
+    }
+
+    #[test]
+    fn test_validate_cache_control() {
+        assert_eq!(
+            validate_cache_control("max-age=3600").as_deref(),
+            Ok("max-age=3600")
+        );
+        assert!(validate_cache_control("public, max-age=60, no-transform").is_ok());
+        assert!(validate_cache_control("").is_err());
+        assert!(validate_cache_control("max-age=60,,public").is_err());
+        assert!(validate_cache_control("max age=60").is_err());
+    }
+}
@@ -0,0 +1,78 @@
+// HTTP client settings (proxy, redirects) for the backends that talk HTTP
+use crate::error::{HttpClientSnafu, InvalidProxySnafu, Result};
+use opendal::raw::HttpClient;
+use reqwest::redirect::Policy;
+use reqwest::{NoProxy, Proxy};
+use snafu::ResultExt;
+
+/// Redirects followed when `follow_redirects` is set.
+const MAX_REDIRECTS: usize = 10;
+
+/// Proxy and redirect settings for every backend request.
+///
+/// Without an explicit proxy the standard `HTTPS_PROXY`, `HTTP_PROXY`, `ALL_PROXY` and
+/// `NO_PROXY` variables (upper or lower case) apply; an explicit proxy replaces them but
+/// still honors `NO_PROXY`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpOptions {
+    /// Send every request through this proxy, e.g. `http://proxy.corp:3128`
+    pub proxy: Option<String>,
+    /// Follow HTTP redirects instead of reporting them as errors
+    pub follow_redirects: bool,
+}
+
+impl HttpOptions {
+    /// Whether these are the settings OpenDAL's own client already uses.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Client builder carrying these settings.
+    pub fn client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        let mut builder = reqwest::Client::builder().redirect(if self.follow_redirects {
+            Policy::limited(MAX_REDIRECTS)
+        } else {
+            Policy::none()
+        });
+        if let Some(proxy) = &self.proxy {
+            let proxy = Proxy::all(proxy)
+                .context(InvalidProxySnafu { proxy })?
+                .no_proxy(NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+        Ok(builder)
+    }
+
+    /// Client with these settings, or `None` when OpenDAL's own client will do.
+    pub fn http_client(&self) -> Result<Option<HttpClient>> {
+        if self.is_default() {
+            return Ok(None);
+        }
+        let client = self.client_builder()?.build().context(HttpClientSnafu)?;
+        Ok(Some(HttpClient::with(client)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_reaches_client() {
+        let options = HttpOptions {
+            proxy: Some("http://proxy.example.com:3128".to_string()),
+            follow_redirects: true,
+        };
+        assert!(!options.is_default());
+        let client = options.client_builder().unwrap().build().unwrap();
+        assert!(format!("{client:?}").contains("proxy.example.com"));
+
+        let invalid = HttpOptions {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.client_builder().is_err());
+        assert!(HttpOptions::default().is_default());
+        assert!(HttpOptions::default().http_client().unwrap().is_none());
+    }
+}
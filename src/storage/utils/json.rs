@@ -0,0 +1,84 @@
+// JSON rendering shared by machine-readable outputs
+use crate::error::Result;
+use serde::Serialize;
+
+/// Layout of JSON output.
+///
+/// Either way fields come out in a fixed order (struct declaration order, or sorted keys
+/// for maps), so repeated runs produce identical, diffable output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonStyle {
+    /// Single line, for piping
+    #[default]
+    Compact,
+    /// Indented, for reading
+    Pretty,
+}
+
+impl JsonStyle {
+    pub fn from_pretty(pretty: bool) -> Self {
+        if pretty {
+            JsonStyle::Pretty
+        } else {
+            JsonStyle::Compact
+        }
+    }
+
+    /// Serialize `value` in this style.
+    pub fn render<T: Serialize + ?Sized>(self, value: &T) -> Result<String> {
+        let json = match self {
+            JsonStyle::Compact => serde_json::to_string(value)?,
+            JsonStyle::Pretty => serde_json::to_string_pretty(value)?,
+        };
+        Ok(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Entry {
+        path: &'static str,
+        size: u64,
+        is_dir: bool,
+    }
+
+    #[test]
+    fn test_compact_and_pretty_agree_on_content_and_order() {
+        let listing = [
+            Entry {
+                path: "dir/b.txt",
+                size: 2,
+                is_dir: false,
+            },
+            Entry {
+                path: "dir/a/",
+                size: 0,
+                is_dir: true,
+            },
+        ];
+        let compact = JsonStyle::Compact.render(&listing).unwrap();
+        let pretty = JsonStyle::Pretty.render(&listing).unwrap();
+
+        assert_eq!(
+            compact,
+            r#"[{"path":"dir/b.txt","size":2,"is_dir":false},{"path":"dir/a/","size":0,"is_dir":true}]"#
+        );
+        assert!(pretty.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap()
+        );
+
+        // Fields keep declaration order in both layouts.
+        for json in [&compact, &pretty] {
+            let path = json.find("\"path\"").unwrap();
+            let size = json.find("\"size\"").unwrap();
+            let is_dir = json.find("\"is_dir\"").unwrap();
+            assert!(path < size && size < is_dir, "unexpected order in {json}");
+        }
+        assert_eq!(JsonStyle::Compact.render(&listing).unwrap(), compact);
+    }
+}
@@ -0,0 +1,42 @@
+// Record of the objects an upload wrote, for auditing and rollback
+use crate::error::Result;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// `<etag>  <key>  <size>` lines, appended as each object lands so the file stays accurate
+/// even when a later upload fails. Clones share the file.
+#[derive(Clone)]
+pub struct Manifest {
+    path: PathBuf,
+    file: Arc<Mutex<File>>,
+}
+
+impl fmt::Debug for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Manifest")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Manifest {
+    /// Create (or truncate) the manifest at `path`.
+    pub fn create(path: &Path) -> Result<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Arc::new(Mutex::new(File::create(path)?)),
+        })
+    }
+
+    /// Record an uploaded object; objects without an ETag are written with `-`.
+    pub fn record(&self, etag: Option<&str>, key: &str, size: u64) -> Result<()> {
+        let etag = etag.map_or("-", |etag| etag.trim_matches('"'));
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        writeln!(file, "{etag}  {key}  {size}")?;
+        file.flush()?;
+        Ok(())
+    }
+}
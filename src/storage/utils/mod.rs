@@ -1,16 +1,35 @@
 // Utilities for storage module
+pub mod checksum;
+pub mod concurrency;
+pub mod content_type;
+pub mod debug_log;
+pub mod endpoint;
 pub mod error;
+pub mod failure;
+#[cfg(test)]
+pub mod fixture;
+pub mod header;
+pub mod http;
+pub mod json;
+pub mod manifest;
 pub mod path;
 pub mod progress;
 pub mod size;
+pub mod sort;
+pub mod stats;
+pub mod template;
+pub mod time;
+pub mod transfer_log;
 
 /// Output format for CLI commands that can render machine-readable results
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OutputFormat {
     /// Human friendly multi-line output
     Human,
     /// Key-value lines compatible with opendal-mkdir's raw output
     Raw,
-    /// Single-line JSON output
-    Json,
+    /// JSON output, single-line or indented
+    Json(json::JsonStyle),
+    /// User-supplied template, e.g. `{size}`
+    Template(template::Template),
 }
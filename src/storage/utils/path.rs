@@ -1,5 +1,25 @@
 // Path helper utilities shared across storage operations
+use std::borrow::Cow;
 use std::path::Path;
+use unicode_normalization::{UnicodeNormalization, is_nfc, is_nfd};
+
+/// Unicode normalization form applied to local file names before they become keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum UnicodeForm {
+    /// Composed form, as typically stored by Linux and Windows tools
+    Nfc,
+    /// Decomposed form, as produced by macOS file systems
+    Nfd,
+}
+
+/// Normalize a file name to the requested form; `None` leaves it untouched.
+pub fn normalize_unicode(name: &str, form: Option<UnicodeForm>) -> Cow<'_, str> {
+    match form {
+        Some(UnicodeForm::Nfc) if !is_nfc(name) => Cow::Owned(name.nfc().collect()),
+        Some(UnicodeForm::Nfd) if !is_nfd(name) => Cow::Owned(name.nfd().collect()),
+        _ => Cow::Borrowed(name),
+    }
+}
 
 /// Build a remote path by joining base and file name.
 pub fn build_remote_path(base: &str, file_name: &str) -> String {
@@ -49,3 +69,55 @@ pub fn get_root_relative_path(full_path: &str, base_path: &str) -> String {
                 .unwrap_or_default()
         })
 }
+
+/// Escape control characters (newline, tab, ...) so a key always renders on one line.
+/// Keys without control characters are returned unchanged.
+pub fn escape_control_chars(path: &str) -> Cow<'_, str> {
+    if !path.chars().any(char::is_control) {
+        return Cow::Borrowed(path);
+    }
+
+    let mut escaped = String::with_capacity(path.len() + 8);
+    for c in path.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{{{:04x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_control_chars() {
+        assert_eq!(escape_control_chars("plain/key.txt"), "plain/key.txt");
+        assert_eq!(escape_control_chars("a\nb.txt"), "a\\nb.txt");
+        assert_eq!(escape_control_chars("tab\there"), "tab\\there");
+        assert_eq!(escape_control_chars("bell\u{7}"), "bell\\u{0007}");
+    }
+
+    #[test]
+    fn test_normalize_unicode() {
+        let decomposed = "cafe\u{301}.txt";
+        let composed = "caf\u{e9}.txt";
+        assert_eq!(
+            normalize_unicode(decomposed, Some(UnicodeForm::Nfc)),
+            composed
+        );
+        assert_eq!(
+            normalize_unicode(composed, Some(UnicodeForm::Nfd)),
+            decomposed
+        );
+        assert_eq!(normalize_unicode(decomposed, None), decomposed);
+        assert!(matches!(
+            normalize_unicode(composed, Some(UnicodeForm::Nfc)),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -1,32 +1,219 @@
-use std::io::{self, Write};
+use crate::storage::utils::size::format_size;
+use serde::Serialize;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
-/// A minimal progress reporter that prints percentage updates to stdout.
+/// Output format for transfer progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ProgressFormat {
+    /// Percentage line rewritten in place on stdout
+    #[default]
+    Text,
+    /// Newline-delimited JSON events on stderr
+    Json,
+}
+
+/// Progress settings shared by all transfer operations of a client.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressOptions {
+    pub format: ProgressFormat,
+    /// Suppress all progress output
+    pub disabled: bool,
+    /// Write the latest progress line to this file (overwritten on each update)
+    /// instead of the terminal
+    pub file: Option<PathBuf>,
+}
+
+/// A single machine-readable progress event.
+#[derive(Debug, Serialize)]
+struct ProgressEvent<'a> {
+    file: &'a str,
+    bytes_done: u64,
+    bytes_total: Option<u64>,
+    percent: Option<u32>,
+}
+
+/// A minimal progress reporter that prints percentage updates to stdout,
+/// or JSON events to stderr when configured for machine consumption.
 pub struct ConsoleProgressReporter {
     label: String,
+    file: String,
     total_bytes: Option<u64>,
     step_bytes: u64,
+    format: ProgressFormat,
+    disabled: bool,
+    output_file: Option<PathBuf>,
+    started: Instant,
+    /// Whether stdout is a terminal; `\r`-rewritten text progress is only drawn there
+    interactive: bool,
+    /// A text progress line has been drawn and not yet terminated
+    line_open: AtomicBool,
 }
 
 impl ConsoleProgressReporter {
     pub fn new(label: impl Into<String>, total_bytes: Option<u64>, step_bytes: u64) -> Self {
         Self {
             label: label.into(),
+            file: String::new(),
             total_bytes,
             step_bytes: step_bytes.max(1),
+            format: ProgressFormat::Text,
+            disabled: false,
+            output_file: None,
+            started: Instant::now(),
+            interactive: io::stdout().is_terminal(),
+            line_open: AtomicBool::new(false),
         }
     }
 
+    /// Apply client-wide progress options; `file` identifies the transfer in JSON events.
+    pub fn with_options(mut self, options: &ProgressOptions, file: impl Into<String>) -> Self {
+        self.format = options.format;
+        self.disabled = options.disabled;
+        self.output_file = options.file.clone();
+        self.file = file.into();
+        self
+    }
+
     /// Print progress if a reporting threshold has been reached.
     pub fn maybe_report(&self, processed_bytes: u64) {
-        if let Some(total) = self.total_bytes {
-            if total == 0 {
-                return;
+        if processed_bytes.is_multiple_of(self.step_bytes) {
+            self.report(processed_bytes);
+        }
+    }
+
+    /// Report the final state of a transfer. JSON and progress files get a terminal
+    /// update; an open text progress line is ended so the caller's completion line starts
+    /// on a fresh line.
+    pub fn finish(&self, processed_bytes: u64) {
+        if self.format == ProgressFormat::Json || self.output_file.is_some() {
+            self.report(processed_bytes);
+        }
+        if self.line_open.swap(false, Ordering::Relaxed) {
+            println!();
+        }
+    }
+
+    fn percent(&self, processed_bytes: u64) -> Option<u32> {
+        match self.total_bytes {
+            Some(0) => Some(100),
+            Some(total) => Some(((processed_bytes as f64 / total as f64) * 100.0) as u32),
+            None => None,
+        }
+    }
+
+    /// One-line summary written to a progress file: percentage, bytes and average rate.
+    fn file_line(&self, processed_bytes: u64) -> String {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 {
+            (processed_bytes as f64 / elapsed) as u64
+        } else {
+            processed_bytes
+        };
+        let percent = self
+            .percent(processed_bytes)
+            .map(|p| format!("{p}%"))
+            .unwrap_or_else(|| "-".to_string());
+        let total = self
+            .total_bytes
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| "?".to_string());
+        format!(
+            "{} {percent} {processed_bytes}/{total} bytes {}/s\n",
+            self.file,
+            format_size(rate)
+        )
+    }
+
+    fn report(&self, processed_bytes: u64) {
+        if self.disabled {
+            return;
+        }
+        if let Some(path) = &self.output_file {
+            let _ = std::fs::write(path, self.file_line(processed_bytes));
+            return;
+        }
+        match self.format {
+            ProgressFormat::Text => {
+                if !self.interactive {
+                    return;
+                }
+                if let Some(total) = self.total_bytes {
+                    if total == 0 {
+                        return;
+                    }
+                    let progress = ((processed_bytes as f64 / total as f64) * 100.0) as u32;
+                    print!("\r {}: {}%", self.label, progress);
+                    let _ = io::stdout().flush();
+                    self.line_open.store(true, Ordering::Relaxed);
+                }
             }
-            if processed_bytes.is_multiple_of(self.step_bytes) {
-                let progress = ((processed_bytes as f64 / total as f64) * 100.0) as u32;
-                print!("\r {}: {}%", self.label, progress);
-                let _ = io::stdout().flush();
+            ProgressFormat::Json => {
+                let event = ProgressEvent {
+                    file: &self.file,
+                    bytes_done: processed_bytes,
+                    bytes_total: self.total_bytes,
+                    percent: self.percent(processed_bytes),
+                };
+                if let Ok(line) = serde_json::to_string(&event) {
+                    eprintln!("{line}");
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::utils::fixture::TempDir;
+
+    #[test]
+    fn test_progress_file_gets_final_line() {
+        let dir = TempDir::new();
+        let path = dir.join("progress");
+        let options = ProgressOptions {
+            file: Some(path.clone()),
+            ..Default::default()
+        };
+        let reporter =
+            ConsoleProgressReporter::new("Uploading", Some(10), 4).with_options(&options, "dst");
+        reporter.maybe_report(4);
+        reporter.finish(10);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.starts_with("dst 100% 10/10 bytes "));
+    }
+
+    #[test]
+    fn test_text_progress_only_opens_line_on_terminal() {
+        let mut reporter = ConsoleProgressReporter::new("Uploading", Some(10), 1);
+        reporter.interactive = false;
+        reporter.maybe_report(5);
+        assert!(!reporter.line_open.load(Ordering::Relaxed));
+
+        reporter.interactive = true;
+        reporter.maybe_report(5);
+        assert!(reporter.line_open.load(Ordering::Relaxed));
+        reporter.finish(10);
+        assert!(!reporter.line_open.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_disabled_progress_writes_nothing() {
+        let dir = TempDir::new();
+        let path = dir.join("progress");
+        let options = ProgressOptions {
+            disabled: true,
+            file: Some(path.clone()),
+            ..Default::default()
+        };
+        ConsoleProgressReporter::new("Uploading", Some(10), 1)
+            .with_options(&options, "dst")
+            .finish(10);
+        assert!(!path.exists());
+    }
+}
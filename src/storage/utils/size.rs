@@ -13,3 +13,97 @@ pub fn format_size(size: u64) -> String {
     }
     format!("{size_f:.1}{}", UNITS[unit_index])
 }
+
+/// Parse a size such as `512`, `64K`, `8M`, `8MiB` or `1G`, using the same 1024 base as
+/// `format_size`.
+pub fn parse_size(input: &str) -> Result<usize, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (value, unit) = input.split_at(split);
+    let value: usize = value
+        .parse()
+        .map_err(|_| format!("invalid number in '{input}'"))?;
+    let multiplier: usize = match unit.trim_end_matches("iB").trim_end_matches('B') {
+        "" => 1,
+        "K" | "k" => 1 << 10,
+        "M" | "m" => 1 << 20,
+        "G" | "g" => 1 << 30,
+        _ => {
+            return Err(format!(
+                "unknown unit '{unit}' in '{input}' (use K, M or G)"
+            ));
+        }
+    };
+    value
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size '{input}' is too large"))
+}
+
+/// Inclusive band of file sizes a directory transfer keeps (`--min-file-size`,
+/// `--max-file-size`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SizeRange {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+impl SizeRange {
+    /// Whether `size` lies inside the band.
+    pub fn contains(&self, size: u64) -> bool {
+        self.min.is_none_or(|min| size >= min) && self.max.is_none_or(|max| size <= max)
+    }
+
+    /// Whether either bound is set, so files need their sizes checked at all.
+    pub fn is_bounded(&self) -> bool {
+        self.min.is_some() || self.max.is_some()
+    }
+}
+
+/// Print how many files a size band left out of a transfer.
+pub fn report_out_of_range(count: usize) {
+    if count > 0 {
+        eprintln!(
+            "Note: skipped {count} file(s) outside the --min-file-size/--max-file-size range"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_range_is_inclusive() {
+        let band = SizeRange {
+            min: Some(1024),
+            max: Some(4096),
+        };
+        assert!(!band.contains(1023));
+        assert!(band.contains(1024));
+        assert!(band.contains(4096));
+        assert!(!band.contains(4097));
+        assert!(SizeRange::default().contains(0));
+        assert!(
+            SizeRange {
+                min: None,
+                max: Some(0)
+            }
+            .contains(0)
+        );
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("512B"), Ok(512));
+        assert_eq!(parse_size("64K"), Ok(64 * 1024));
+        assert_eq!(parse_size("8M"), Ok(8 * 1024 * 1024));
+        assert_eq!(parse_size("8MiB"), Ok(8 * 1024 * 1024));
+        assert_eq!(parse_size("5MB"), Ok(5 * 1024 * 1024));
+        assert_eq!(parse_size("1G"), Ok(1024 * 1024 * 1024));
+        assert!(parse_size("8X").is_err());
+        assert!(parse_size("M").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+// Ordering helpers for listing output
+use std::cmp::Ordering;
+
+/// Sort order applied to collected listing entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Lexical order by path
+    Name,
+    /// Largest entries first
+    Size,
+    /// Most recently modified entries first
+    Time,
+    /// Natural order by path, comparing embedded numbers numerically
+    Version,
+}
+
+/// Compare two strings so that embedded digit runs are ordered by numeric value,
+/// e.g. `file2` sorts before `file10`.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut left = a.chars().peekable();
+    let mut right = b.chars().peekable();
+
+    loop {
+        match (left.peek().copied(), right.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+                let l_num = take_digits(&mut left);
+                let r_num = take_digits(&mut right);
+                let ordering = compare_digit_runs(&l_num, &r_num);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(l), Some(r)) => {
+                if l != r {
+                    return l.cmp(&r);
+                }
+                left.next();
+                right.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek().copied().filter(char::is_ascii_digit) {
+        digits.push(c);
+        chars.next();
+    }
+    digits
+}
+
+/// Compare two digit runs by numeric value without parsing, so arbitrarily long runs work.
+fn compare_digit_runs(a: &str, b: &str) -> Ordering {
+    let a_trimmed = a.trim_start_matches('0');
+    let b_trimmed = b.trim_start_matches('0');
+    a_trimmed
+        .len()
+        .cmp(&b_trimmed.len())
+        .then_with(|| a_trimmed.cmp(b_trimmed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp_orders_numbers_numerically() {
+        assert_eq!(natural_cmp("file2", "file10"), Ordering::Less);
+        assert_eq!(natural_cmp("file10", "file2"), Ordering::Greater);
+        assert_eq!(natural_cmp("a/file1.txt", "a/file1.txt"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_natural_cmp_sorts_vector() {
+        let mut names = vec!["file10", "file2", "file1", "file02b", "afile"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, vec!["afile", "file1", "file2", "file02b", "file10"]);
+    }
+}
@@ -0,0 +1,39 @@
+// Totals of a finished transfer, returned to library callers
+use crate::storage::utils::size::format_size;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// What an upload or download moved: files written (skipped files don't count), their
+/// bytes, and the wall-clock time the whole transfer took.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferStats {
+    pub files: usize,
+    pub bytes: u64,
+    pub duration: Duration,
+}
+
+impl TransferStats {
+    /// Count one transferred file of `bytes`.
+    pub fn add_file(&mut self, bytes: u64) {
+        self.files += 1;
+        self.bytes += bytes;
+    }
+
+    /// Set the duration to the time elapsed since `started`.
+    pub fn finish(mut self, started: Instant) -> Self {
+        self.duration = started.elapsed();
+        self
+    }
+}
+
+impl fmt::Display for TransferStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} file(s), {} in {:.2}s",
+            self.files,
+            format_size(self.bytes),
+            self.duration.as_secs_f64()
+        )
+    }
+}
@@ -0,0 +1,115 @@
+// Output templates with `{placeholder}` fields, used to customize per-entry output
+use crate::error::{Error, Result};
+
+/// A piece of a parsed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field(String),
+}
+
+/// A parsed output template such as `{size}\t{path}`.
+///
+/// Placeholders are written as `{name}`; `{{` and `}}` produce literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parse a template, rejecting placeholders that are not in `fields`.
+    pub fn parse(input: &str, fields: &[&str]) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = input.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => {
+                                return Err(invalid(format!("unclosed placeholder '{{{name}'")));
+                            }
+                        }
+                    }
+                    if !fields.contains(&name.as_str()) {
+                        return Err(invalid(format!(
+                            "unknown placeholder '{{{name}}}', expected one of: {}",
+                            fields.join(", ")
+                        )));
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(name));
+                }
+                '}' => return Err(invalid("unmatched '}'".to_string())),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Render the template, resolving each placeholder through `lookup`.
+    pub fn render(&self, lookup: impl Fn(&str) -> String) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Field(name) => out.push_str(&lookup(name)),
+            }
+        }
+        out
+    }
+}
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidTemplate { reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: &[&str] = &["size", "path"];
+
+    #[test]
+    fn test_render_size_and_path() {
+        let template = Template::parse("{size}\t{path}", FIELDS).unwrap();
+        let rendered = template.render(|name| match name {
+            "size" => "42".to_string(),
+            "path" => "dir/file.txt".to_string(),
+            _ => unreachable!(),
+        });
+        assert_eq!(rendered, "42\tdir/file.txt");
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let template = Template::parse("{{{path}}}", FIELDS).unwrap();
+        assert_eq!(template.render(|_| "p".to_string()), "{p}");
+    }
+
+    #[test]
+    fn test_rejects_invalid_templates() {
+        assert!(Template::parse("{owner}", FIELDS).is_err());
+        assert!(Template::parse("{size", FIELDS).is_err());
+        assert!(Template::parse("size}", FIELDS).is_err());
+    }
+}
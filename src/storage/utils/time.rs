@@ -0,0 +1,223 @@
+// Timestamp rendering helpers shared by listing output
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// How timestamps are rendered in text output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum TimeStyle {
+    /// RFC3339 timestamp, e.g. `2024-01-02T03:04:05+00:00`
+    #[default]
+    Rfc3339,
+    /// Seconds since the Unix epoch
+    Epoch,
+    /// Age relative to now, e.g. `3 days ago`
+    Relative,
+}
+
+impl TimeStyle {
+    /// Render a timestamp according to this style.
+    pub fn format(&self, time: &DateTime<Utc>) -> String {
+        match self {
+            TimeStyle::Rfc3339 => time.to_rfc3339(),
+            TimeStyle::Epoch => time.timestamp().to_string(),
+            TimeStyle::Relative => format_relative(time, &Utc::now()),
+        }
+    }
+}
+
+/// Render the distance between `time` and `now` as a short English phrase.
+pub fn format_relative(time: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+    let delta = now.signed_duration_since(*time).num_seconds();
+    let secs = delta.unsigned_abs();
+    if secs == 0 {
+        return "just now".to_string();
+    }
+
+    let (value, unit) = match secs {
+        0..60 => (secs, "second"),
+        60..3_600 => (secs / 60, "minute"),
+        3_600..86_400 => (secs / 3_600, "hour"),
+        86_400..2_592_000 => (secs / 86_400, "day"),
+        2_592_000..31_536_000 => (secs / 2_592_000, "month"),
+        _ => (secs / 31_536_000, "year"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+
+    if delta >= 0 {
+        format!("{value} {unit}{plural} ago")
+    } else {
+        format!("in {value} {unit}{plural}")
+    }
+}
+
+/// Parse a compact age such as `90s`, `30m`, `12h`, `7d` or `2w`.
+pub fn parse_age(input: &str) -> Result<chrono::Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("missing unit in '{input}' (use s, m, h, d or w)"))?;
+    let (value, unit) = input.split_at(split);
+    let value: i64 = value
+        .parse()
+        .map_err(|_| format!("invalid number in '{input}'"))?;
+    match unit {
+        "s" => Ok(chrono::Duration::seconds(value)),
+        "m" => Ok(chrono::Duration::minutes(value)),
+        "h" => Ok(chrono::Duration::hours(value)),
+        "d" => Ok(chrono::Duration::days(value)),
+        "w" => Ok(chrono::Duration::weeks(value)),
+        _ => Err(format!(
+            "unknown unit '{unit}' in '{input}' (use s, m, h, d or w)"
+        )),
+    }
+}
+
+/// Parse a point in time given as RFC3339 (`2024-01-02T03:04:05Z`) or as a plain date
+/// (`2024-01-02`, meaning midnight UTC).
+pub fn parse_datetime(input: &str) -> Result<DateTime<Utc>, String> {
+    let input = input.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|time| time.and_utc())
+        .ok_or_else(|| format!("invalid date '{input}' (use YYYY-MM-DD or RFC3339)"))
+}
+
+/// An inclusive window of modification times; either end may be open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeRange {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    /// Objects modified at most `age` before `now`, e.g. within the last 24 hours.
+    pub fn within(age: chrono::Duration, now: DateTime<Utc>) -> Self {
+        Self {
+            since: Some(now - age),
+            until: None,
+        }
+    }
+
+    /// Whether neither end is set, so every entry matches.
+    pub fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    /// Whether `modified` falls inside the window. Undated objects only match an
+    /// unbounded range.
+    pub fn contains(&self, modified: Option<DateTime<Utc>>) -> bool {
+        let Some(modified) = modified else {
+            return self.is_unbounded();
+        };
+        self.since.is_none_or(|since| modified >= since)
+            && self.until.is_none_or(|until| modified <= until)
+    }
+}
+
+/// Tell the user how many objects a date-ranged query left out for having no
+/// modification time.
+pub fn report_undated(count: usize) {
+    if count > 0 {
+        eprintln!(
+            "Note: {count} object(s) without a modification time excluded by the time filter"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn known_time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()
+    }
+
+    #[test]
+    fn test_format_rfc3339() {
+        assert_eq!(
+            TimeStyle::Rfc3339.format(&known_time()),
+            "2024-01-02T03:04:05+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_datetime() {
+        assert_eq!(
+            parse_datetime("2024-01-02").unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_datetime("2024-01-02T05:04:05+02:00").unwrap(),
+            known_time()
+        );
+        assert!(parse_datetime("02/01/2024").is_err());
+    }
+
+    #[test]
+    fn test_time_range_is_inclusive_and_excludes_undated() {
+        let time = known_time();
+        let range = TimeRange {
+            since: Some(time),
+            until: Some(time),
+        };
+        assert!(range.contains(Some(time)));
+        assert!(!range.contains(Some(time - chrono::Duration::seconds(1))));
+        assert!(!range.contains(Some(time + chrono::Duration::seconds(1))));
+        assert!(!range.contains(None));
+
+        let open = TimeRange {
+            since: Some(time),
+            until: None,
+        };
+        assert!(open.contains(Some(time + chrono::Duration::days(365))));
+        assert!(TimeRange::default().contains(None));
+    }
+
+    #[test]
+    fn test_time_range_within_relative_age() {
+        let now = known_time();
+        let last_day = TimeRange::within(chrono::Duration::hours(24), now);
+        for (age, inside) in [
+            (chrono::Duration::minutes(30), true),
+            (chrono::Duration::hours(24), true),
+            (chrono::Duration::hours(25), false),
+            (chrono::Duration::days(7), false),
+        ] {
+            assert_eq!(last_day.contains(Some(now - age)), inside, "{age}");
+        }
+        assert!(!last_day.contains(None));
+    }
+
+    #[test]
+    fn test_format_epoch() {
+        assert_eq!(TimeStyle::Epoch.format(&known_time()), "1704164645");
+    }
+
+    #[test]
+    fn test_format_relative() {
+        let time = known_time();
+        let now = time + chrono::Duration::days(3);
+        assert_eq!(format_relative(&time, &now), "3 days ago");
+        assert_eq!(format_relative(&time, &time), "just now");
+
+        let now = time + chrono::Duration::seconds(1);
+        assert_eq!(format_relative(&time, &now), "1 second ago");
+
+        let now = time - chrono::Duration::hours(2);
+        assert_eq!(format_relative(&time, &now), "in 2 hours");
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("7d"), Ok(chrono::Duration::days(7)));
+        assert_eq!(parse_age("12h"), Ok(chrono::Duration::hours(12)));
+        assert_eq!(parse_age("90s"), Ok(chrono::Duration::seconds(90)));
+        assert!(parse_age("7").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("7y").is_err());
+    }
+}
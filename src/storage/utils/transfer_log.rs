@@ -0,0 +1,158 @@
+// CSV audit trail of every file a put or get transferred (`--transfer-log`)
+use crate::error::Result;
+use chrono::Utc;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+/// Columns of the log, written once at the top of a new file.
+const HEADER: &str = "timestamp,operation,path,bytes,status,duration_ms";
+
+/// Which command moved the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferOperation {
+    Put,
+    Get,
+}
+
+impl fmt::Display for TransferOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransferOperation::Put => "put",
+            TransferOperation::Get => "get",
+        })
+    }
+}
+
+/// `timestamp,operation,path,bytes,status,duration_ms` rows, appended and flushed one file
+/// at a time so a crash still leaves every finished transfer on record. Clones share the
+/// file.
+#[derive(Clone)]
+pub struct TransferLog {
+    path: PathBuf,
+    file: Arc<Mutex<File>>,
+}
+
+impl fmt::Debug for TransferLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransferLog")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TransferLog {
+    /// Open the log at `path` for appending, writing the header if the file is new.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{HEADER}")?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    /// Append the outcome of one file: `ok` with the bytes moved, `skipped`, or `failed`.
+    pub fn record(
+        &self,
+        operation: TransferOperation,
+        path: &str,
+        outcome: &Result<Option<u64>>,
+        started: Instant,
+    ) -> Result<()> {
+        let (bytes, status) = match outcome {
+            Ok(Some(bytes)) => (*bytes, "ok"),
+            Ok(None) => (0, "skipped"),
+            Err(_) => (0, "failed"),
+        };
+        let row = format!(
+            "{},{operation},{},{bytes},{status},{}\n",
+            Utc::now().to_rfc3339(),
+            csv_field(path),
+            started.elapsed().as_millis()
+        );
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        file.write_all(row.as_bytes())?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Record `outcome` in `log`, if there is one, and hand it back. A row that cannot be
+/// written fails the file, so the log never silently misses a transfer.
+pub fn log_transfer(
+    log: Option<&TransferLog>,
+    operation: TransferOperation,
+    path: &str,
+    started: Instant,
+    outcome: Result<Option<u64>>,
+) -> Result<Option<u64>> {
+    if let Some(log) = log {
+        log.record(operation, path, &outcome, started)?;
+    }
+    outcome
+}
+
+/// Quote a field that contains a comma, quote or line break, doubling inner quotes.
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::storage::utils::fixture::TempDir;
+
+    #[test]
+    fn test_transfer_log_appends_rows_under_one_header() {
+        let dir = TempDir::new();
+        let path = dir.join("log.csv");
+        let started = Instant::now();
+        let log = TransferLog::open(&path).unwrap();
+        log.record(TransferOperation::Put, "dst/a.txt", &Ok(Some(5)), started)
+            .unwrap();
+        log.record(TransferOperation::Get, "dst/b,c.txt", &Ok(None), started)
+            .unwrap();
+        // Reopening appends instead of truncating or repeating the header.
+        let log = TransferLog::open(&path).unwrap();
+        let failed = Err(Error::PathNotFound {
+            path: PathBuf::from("dst/d.txt"),
+        });
+        log.record(TransferOperation::Get, "dst/d.txt", &failed, started)
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], HEADER);
+        // Timestamp and duration vary; check their shape and compare the rest.
+        let rows: Vec<&str> = lines[1..]
+            .iter()
+            .map(|line| {
+                let (timestamp, rest) = line.split_once(',').unwrap();
+                assert!(chrono::DateTime::parse_from_rfc3339(timestamp).is_ok());
+                let (fields, duration_ms) = rest.rsplit_once(',').unwrap();
+                assert!(duration_ms.parse::<u128>().is_ok());
+                fields
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                "put,dst/a.txt,5,ok",
+                "get,\"dst/b,c.txt\",0,skipped",
+                "get,dst/d.txt,0,failed",
+            ]
+        );
+    }
+}
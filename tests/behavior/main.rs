@@ -15,10 +15,20 @@ fn main() -> Result<()> {
     let mut tests = Vec::new();
 
     operations::list::tests(&client, &mut tests);
+    operations::config::tests(&client, &mut tests);
+    operations::batch::tests(&client, &mut tests);
+    operations::cat::tests(&client, &mut tests);
     operations::copy::tests(&client, &mut tests);
     operations::delete::tests(&client, &mut tests);
+    operations::doctor::tests(&client, &mut tests);
     operations::download::tests(&client, &mut tests);
+    operations::find::tests(&client, &mut tests);
+    operations::grep::tests(&client, &mut tests);
+    operations::head::tests(&client, &mut tests);
     operations::mkdir::tests(&client, &mut tests);
+    operations::read_only::tests(&client, &mut tests);
+    operations::rename::tests(&client, &mut tests);
+    operations::select::tests(&client, &mut tests);
     operations::upload::tests(&client, &mut tests);
     operations::usage::tests(&client, &mut tests);
     operations::stat::tests(&client, &mut tests);
@@ -31,7 +41,7 @@ fn main() -> Result<()> {
 
     let conclusion = libtest_mimic::run(&args, tests);
 
-    TEST_RUNTIME.block_on(TEST_FIXTURE.cleanup(client.operator()));
+    TEST_RUNTIME.block_on(TEST_FIXTURE.cleanup(&client.operator()));
 
     conclusion.exit()
 }
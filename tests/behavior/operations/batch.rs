@@ -0,0 +1,127 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+use uuid::Uuid;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_batch_runs_mixed_operations,
+        test_batch_stop_on_error,
+        test_batch_nonzero_status_fails_line
+    ));
+}
+
+async fn test_batch_runs_mixed_operations(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let root = std::env::temp_dir().join(format!("storify-batch-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&root)?;
+    let local = root.join("a file.txt");
+    std::fs::write(&local, b"batch content")?;
+    client
+        .operator()
+        .write(&format!("{dir}old.txt"), b"stale".to_vec())
+        .await?;
+
+    let script = root.join("ops.txt");
+    std::fs::write(
+        &script,
+        format!(
+            "# stage, copy and clean up\n\
+             mkdir {dir}sub/\n\
+             put '{}' {dir}\n\
+             \n\
+             cp '{dir}a file.txt' {dir}sub/b.txt\n\
+             rm -f {dir}old.txt\n",
+            local.display()
+        ),
+    )?;
+
+    storify_cmd()
+        .arg("batch")
+        .arg(&script)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[2] ok: mkdir"))
+        .stdout(predicate::str::contains("[6] ok: rm -f"))
+        .stdout(predicate::str::contains("Batch: 4 succeeded, 0 failed"));
+
+    let op = client.operator();
+    assert_eq!(
+        op.read(&format!("{dir}a file.txt")).await?.to_vec(),
+        b"batch content"
+    );
+    assert_eq!(
+        op.read(&format!("{dir}sub/b.txt")).await?.to_vec(),
+        b"batch content"
+    );
+    assert!(!op.exists(&format!("{dir}old.txt")).await?);
+
+    let _ = std::fs::remove_dir_all(&root);
+    Ok(())
+}
+
+async fn test_batch_stop_on_error(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let missing = TEST_FIXTURE.new_file_path();
+    let script = std::env::temp_dir().join(format!("storify-batch-{}.txt", Uuid::new_v4()));
+    std::fs::write(
+        &script,
+        format!("mkdir {dir}\ncp {missing} {dir}copy.txt\nbogus-command\nmkdir {dir}later/\n"),
+    )?;
+
+    // Without --stop-on-error every line runs and the batch fails at the end.
+    storify_cmd()
+        .arg("batch")
+        .arg(&script)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("[2] failed: cp"))
+        .stderr(predicate::str::contains("[3] failed: bogus-command"))
+        .stdout(predicate::str::contains("Batch: 2 succeeded, 2 failed"));
+    assert!(client.operator().exists(&format!("{dir}later/")).await?);
+    client.operator().delete(&format!("{dir}later/")).await?;
+
+    storify_cmd()
+        .args(["batch", "--stop-on-error"])
+        .arg(&script)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Stopping at line 2"))
+        .stdout(predicate::str::contains("Batch: 1 succeeded, 1 failed"));
+    assert!(!client.operator().exists(&format!("{dir}later/")).await?);
+
+    let _ = std::fs::remove_file(&script);
+    Ok(())
+}
+
+async fn test_batch_nonzero_status_fails_line(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let script = std::env::temp_dir().join(format!("storify-batch-{}.txt", Uuid::new_v4()));
+    std::fs::write(
+        &script,
+        format!(
+            "exists {dir}missing.txt
+mkdir {dir}after/
+"
+        ),
+    )?;
+
+    // A miss fails its line without ending the batch.
+    storify_cmd()
+        .arg("batch")
+        .arg(&script)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(format!(
+            "[1] failed: exists {dir}missing.txt: exit status 1"
+        )))
+        .stdout(predicate::str::contains("[2] ok: mkdir"))
+        .stdout(predicate::str::contains("Batch: 1 succeeded, 1 failed"));
+    assert!(client.operator().exists(&format!("{dir}after/")).await?);
+
+    let _ = std::fs::remove_file(&script);
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_cat_prints_file_contents,
+        test_cat_rejects_directories
+    ));
+}
+
+async fn test_cat_prints_file_contents(client: StorageClient) -> Result<()> {
+    let (path, content, _) = TEST_FIXTURE.new_file(&client.operator());
+    client.operator().write(&path, content.clone()).await?;
+
+    let output = storify_cmd().args(["cat", &path]).assert().success();
+    assert_eq!(output.get_output().stdout, content);
+
+    Ok(())
+}
+
+async fn test_cat_rejects_directories(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    client
+        .operator()
+        .write(&format!("{dir}file.txt"), b"hello".to_vec())
+        .await?;
+
+    storify_cmd()
+        .args(["cat", &dir])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is a directory"));
+
+    Ok(())
+}
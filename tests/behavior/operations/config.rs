@@ -0,0 +1,41 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_config_show_sources_and_redaction
+    ));
+}
+
+async fn test_config_show_sources_and_redaction(_client: StorageClient) -> Result<()> {
+    let secret = "config-show-secret";
+    storify_cmd()
+        .args(["config", "show", "--read-only"])
+        .env("STORAGE_ACCESS_KEY_SECRET", secret)
+        .env("MINIO_SECRET_KEY", "shadowed-secret")
+        .env_remove("STORAGE_REGION")
+        .env_remove("MINIO_DEFAULT_REGION")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(env STORAGE_PROVIDER)"))
+        .stdout(predicate::str::contains("(env STORAGE_ACCESS_KEY_SECRET)"))
+        .stdout(predicate::str::contains(secret).not())
+        .stdout(predicate::str::contains("shadowed-secret").not())
+        .stdout(predicate::str::is_match(r"region\s+-\s+\(unset\)").unwrap())
+        .stdout(predicate::str::is_match(r"read_only\s+true\s+\(flag --read-only or env").unwrap());
+
+    // Works even when the config could not be loaded.
+    storify_cmd()
+        .args(["config", "show"])
+        .env_remove("STORAGE_BUCKET")
+        .env_remove("MINIO_BUCKET")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"bucket\s+-\s+\(unset\)").unwrap());
+
+    Ok(())
+}
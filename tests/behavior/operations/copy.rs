@@ -12,8 +12,15 @@ pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
         test_copy_file_to_new_path,
         test_copy_across_directory,
         test_copy_overwrite_existing_file,
+        test_copy_overwrite_sibling_in_same_directory,
         test_copy_to_nonexistent_directory,
-        test_copy_non_existent_file
+        test_copy_non_existent_file,
+        test_copy_between_fs_roots,
+        test_copy_server_side_toggle,
+        test_copy_preserve_metadata,
+        test_copy_client_side_with_request_limit,
+        test_copy_replace_content_type,
+        test_copy_overwrite_policy
     ));
 }
 
@@ -21,7 +28,7 @@ async fn test_copy_file_to_existing_directory(client: StorageClient) -> Result<(
     let src_dir = TEST_FIXTURE.new_dir_path();
     client.operator().create_dir(&src_dir).await?;
 
-    let (src_file, content, _) = TEST_FIXTURE.new_file(client.operator());
+    let (src_file, content, _) = TEST_FIXTURE.new_file(&client.operator());
     let src_file_path = format!("{}{}", src_dir, src_file);
     client
         .operator()
@@ -49,7 +56,7 @@ async fn test_copy_file_to_existing_directory(client: StorageClient) -> Result<(
 }
 
 async fn test_copy_file_to_new_path(client: StorageClient) -> Result<()> {
-    let (src_file, content, _) = TEST_FIXTURE.new_file(client.operator());
+    let (src_file, content, _) = TEST_FIXTURE.new_file(&client.operator());
     client.operator().write(&src_file, content.clone()).await?;
 
     let dest_file = TEST_FIXTURE.new_file_path();
@@ -71,7 +78,7 @@ async fn test_copy_file_to_new_path(client: StorageClient) -> Result<()> {
 }
 
 async fn test_copy_across_directory(client: StorageClient) -> Result<()> {
-    let (src_path, content, _) = TEST_FIXTURE.new_file(client.operator());
+    let (src_path, content, _) = TEST_FIXTURE.new_file(&client.operator());
     client.operator().write(&src_path, content.clone()).await?;
 
     let dest_path = TEST_FIXTURE.new_dir_path();
@@ -100,13 +107,13 @@ async fn test_copy_across_directory(client: StorageClient) -> Result<()> {
 }
 
 async fn test_copy_overwrite_existing_file(client: StorageClient) -> Result<()> {
-    let (src_file_path, src_content, _) = TEST_FIXTURE.new_file(client.operator());
+    let (src_file_path, src_content, _) = TEST_FIXTURE.new_file(&client.operator());
     client
         .operator()
         .write(&src_file_path, src_content.clone())
         .await?;
 
-    let (dst_file_path, dst_content, _) = TEST_FIXTURE.new_file(client.operator());
+    let (dst_file_path, dst_content, _) = TEST_FIXTURE.new_file(&client.operator());
     client
         .operator()
         .write(&dst_file_path, dst_content.clone())
@@ -133,8 +140,34 @@ async fn test_copy_overwrite_existing_file(client: StorageClient) -> Result<()>
     Ok(())
 }
 
+async fn test_copy_overwrite_sibling_in_same_directory(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let (src, dst) = (format!("{dir}a.txt"), format!("{dir}b.txt"));
+    client.operator().write(&src, b"from a".to_vec()).await?;
+
+    // A longer destination shows the copy replaces the object rather than writing into it.
+    for client_side in [false, true] {
+        client
+            .operator()
+            .write(&dst, b"old content of b".to_vec())
+            .await?;
+
+        let mut cmd = storify_cmd();
+        cmd.arg("cp");
+        if client_side {
+            cmd.arg("--client-side");
+        }
+        cmd.arg(&src).arg(&dst).assert().success();
+
+        assert_eq!(client.operator().read(&dst).await?.to_vec(), b"from a");
+        assert!(!client.operator().exists(&format!("{dst}/a.txt")).await?);
+    }
+
+    Ok(())
+}
+
 async fn test_copy_to_nonexistent_directory(client: StorageClient) -> Result<()> {
-    let (src_file, content, _) = TEST_FIXTURE.new_file(client.operator());
+    let (src_file, content, _) = TEST_FIXTURE.new_file(&client.operator());
     client.operator().write(&src_file, content.clone()).await?;
 
     let nonexistent_dir = format!("{}/", TEST_FIXTURE.new_dir_path());
@@ -169,3 +202,226 @@ async fn test_copy_non_existent_file(client: StorageClient) -> Result<()> {
 
     Ok(())
 }
+
+async fn test_copy_between_fs_roots(_client: StorageClient) -> Result<()> {
+    let src_root = std::env::temp_dir().join(format!("storify-src-{}", uuid::Uuid::new_v4()));
+    let dest_root = std::env::temp_dir().join(format!("storify-dest-{}", uuid::Uuid::new_v4()));
+    tokio::fs::create_dir_all(src_root.join("nested")).await?;
+    tokio::fs::create_dir_all(&dest_root).await?;
+    tokio::fs::write(src_root.join("a.txt"), b"alpha").await?;
+    tokio::fs::write(src_root.join("nested").join("b.txt"), b"beta").await?;
+
+    storify_cmd()
+        .arg("cp")
+        .arg(format!("fs://{}/a.txt", src_root.display()))
+        .arg(format!("fs://{}/", dest_root.display()))
+        .assert()
+        .success();
+    assert_eq!(tokio::fs::read(dest_root.join("a.txt")).await?, b"alpha");
+
+    storify_cmd()
+        .arg("cp")
+        .arg(format!("fs://{}/nested/", src_root.display()))
+        .arg(format!("fs://{}/copied/", dest_root.display()))
+        .assert()
+        .success();
+    assert_eq!(
+        tokio::fs::read(dest_root.join("copied").join("b.txt")).await?,
+        b"beta"
+    );
+
+    let _ = tokio::fs::remove_dir_all(&src_root).await;
+    let _ = tokio::fs::remove_dir_all(&dest_root).await;
+    Ok(())
+}
+
+async fn test_copy_server_side_toggle(client: StorageClient) -> Result<()> {
+    let (src_file, content, _) =
+        TEST_FIXTURE.new_file_with_range(uuid::Uuid::new_v4().to_string(), 10..100);
+    client.operator().write(&src_file, content.clone()).await?;
+
+    let server_dest = TEST_FIXTURE.new_file_path();
+    storify_cmd()
+        .arg("cp")
+        .arg(&src_file)
+        .arg(&server_dest)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("server-side"));
+    assert_eq!(
+        client.operator().read(&server_dest).await?.to_vec(),
+        content
+    );
+
+    let client_dest = TEST_FIXTURE.new_file_path();
+    storify_cmd()
+        .arg("cp")
+        .arg("--client-side")
+        .arg(&src_file)
+        .arg(&client_dest)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("server-side").not());
+    assert_eq!(
+        client.operator().read(&client_dest).await?.to_vec(),
+        content
+    );
+
+    Ok(())
+}
+
+async fn test_copy_preserve_metadata(client: StorageClient) -> Result<()> {
+    let src_file = TEST_FIXTURE.new_file_path();
+    client
+        .operator()
+        .write_with(&src_file, b"<p>hello</p>".to_vec())
+        .content_type("text/html")
+        .cache_control("max-age=600")
+        .user_metadata([("owner".to_string(), "web-team".to_string())])
+        .await?;
+
+    let preserved = TEST_FIXTURE.new_file_path();
+    storify_cmd()
+        .arg("cp")
+        .arg("--client-side")
+        .arg("--preserve-metadata")
+        .arg(&src_file)
+        .arg(format!("minio://{}/{preserved}", test_bucket()))
+        .assert()
+        .success();
+    let meta = client.operator().stat(&preserved).await?;
+    assert_eq!(meta.content_type(), Some("text/html"));
+    assert_eq!(meta.cache_control(), Some("max-age=600"));
+    assert_eq!(
+        meta.user_metadata()
+            .and_then(|m| m.get("owner"))
+            .map(String::as_str),
+        Some("web-team")
+    );
+
+    let plain = TEST_FIXTURE.new_file_path();
+    storify_cmd()
+        .arg("cp")
+        .arg("--client-side")
+        .arg(&src_file)
+        .arg(format!("minio://{}/{plain}", test_bucket()))
+        .assert()
+        .success();
+    let meta = client.operator().stat(&plain).await?;
+    assert!(
+        meta.user_metadata()
+            .is_none_or(|m| !m.contains_key("owner"))
+    );
+
+    Ok(())
+}
+
+async fn test_copy_client_side_with_request_limit(client: StorageClient) -> Result<()> {
+    let (src_file, content, _) = TEST_FIXTURE.new_file(&client.operator());
+    client.operator().write(&src_file, content.clone()).await?;
+
+    // The reader and writer of a streaming copy are open together; a cap of one request
+    // must still let it finish.
+    let dest = TEST_FIXTURE.new_file_path();
+    storify_cmd()
+        .args(["--max-concurrent-requests", "1", "cp", "--client-side"])
+        .arg(&src_file)
+        .arg(&dest)
+        .assert()
+        .success();
+    assert_eq!(client.operator().read(&dest).await?.to_vec(), content);
+
+    Ok(())
+}
+
+async fn test_copy_replace_content_type(client: StorageClient) -> Result<()> {
+    let src_file = TEST_FIXTURE.new_file_path();
+    client
+        .operator()
+        .write_with(&src_file, b"plain words".to_vec())
+        .content_type("application/octet-stream")
+        .cache_control("max-age=600")
+        .await?;
+
+    let dest = TEST_FIXTURE.new_file_path();
+    storify_cmd()
+        .args(["cp", "--metadata-directive", "replace"])
+        .args(["--content-type", "text/plain"])
+        .arg(&src_file)
+        .arg(&dest)
+        .assert()
+        .success();
+    let meta = client.operator().stat(&dest).await?;
+    assert_eq!(meta.content_type(), Some("text/plain"));
+    assert_eq!(meta.cache_control(), None);
+    assert_eq!(
+        client.operator().read(&dest).await?.to_vec(),
+        b"plain words"
+    );
+
+    // Fixing a content type in place keeps the bytes.
+    storify_cmd()
+        .args(["cp", "--metadata-directive", "replace"])
+        .args(["--content-type", "text/plain"])
+        .arg(&src_file)
+        .arg(&src_file)
+        .assert()
+        .success();
+    let meta = client.operator().stat(&src_file).await?;
+    assert_eq!(meta.content_type(), Some("text/plain"));
+    assert_eq!(
+        client.operator().read(&src_file).await?.to_vec(),
+        b"plain words"
+    );
+
+    storify_cmd()
+        .args(["cp", "--content-type", "text/plain"])
+        .arg(&src_file)
+        .arg(TEST_FIXTURE.new_file_path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--metadata-directive replace"));
+
+    Ok(())
+}
+
+async fn test_copy_overwrite_policy(client: StorageClient) -> Result<()> {
+    let op = client.operator();
+    let src = TEST_FIXTURE.new_file_path();
+    let dest = TEST_FIXTURE.new_file_path();
+    let copy_with = |policy: &str| {
+        storify_cmd()
+            .args(["cp", "--overwrite-policy", policy])
+            .arg(&src)
+            .arg(&dest)
+            .assert()
+            .success()
+    };
+
+    op.write(&src, b"source".to_vec()).await?;
+    op.write(&dest, b"dest".to_vec()).await?;
+    copy_with("never").stderr(predicate::str::contains("destination already exists"));
+    assert_eq!(op.read(&dest).await?.to_vec(), b"dest");
+
+    // The destination was written last, so the source is not newer.
+    copy_with("newer").stderr(predicate::str::contains("at least as new"));
+    assert_eq!(op.read(&dest).await?.to_vec(), b"dest");
+
+    copy_with("if-size-differs");
+    assert_eq!(op.read(&dest).await?.to_vec(), b"source");
+    op.write(&src, b"SOURCE".to_vec()).await?;
+    copy_with("if-size-differs").stderr(predicate::str::contains("same size"));
+    assert_eq!(op.read(&dest).await?.to_vec(), b"source");
+
+    // Modification times often have one-second resolution.
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    op.write(&src, b"newest".to_vec()).await?;
+    copy_with("newer");
+    assert_eq!(op.read(&dest).await?.to_vec(), b"newest");
+
+    op.write(&dest, b"stale".to_vec()).await?;
+    copy_with("always");
+    assert_eq!(op.read(&dest).await?.to_vec(), b"newest");
+
+    Ok(())
+}
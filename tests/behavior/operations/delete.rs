@@ -11,12 +11,13 @@ pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
         test_delete_non_existent_file,
         test_delete_empty_directory,
         test_delete_non_empty_directory_recursively,
-        test_delete_multiple_files_bulk
+        test_delete_multiple_files_bulk,
+        test_delete_directory_requires_recursive
     ));
 }
 
 async fn test_delete_single_file(client: StorageClient) -> Result<()> {
-    let (path, content, _) = TEST_FIXTURE.new_file(client.operator());
+    let (path, content, _) = TEST_FIXTURE.new_file(&client.operator());
     client.operator().write(&path, content).await?;
 
     storify_cmd()
@@ -86,7 +87,8 @@ async fn test_delete_non_empty_directory_recursively(client: StorageClient) -> R
         .arg("--force")
         .arg(&root_dir)
         .assert()
-        .success();
+        .success()
+        .stdout(predicate::str::contains(format!("Deleted: {path}")));
 
     let result = client.operator().stat(&root_dir).await;
     assert!(result.is_err(), "Root directory should be deleted");
@@ -103,7 +105,7 @@ async fn test_delete_non_empty_directory_recursively(client: StorageClient) -> R
 async fn test_delete_multiple_files_bulk(client: StorageClient) -> Result<()> {
     let mut paths = Vec::new();
     for _ in 0..5 {
-        let (path, content, _) = TEST_FIXTURE.new_file(client.operator());
+        let (path, content, _) = TEST_FIXTURE.new_file(&client.operator());
         client.operator().write(&path, content).await?;
         paths.push(path);
     }
@@ -122,3 +124,19 @@ async fn test_delete_multiple_files_bulk(client: StorageClient) -> Result<()> {
 
     Ok(())
 }
+
+async fn test_delete_directory_requires_recursive(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let path = format!("{dir}keep.txt");
+    client.operator().write(&path, b"keep".to_vec()).await?;
+
+    storify_cmd()
+        .args(["rm", "--force", &dir])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("without -R"));
+
+    assert!(client.operator().exists(&path).await?);
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_doctor_passes_with_valid_config,
+        test_doctor_reports_incomplete_config
+    ));
+}
+
+async fn test_doctor_passes_with_valid_config(_client: StorageClient) -> Result<()> {
+    storify_cmd()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bucket is reachable"))
+        .stdout(predicate::str::contains("0 failed"));
+
+    Ok(())
+}
+
+async fn test_doctor_reports_incomplete_config(_client: StorageClient) -> Result<()> {
+    storify_cmd()
+        .arg("doctor")
+        .env_remove("STORAGE_ACCESS_KEY_SECRET")
+        .env("STORAGE_ENDPOINT", "127.0.0.1:9000")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("access key secret is not set"))
+        .stdout(predicate::str::contains(
+            "export STORAGE_ACCESS_KEY_SECRET or MINIO_SECRET_KEY",
+        ))
+        .stdout(predicate::str::contains(
+            "export STORAGE_ENDPOINT=https://127.0.0.1:9000",
+        ))
+        .stdout(predicate::str::contains("Connectivity").not())
+        .stderr(predicate::str::contains("doctor found 2 problem(s)"));
+
+    Ok(())
+}
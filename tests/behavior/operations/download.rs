@@ -13,7 +13,15 @@ pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
         test_download_directory_recursive,
         test_download_non_existent_file,
         test_download_large_file,
-        test_download_with_special_chars
+        test_download_with_special_chars,
+        test_download_specific_version,
+        test_download_dedupe_links_identical_objects,
+        test_download_if_size_differs,
+        test_download_multiple_sources,
+        test_download_not_found_json_error,
+        test_download_fail_fast_modes,
+        test_download_concurrency_modes,
+        test_download_missing_key_strict_and_lenient
     ));
 }
 
@@ -25,7 +33,7 @@ struct StagedFile {
 }
 
 async fn stage_remote_file(client: &StorageClient) -> Result<StagedFile> {
-    let (src_file, content, _) = TEST_FIXTURE.new_file(client.operator());
+    let (src_file, content, _) = TEST_FIXTURE.new_file(&client.operator());
     let src_path = TEST_FIXTURE.new_dir_path();
 
     client.operator().create_dir(&src_path).await?;
@@ -187,3 +195,313 @@ async fn test_download_with_special_chars(client: StorageClient) -> Result<()> {
     let _ = fs::remove_dir_all(&local_dir).await;
     Ok(())
 }
+
+async fn test_download_specific_version(client: StorageClient) -> Result<()> {
+    let staged_file = stage_remote_file(&client).await?;
+
+    let output = storify_cmd()
+        .arg("ls")
+        .arg("--versions")
+        .arg(&staged_file.remote_path)
+        .output()
+        .expect("failed to run ls");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version_id = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix(&format!("{} ", staged_file.remote_path)))
+        .expect("listing should include a version ID column")
+        .to_string();
+
+    let local_dir = std::env::temp_dir().join(format!("storify-dl-{}", Uuid::new_v4()));
+    storify_cmd()
+        .arg("get")
+        .arg(&staged_file.remote_path)
+        .arg(&local_dir)
+        .arg("--version-id")
+        .arg(&version_id)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("(version {version_id})")));
+
+    let actual_content = fs::read(local_dir.join(&staged_file.file_name)).await?;
+    assert_eq!(staged_file.content, actual_content);
+
+    storify_cmd()
+        .arg("get")
+        .arg(&staged_file.remote_path)
+        .arg(&local_dir)
+        .arg("--version-id")
+        .arg("does-not-exist")
+        .assert()
+        .failure();
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_download_dedupe_links_identical_objects(client: StorageClient) -> Result<()> {
+    let remote_dir = TEST_FIXTURE.new_dir_path();
+    let content = b"same bytes in two objects".to_vec();
+    for name in ["first.bin", "second.bin"] {
+        client
+            .operator()
+            .write(&format!("{remote_dir}{name}"), content.clone())
+            .await?;
+    }
+    client
+        .operator()
+        .write(&format!("{remote_dir}other.bin"), b"different".to_vec())
+        .await?;
+
+    let local_dir = std::env::temp_dir().join(format!("storify-dl-{}", Uuid::new_v4()));
+    storify_cmd()
+        .arg("get")
+        .arg("--dedupe")
+        .arg(&remote_dir)
+        .arg(&local_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Linked:"));
+
+    let first = local_dir.join("first.bin");
+    let second = local_dir.join("second.bin");
+    assert_eq!(fs::read(&first).await?, content);
+    assert_eq!(fs::read(&second).await?, content);
+    assert_eq!(fs::read(local_dir.join("other.bin")).await?, b"different");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let first_meta = fs::metadata(&first).await?;
+        let second_meta = fs::metadata(&second).await?;
+        assert_eq!(first_meta.ino(), second_meta.ino());
+        assert_eq!(first_meta.nlink(), 2);
+    }
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_download_if_size_differs(client: StorageClient) -> Result<()> {
+    let remote_dir = TEST_FIXTURE.new_dir_path();
+    client
+        .operator()
+        .write(&format!("{remote_dir}same.txt"), b"remote-1".to_vec())
+        .await?;
+    client
+        .operator()
+        .write(&format!("{remote_dir}changed.txt"), b"remote-22".to_vec())
+        .await?;
+
+    let local_dir = std::env::temp_dir().join(format!("storify-dl-{}", Uuid::new_v4()));
+    fs::create_dir_all(&local_dir).await?;
+    // Same length as the remote object, different bytes: must be left alone.
+    fs::write(local_dir.join("same.txt"), b"local--1").await?;
+    fs::write(local_dir.join("changed.txt"), b"old").await?;
+
+    storify_cmd()
+        .arg("get")
+        .arg("--if-size-differs")
+        .arg(&remote_dir)
+        .arg(&local_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped (same size)"));
+
+    assert_eq!(fs::read(local_dir.join("same.txt")).await?, b"local--1");
+    assert_eq!(fs::read(local_dir.join("changed.txt")).await?, b"remote-22");
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_download_multiple_sources(client: StorageClient) -> Result<()> {
+    let first = stage_remote_file(&client).await?;
+    let second = stage_remote_file(&client).await?;
+    let local_dir = std::env::temp_dir().join(format!("storify-dl-multi-{}", Uuid::new_v4()));
+
+    storify_cmd()
+        .arg("get")
+        .arg(&first.remote_path)
+        .arg(&second.remote_path)
+        .arg(&local_dir)
+        .assert()
+        .success();
+
+    for staged in [&first, &second] {
+        let actual = fs::read(local_dir.join(&staged.file_name)).await?;
+        assert_eq!(staged.content, actual);
+    }
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_download_not_found_json_error(_client: StorageClient) -> Result<()> {
+    let remote_path = TEST_FIXTURE.new_file_path();
+    let local_dir = std::env::temp_dir().join(format!("storify-dl-json-{}", Uuid::new_v4()));
+
+    let output = storify_cmd()
+        .arg("--error-format")
+        .arg("json")
+        .arg("get")
+        .arg(&remote_path)
+        .arg(&local_dir)
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let line = stderr.lines().last().unwrap_or_default();
+    let json: serde_json::Value = serde_json::from_str(line)?;
+    assert_eq!(json["error"]["kind"], "path_not_found");
+    assert_eq!(json["error"]["path"], remote_path.as_str());
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .is_some_and(|m| m.contains("Failed to download"))
+    );
+    Ok(())
+}
+
+async fn test_download_fail_fast_modes(client: StorageClient) -> Result<()> {
+    let remote_dir = TEST_FIXTURE.new_dir_path();
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        client
+            .operator()
+            .write(&format!("{remote_dir}{name}"), name.as_bytes().to_vec())
+            .await?;
+    }
+
+    // A directory where b.txt should land makes that one file fail mid-batch.
+    let blocked_dir = || async {
+        let local_dir = std::env::temp_dir().join(format!("storify-dl-ff-{}", Uuid::new_v4()));
+        fs::create_dir_all(local_dir.join("b.txt")).await?;
+        Ok::<_, std::io::Error>(local_dir)
+    };
+
+    let fail_fast = blocked_dir().await?;
+    storify_cmd()
+        .arg("get")
+        .arg(&remote_dir)
+        .arg(&fail_fast)
+        .assert()
+        .failure();
+    assert_eq!(fs::read(fail_fast.join("a.txt")).await?, b"a.txt");
+    assert!(!fail_fast.join("c.txt").exists());
+
+    let keep_going = blocked_dir().await?;
+    storify_cmd()
+        .arg("--no-fail-fast")
+        .arg("get")
+        .arg(&remote_dir)
+        .arg(&keep_going)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed:"))
+        .stderr(predicate::str::contains("1 of 3 object(s) failed"));
+    assert_eq!(fs::read(keep_going.join("a.txt")).await?, b"a.txt");
+    assert_eq!(fs::read(keep_going.join("c.txt")).await?, b"c.txt");
+
+    // Retrying cannot fix the blocked file: it is tried again, then skipped.
+    let retried = blocked_dir().await?;
+    storify_cmd()
+        .args(["--on-error", "retry:1", "get"])
+        .arg(&remote_dir)
+        .arg(&retried)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Retrying (1/1)"))
+        .stderr(predicate::str::contains("1 of 3 object(s) failed"));
+    assert_eq!(fs::read(retried.join("c.txt")).await?, b"c.txt");
+
+    // An explicit --fail-fast overrides the environment default.
+    let overridden = blocked_dir().await?;
+    storify_cmd()
+        .env("STORAGE_NO_FAIL_FAST", "true")
+        .args(["--fail-fast", "get"])
+        .arg(&remote_dir)
+        .arg(&overridden)
+        .assert()
+        .failure();
+    assert!(!overridden.join("c.txt").exists());
+
+    for dir in [fail_fast, keep_going, retried, overridden] {
+        let _ = fs::remove_dir_all(&dir).await;
+    }
+    Ok(())
+}
+
+async fn test_download_concurrency_modes(client: StorageClient) -> Result<()> {
+    let remote_dir = TEST_FIXTURE.new_dir_path();
+    let names: Vec<String> = (0..12)
+        .map(|i| format!("nested/{}/file-{i}.txt", i % 3))
+        .collect();
+    for name in &names {
+        client
+            .operator()
+            .write(&format!("{remote_dir}{name}"), name.as_bytes().to_vec())
+            .await?;
+    }
+
+    for concurrency in ["auto", "4"] {
+        let local_dir = std::env::temp_dir().join(format!("storify-dl-cc-{}", Uuid::new_v4()));
+        storify_cmd()
+            .args(["get", "--concurrency", concurrency])
+            .arg(&remote_dir)
+            .arg(&local_dir)
+            .assert()
+            .success();
+        for name in &names {
+            assert_eq!(fs::read(local_dir.join(name)).await?, name.as_bytes());
+        }
+        let _ = fs::remove_dir_all(&local_dir).await;
+    }
+
+    storify_cmd()
+        .args(["get", "--concurrency", "0"])
+        .arg(&remote_dir)
+        .arg(std::env::temp_dir())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("positive number or 'auto'"));
+
+    Ok(())
+}
+
+async fn test_download_missing_key_strict_and_lenient(_client: StorageClient) -> Result<()> {
+    let remote_path = TEST_FIXTURE.new_file_path();
+    let local_dir = std::env::temp_dir().join(format!("storify-dl-lenient-{}", Uuid::new_v4()));
+
+    for args in [&[][..], &["--strict-existence"][..]] {
+        storify_cmd()
+            .arg("get")
+            .args(args)
+            .arg(&remote_path)
+            .arg(&local_dir)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Path does not exist"));
+        assert!(!local_dir.exists());
+    }
+
+    storify_cmd()
+        .args(["get", "--lenient"])
+        .arg(&remote_path)
+        .arg(&local_dir)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "does not exist; nothing to download",
+        ));
+    assert!(!local_dir.exists());
+
+    storify_cmd()
+        .args(["get", "--lenient", "--strict-existence"])
+        .arg(&remote_path)
+        .arg(&local_dir)
+        .assert()
+        .failure();
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_find_empty_objects,
+        test_find_modified_within
+    ));
+}
+
+pub async fn test_find_empty_objects(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let empty = format!("{dir}nested/empty.bin");
+    let full = format!("{dir}full.bin");
+    client.operator().write(&empty, Vec::<u8>::new()).await?;
+    client.operator().write(&full, b"content".to_vec()).await?;
+    client
+        .operator()
+        .create_dir(&format!("{dir}marker/"))
+        .await?;
+
+    let output = storify_cmd()
+        .arg("find")
+        .arg(&dir)
+        .arg("--empty")
+        .output()
+        .expect("failed to run find");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let found: Vec<&str> = stdout.lines().collect();
+    assert_eq!(found, vec![empty.as_str()]);
+    Ok(())
+}
+
+pub async fn test_find_modified_within(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let fresh = format!("{dir}fresh.log");
+    client.operator().write(&fresh, b"new".to_vec()).await?;
+
+    let output = storify_cmd()
+        .args(["find", "--modified-within", "1h"])
+        .arg(&dir)
+        .output()
+        .expect("failed to run find");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), vec![fresh.as_str()]);
+
+    storify_cmd()
+        .args(["ls", "--modified-within", "7d"])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("fresh.log"));
+
+    storify_cmd()
+        .args(["du", "--modified-within", "24h", "--since", "2024-01-01"])
+        .arg(&dir)
+        .assert()
+        .failure();
+    Ok(())
+}
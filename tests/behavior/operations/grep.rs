@@ -0,0 +1,86 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_grep_prints_matching_lines,
+        test_grep_files_with_matches_and_ignore_case
+    ));
+}
+
+/// Seed a prefix where only `nested/app.log` mentions ERROR.
+async fn seed_logs(client: &StorageClient) -> Result<String> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    client
+        .operator()
+        .write(
+            &format!("{dir}nested/app.log"),
+            b"boot ok\nERROR disk full\nshutdown".to_vec(),
+        )
+        .await?;
+    client
+        .operator()
+        .write(
+            &format!("{dir}other.log"),
+            b"all good\nstill fine\n".to_vec(),
+        )
+        .await?;
+    client
+        .operator()
+        .write(&format!("{dir}blob.bin"), b"\0\x01ERROR\n".to_vec())
+        .await?;
+    Ok(dir)
+}
+
+async fn test_grep_prints_matching_lines(client: StorageClient) -> Result<()> {
+    let dir = seed_logs(&client).await?;
+
+    storify_cmd()
+        .args(["grep", "ERROR", "-r"])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::eq(format!(
+            "{dir}nested/app.log:ERROR disk full\n"
+        )));
+
+    // Without -r only direct children are searched.
+    storify_cmd()
+        .args(["grep", "ERROR"])
+        .arg(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::is_empty());
+
+    Ok(())
+}
+
+async fn test_grep_files_with_matches_and_ignore_case(client: StorageClient) -> Result<()> {
+    let dir = seed_logs(&client).await?;
+
+    storify_cmd()
+        .args(["grep", "-r", "-l", "-i", "error"])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::eq(format!("{dir}nested/app.log\n")));
+
+    storify_cmd()
+        .args(["grep", "error"])
+        .arg(format!("{dir}nested/app.log"))
+        .assert()
+        .code(1);
+
+    // Errors exit with 2, as grep(1) does, rather than looking like no match.
+    storify_cmd()
+        .args(["grep", "-r", "("])
+        .arg(&dir)
+        .assert()
+        .code(2);
+
+    Ok(())
+}
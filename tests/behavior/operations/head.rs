@@ -0,0 +1,57 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_head_and_tail_limits,
+        test_head_rejects_zero_limit
+    ));
+}
+
+async fn test_head_and_tail_limits(client: StorageClient) -> Result<()> {
+    let path = TEST_FIXTURE.new_file_path();
+    client
+        .operator()
+        .write(&path, b"one\ntwo\nthree\nfour\n".to_vec())
+        .await?;
+
+    storify_cmd()
+        .args(["head", "-n", "2", &path])
+        .assert()
+        .success()
+        .stdout(predicate::eq("one\ntwo\n"));
+    storify_cmd()
+        .args(["head", "-c", "5", &path])
+        .assert()
+        .success()
+        .stdout(predicate::eq("one\nt"));
+    storify_cmd()
+        .args(["tail", "-n", "1", &path])
+        .assert()
+        .success()
+        .stdout(predicate::eq("four\n"));
+    storify_cmd()
+        .args(["tail", "-c", "3", &path])
+        .assert()
+        .success()
+        .stdout(predicate::eq("ur\n"));
+
+    Ok(())
+}
+
+async fn test_head_rejects_zero_limit(client: StorageClient) -> Result<()> {
+    let path = TEST_FIXTURE.new_file_path();
+    client.operator().write(&path, b"one\n".to_vec()).await?;
+
+    storify_cmd()
+        .args(["head", "-n", "0", &path])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("greater than zero"));
+
+    Ok(())
+}
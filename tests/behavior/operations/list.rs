@@ -16,7 +16,24 @@ pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
         test_list_nested_directories,
         test_list_with_special_chars,
         test_list_invalid_path,
-        test_list_recursive
+        test_list_recursive,
+        test_list_sort_version,
+        test_list_format_template,
+        test_list_null_separated,
+        test_list_key_with_newline,
+        test_list_debug_log_redacts_secrets,
+        test_list_since_until_inclusive,
+        test_list_parallel_stat_matches_stat,
+        test_list_with_request_limit,
+        test_list_recursive_summary,
+        test_list_tsv_format,
+        test_list_json_format,
+        test_list_keys_only_and_basename_only,
+        test_list_strip_trailing_slash,
+        test_list_page_size,
+        test_list_http_client_settings,
+        test_list_checksums,
+        test_list_grouped
     ));
 }
 
@@ -46,7 +63,7 @@ pub async fn test_list_empty_directory(client: StorageClient) -> Result<()> {
 }
 
 pub async fn test_list_single_file(client: StorageClient) -> Result<()> {
-    let (path, content, size) = TEST_FIXTURE.new_file(client.operator());
+    let (path, content, size) = TEST_FIXTURE.new_file(&client.operator());
 
     client.operator().write(&path, content).await?;
 
@@ -200,3 +217,566 @@ pub async fn test_list_recursive(client: StorageClient) -> Result<()> {
 
     Ok(())
 }
+
+pub async fn test_list_sort_version(client: StorageClient) -> Result<()> {
+    let parent = TEST_FIXTURE.new_dir_path();
+    let file2 = format!("{parent}file2");
+    let file10 = format!("{parent}file10");
+
+    for file_path in [&file10, &file2] {
+        let (_, content, _) = TEST_FIXTURE.new_file_with_range(file_path, 10..100);
+        client.operator().write(file_path, content).await?;
+    }
+
+    let output = storify_cmd()
+        .arg("ls")
+        .arg("--sort")
+        .arg("version")
+        .arg(&parent)
+        .output()
+        .expect("failed to run ls");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let pos2 = stdout.find(&file2).expect("file2 should be listed");
+    let pos10 = stdout.find(&file10).expect("file10 should be listed");
+    assert!(pos2 < pos10, "file2 should precede file10, got: {stdout}");
+
+    Ok(())
+}
+
+pub async fn test_list_format_template(client: StorageClient) -> Result<()> {
+    let parent = TEST_FIXTURE.new_dir_path();
+    let file_path = format!("{parent}templated.txt");
+    let (_, content, size) = TEST_FIXTURE.new_file_with_range(&file_path, 10..100);
+    client.operator().write(&file_path, content).await?;
+
+    storify_cmd()
+        .arg("ls")
+        .arg("--format-template")
+        .arg("{size} {path}")
+        .arg(&parent)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("{size} {file_path}\n")));
+
+    storify_cmd()
+        .arg("ls")
+        .arg("--format-template")
+        .arg("{owner}")
+        .arg(&parent)
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+pub async fn test_list_null_separated(client: StorageClient) -> Result<()> {
+    let parent = TEST_FIXTURE.new_dir_path();
+    let names = ["with space.txt", "another file.txt"];
+    for name in &names {
+        let file_path = format!("{parent}{name}");
+        let (_, content, _) = TEST_FIXTURE.new_file_with_range(&file_path, 10..100);
+        client.operator().write(&file_path, content).await?;
+    }
+
+    let output = storify_cmd()
+        .arg("ls")
+        .arg("-0")
+        .arg(&parent)
+        .output()
+        .expect("failed to run ls");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains('\n'), "NUL mode should not emit newlines");
+    let listed: Vec<&str> = stdout.split('\0').filter(|s| !s.is_empty()).collect();
+    for name in &names {
+        let expected = format!("{parent}{name}");
+        assert!(
+            listed.contains(&expected.as_str()),
+            "missing {expected} in {listed:?}"
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn test_list_key_with_newline(client: StorageClient) -> Result<()> {
+    let parent = TEST_FIXTURE.new_dir_path();
+    let tricky = format!("{parent}line\nbreak.txt");
+    let normal = format!("{parent}normal.txt");
+    for file_path in [&tricky, &normal] {
+        let (_, content, _) = TEST_FIXTURE.new_file_with_range(file_path, 10..100);
+        client.operator().write(file_path, content).await?;
+    }
+
+    let output = storify_cmd()
+        .arg("ls")
+        .arg(&parent)
+        .output()
+        .expect("failed to run ls");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines.contains(&format!("{parent}line\\nbreak.txt").as_str()));
+    assert!(lines.contains(&normal.as_str()));
+    assert!(
+        !lines.contains(&"break.txt"),
+        "escaped key must not spill onto its own line: {lines:?}"
+    );
+
+    let output = storify_cmd()
+        .arg("ls")
+        .arg("-0")
+        .arg(&parent)
+        .output()
+        .expect("failed to run ls");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.split('\0').any(|entry| entry == tricky));
+
+    Ok(())
+}
+
+pub async fn test_list_debug_log_redacts_secrets(client: StorageClient) -> Result<()> {
+    let parent = TEST_FIXTURE.new_dir_path();
+    let (file_path, content, _) =
+        TEST_FIXTURE.new_file_with_range(format!("{parent}traced.txt"), 10..100);
+    client.operator().write(&file_path, content).await?;
+    let log_path = std::env::temp_dir().join(format!("storify-debug-{}.log", uuid::Uuid::new_v4()));
+
+    storify_cmd()
+        .arg("ls")
+        .arg(&parent)
+        .arg("--debug-log")
+        .arg(&log_path)
+        .assert()
+        .success();
+
+    let log = std::fs::read_to_string(&log_path)?;
+    let _ = std::fs::remove_file(&log_path);
+    assert!(!log.is_empty(), "expected at least one traced operation");
+    for line in log.lines() {
+        let record: serde_json::Value = serde_json::from_str(line)?;
+        assert!(record["operation"].is_string());
+        assert!(record["status"].is_string());
+    }
+
+    let secrets = [
+        std::env::var("STORAGE_ACCESS_KEY_ID").unwrap_or_else(|_| "minioadmin".to_string()),
+        std::env::var("STORAGE_ACCESS_KEY_SECRET").unwrap_or_else(|_| "minioadmin".to_string()),
+    ];
+    for secret in secrets {
+        assert!(!log.contains(&secret), "debug log leaked a credential");
+    }
+    Ok(())
+}
+
+pub async fn test_list_since_until_inclusive(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let path = format!("{dir}dated.txt");
+    client.operator().write(&path, b"dated".to_vec()).await?;
+    // Take the time from a listing: it can be more precise than the one `stat` returns.
+    let modified = client
+        .operator()
+        .list(&dir)
+        .await?
+        .into_iter()
+        .find(|entry| entry.path() == path)
+        .and_then(|entry| entry.metadata().last_modified())
+        .expect("MinIO reports modification times");
+    let exact = modified.to_rfc3339();
+    let later = (modified + chrono::Duration::seconds(1)).to_rfc3339();
+
+    // Both ends equal to the modification time: inclusive, so the object is listed.
+    storify_cmd()
+        .args(["ls", "--since", &exact, "--until", &exact])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dated.txt"));
+
+    storify_cmd()
+        .args(["ls", "--since", &later])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dated.txt").not());
+
+    storify_cmd()
+        .args(["ls", "--since", "2000-01-01"])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dated.txt"));
+
+    storify_cmd()
+        .args(["ls", "--until", "not-a-date"])
+        .arg(&dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid date"));
+
+    Ok(())
+}
+
+pub async fn test_list_parallel_stat_matches_stat(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let mut expected = Vec::new();
+    for (name, content_type) in [
+        ("a.json", "application/json"),
+        ("b.txt", "text/plain"),
+        ("c.csv", "text/csv"),
+    ] {
+        let path = format!("{dir}{name}");
+        client
+            .operator()
+            .write_with(&path, name.as_bytes().to_vec())
+            .content_type(content_type)
+            .await?;
+        let meta = client.operator().stat(&path).await?;
+        expected.push(format!(
+            "{path} {} {} {}",
+            meta.content_length(),
+            meta.etag().unwrap_or("-"),
+            meta.content_type().unwrap_or("-")
+        ));
+    }
+
+    let output = storify_cmd()
+        .args(["ls", "-L", "--parallel-stat", "--sort", "name"])
+        .args(["--format-template", "{path} {size} {etag} {content_type}"])
+        .arg(&dir)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout
+        .lines()
+        .filter(|line| !line.starts_with(&format!("{dir} ")))
+        .collect();
+    assert_eq!(lines, expected);
+
+    Ok(())
+}
+
+pub async fn test_list_with_request_limit(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    for name in ["a.txt", "nested/b.txt", "nested/c.txt"] {
+        client
+            .operator()
+            .write(&format!("{dir}{name}"), name.as_bytes().to_vec())
+            .await?;
+    }
+
+    let list = |limit: Option<&str>| {
+        let mut cmd = storify_cmd();
+        if let Some(limit) = limit {
+            cmd.args(["--max-concurrent-requests", limit]);
+        }
+        cmd.args(["ls", "-R", "-L", "--parallel-stat", "--sort", "name"])
+            .arg(&dir)
+            .output()
+    };
+    let unlimited = list(None)?;
+    let limited = list(Some("1"))?;
+    assert!(limited.status.success());
+    assert_eq!(limited.stdout, unlimited.stdout);
+
+    storify_cmd()
+        .args(["--max-concurrent-requests", "0", "ls"])
+        .arg(&dir)
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+pub async fn test_list_recursive_summary(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    for (name, size) in [
+        ("a/1.bin", 10),
+        ("a/b/2.bin", 20),
+        ("c/3.bin", 30),
+        ("top.bin", 40),
+    ] {
+        client
+            .operator()
+            .write(&format!("{dir}{name}"), vec![0u8; size])
+            .await?;
+    }
+
+    let output = storify_cmd()
+        .args(["ls", "--recursive-summary"])
+        .arg(&dir)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            format!("{dir}a/b/: 1 files, 20B"),
+            format!("{dir}a/: 2 files, 30B"),
+            format!("{dir}c/: 1 files, 30B"),
+            format!("{dir}: 4 files, 100B"),
+        ]
+    );
+
+    Ok(())
+}
+
+pub async fn test_list_tsv_format(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    client
+        .operator()
+        .write(&format!("{dir}plain.txt"), vec![b'x'; 12])
+        .await?;
+    client
+        .operator()
+        .write(&format!("{dir}tab\there.txt"), vec![b'y'; 3])
+        .await?;
+
+    let output = storify_cmd()
+        .args(["ls", "--format", "tsv", "--sort", "name"])
+        .arg(&dir)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split('\t').collect())
+        .filter(|fields: &Vec<&str>| fields.get(3) != Some(&dir.as_str()))
+        .collect();
+    assert_eq!(rows.len(), 2, "unexpected rows: {rows:?}");
+    for row in &rows {
+        assert_eq!(row.len(), 4, "a key's tab must not add a column: {row:?}");
+        assert_eq!(row[0], "FILE");
+        assert!(chrono::DateTime::parse_from_rfc3339(row[2]).is_ok());
+    }
+    assert_eq!(rows[0][1], "12");
+    assert_eq!(rows[0][3], format!("{dir}plain.txt"));
+    assert_eq!(rows[1][1], "3");
+    assert_eq!(rows[1][3], format!("{dir}tab\\there.txt"));
+
+    Ok(())
+}
+
+pub async fn test_list_json_format(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    client
+        .operator()
+        .write(&format!("{dir}plain.txt"), vec![b'x'; 12])
+        .await?;
+    client
+        .operator()
+        .write(&format!("{dir}sub/inner.txt"), vec![b'y'; 3])
+        .await?;
+
+    let output = storify_cmd()
+        .args(["ls", "--format", "json", "--sort", "name"])
+        .arg(&dir)
+        .output()?;
+    assert!(output.status.success());
+    let entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+    let entries: Vec<_> = entries
+        .iter()
+        .filter(|entry| entry["path"] != dir.as_str())
+        .collect();
+    assert_eq!(entries.len(), 2, "unexpected entries: {entries:?}");
+    assert_eq!(entries[0]["path"], format!("{dir}plain.txt"));
+    assert_eq!(entries[0]["size"], 12);
+    assert_eq!(entries[0]["is_dir"], false);
+    assert!(entries[0]["modified"].is_string());
+    assert_eq!(entries[1]["path"], format!("{dir}sub/"));
+    assert_eq!(entries[1]["is_dir"], true);
+
+    Ok(())
+}
+
+pub async fn test_list_keys_only_and_basename_only(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    for key in ["top.txt", "sub/inner.txt", "sub/deep/leaf.txt"] {
+        client
+            .operator()
+            .write(&format!("{dir}{key}"), b"data".to_vec())
+            .await?;
+    }
+
+    let run = |mode: &str| -> Result<Vec<String>> {
+        let output = storify_cmd()
+            .args(["ls", "-R", "-L", "--sort", "name", mode])
+            .arg(&dir)
+            .output()?;
+        assert!(output.status.success());
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    };
+
+    // `-L` is ignored: no type, size or time columns.
+    let keys = run("--keys-only")?;
+    for key in ["top.txt", "sub/inner.txt", "sub/deep/leaf.txt"] {
+        assert!(
+            keys.contains(&format!("{dir}{key}")),
+            "missing {key}: {keys:?}"
+        );
+    }
+    assert!(keys.iter().all(|line| line.starts_with(&dir)), "{keys:?}");
+
+    let names = run("--basename-only")?;
+    for name in ["top.txt", "inner.txt", "leaf.txt"] {
+        assert!(
+            names.iter().any(|line| line == name),
+            "missing {name}: {names:?}"
+        );
+    }
+    assert!(names.iter().all(|line| !line.contains(&dir)), "{names:?}");
+
+    Ok(())
+}
+
+pub async fn test_list_strip_trailing_slash(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let sub_dir = format!("{dir}sub/");
+    client.operator().create_dir(&sub_dir).await?;
+    client
+        .operator()
+        .write(&format!("{sub_dir}file.txt"), b"data".to_vec())
+        .await?;
+
+    let output = storify_cmd()
+        .args(["ls", "--strip-trailing-slash"])
+        .arg(&dir)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let sub_key = sub_dir.trim_end_matches('/');
+    assert!(stdout.lines().any(|line| line == sub_key), "{stdout}");
+    assert!(stdout.lines().all(|line| !line.ends_with('/')), "{stdout}");
+
+    storify_cmd()
+        .args(["ls", "-L", "--strip-trailing-slash"])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(format!("DIR .* {sub_key}\n")).unwrap());
+    Ok(())
+}
+
+pub async fn test_list_page_size(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let mut expected = Vec::new();
+    for i in 0..5 {
+        let path = format!("{dir}file-{i}.txt");
+        client.operator().write(&path, b"data".to_vec()).await?;
+        expected.push(path);
+    }
+
+    // Pages smaller than the listing still add up to every entry.
+    let output = storify_cmd()
+        .args(["ls", "-R", "--page-size", "2"])
+        .arg(&dir)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for path in &expected {
+        assert!(stdout.lines().any(|line| line == path), "{stdout}");
+    }
+
+    storify_cmd()
+        .args(["ls", "--page-size", "0"])
+        .arg(&dir)
+        .assert()
+        .failure();
+    Ok(())
+}
+
+pub async fn test_list_http_client_settings(client: StorageClient) -> Result<()> {
+    let (path, content, _) = TEST_FIXTURE.new_file(&client.operator());
+    client.operator().write(&path, content).await?;
+
+    storify_cmd()
+        .args(["--follow-redirects", "ls"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(path.as_str()));
+
+    // Nothing listens on the discard port, so requests only fail if they use the proxy.
+    storify_cmd()
+        .args(["--proxy", "http://127.0.0.1:9", "ls"])
+        .arg(&path)
+        .assert()
+        .failure();
+
+    storify_cmd()
+        .args(["--proxy", "not a url", "ls"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid proxy"));
+
+    Ok(())
+}
+
+pub async fn test_list_checksums(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let path = format!("{dir}small.txt");
+    client.operator().write(&path, b"tiny".to_vec()).await?;
+    let etag = client
+        .operator()
+        .stat(&path)
+        .await?
+        .etag()
+        .expect("backend should report an etag")
+        .to_string();
+
+    storify_cmd()
+        .args(["ls", "-L", "--checksums"])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(" {etag} {path}")));
+
+    storify_cmd()
+        .args(["ls", "--checksums"])
+        .arg(&dir)
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+pub async fn test_list_grouped(client: StorageClient) -> Result<()> {
+    let root = TEST_FIXTURE.new_dir_path();
+    for key in ["a.txt", "sub/b.txt", "sub/deep/c.txt", "z.txt"] {
+        client
+            .operator()
+            .write(&format!("{root}{key}"), key.as_bytes().to_vec())
+            .await?;
+    }
+
+    let output = storify_cmd()
+        .args(["ls", "-R", "--grouped"])
+        .arg(&root)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!(
+            "{root}:\n  a.txt\n  sub/\n  z.txt\n\n\
+             {root}sub/:\n  b.txt\n  deep/\n\n\
+             {root}sub/deep/:\n  c.txt\n"
+        )
+    );
+
+    storify_cmd()
+        .args(["ls", "--grouped"])
+        .arg(&root)
+        .assert()
+        .failure();
+
+    Ok(())
+}
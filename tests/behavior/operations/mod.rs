@@ -1,8 +1,18 @@
+pub mod batch;
+pub mod cat;
+pub mod config;
 pub mod copy;
 pub mod delete;
+pub mod doctor;
 pub mod download;
+pub mod find;
+pub mod grep;
+pub mod head;
 pub mod list;
 pub mod mkdir;
+pub mod read_only;
+pub mod rename;
+pub mod select;
 pub mod stat;
 pub mod upload;
 pub mod usage;
@@ -0,0 +1,84 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_read_only_rejects_mutations,
+        test_read_only_allows_reads
+    ));
+}
+
+async fn test_read_only_rejects_mutations(client: StorageClient) -> Result<()> {
+    let (src_file, content, _) = TEST_FIXTURE.new_file(&client.operator());
+    client.operator().write(&src_file, content).await?;
+    let local = get_test_data_path("small.txt");
+    let dest = TEST_FIXTURE.new_file_path();
+    let dir = TEST_FIXTURE.new_dir_path();
+
+    let commands: Vec<Vec<String>> = vec![
+        vec![
+            "put".into(),
+            local.to_string_lossy().into_owned(),
+            dest.clone(),
+        ],
+        vec!["rm".into(), "-f".into(), src_file.clone()],
+        vec!["cp".into(), src_file.clone(), dest.clone()],
+        vec!["mkdir".into(), dir.clone()],
+    ];
+    for args in commands {
+        storify_cmd()
+            .arg("--read-only")
+            .args(&args)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("the client is read-only"));
+    }
+
+    // The environment variable works the same as the flag.
+    storify_cmd()
+        .env("STORAGE_READ_ONLY", "true")
+        .arg("mkdir")
+        .arg(&dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("the client is read-only"));
+
+    assert!(client.operator().exists(&src_file).await?);
+    assert!(!client.operator().exists(&dest).await?);
+    assert!(!client.operator().exists(&dir).await?);
+    Ok(())
+}
+
+async fn test_read_only_allows_reads(client: StorageClient) -> Result<()> {
+    let (src_file, content, _) = TEST_FIXTURE.new_file(&client.operator());
+    client.operator().write(&src_file, content.clone()).await?;
+    let local_dir = std::env::temp_dir().join(format!("storify-ro-{}", uuid::Uuid::new_v4()));
+
+    storify_cmd()
+        .arg("--read-only")
+        .arg("ls")
+        .arg(&src_file)
+        .assert()
+        .success();
+    storify_cmd()
+        .arg("--read-only")
+        .arg("stat")
+        .arg(&src_file)
+        .assert()
+        .success();
+    storify_cmd()
+        .arg("--read-only")
+        .arg("get")
+        .arg(&src_file)
+        .arg(&local_dir)
+        .assert()
+        .success();
+    assert_eq!(tokio::fs::read(local_dir.join(&src_file)).await?, content);
+
+    let _ = tokio::fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_move_file_overwrites_destination,
+        test_move_directory
+    ));
+}
+
+async fn test_move_file_overwrites_destination(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let (src, dst) = (format!("{dir}src.txt"), format!("{dir}dst.txt"));
+    client.operator().write(&src, b"new".to_vec()).await?;
+    client.operator().write(&dst, b"old".to_vec()).await?;
+
+    storify_cmd()
+        .args(["mv", &src, &dst])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Moved"));
+
+    assert!(!client.operator().exists(&src).await?);
+    assert_eq!(client.operator().read(&dst).await?.to_vec(), b"new");
+
+    Ok(())
+}
+
+async fn test_move_directory(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    let (src_dir, dst_dir) = (format!("{dir}src/"), format!("{dir}dst/"));
+    client
+        .operator()
+        .write(&format!("{src_dir}a.txt"), b"a".to_vec())
+        .await?;
+    client
+        .operator()
+        .write(&format!("{src_dir}nested/b.txt"), b"b".to_vec())
+        .await?;
+
+    storify_cmd()
+        .args([
+            "mv",
+            src_dir.trim_end_matches('/'),
+            dst_dir.trim_end_matches('/'),
+        ])
+        .assert()
+        .success();
+
+    assert!(
+        client
+            .operator()
+            .list(&src_dir)
+            .await
+            .map(|entries| entries.is_empty())
+            .unwrap_or(true)
+    );
+    assert_eq!(
+        client
+            .operator()
+            .read(&format!("{dst_dir}a.txt"))
+            .await?
+            .to_vec(),
+        b"a"
+    );
+    assert_eq!(
+        client
+            .operator()
+            .read(&format!("{dst_dir}nested/b.txt"))
+            .await?
+            .to_vec(),
+        b"b"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::*;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use storify::error::Result;
+use storify::storage::StorageClient;
+
+pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
+    tests.extend(async_trials!(
+        client,
+        test_select_filters_csv_rows,
+        test_select_rejects_unsupported_input
+    ));
+}
+
+async fn test_select_filters_csv_rows(client: StorageClient) -> Result<()> {
+    let path = format!("{}sales.csv", TEST_FIXTURE.new_dir_path());
+    client
+        .operator()
+        .write(
+            &path,
+            b"id,region,amount\n1,eu,50\n2,us,150\n3,eu,300\n".to_vec(),
+        )
+        .await?;
+
+    let output = storify_cmd()
+        .args(["select", "--header", "--query"])
+        .arg("SELECT col1, col3 WHERE col3 > 100 AND col2 = 'eu'")
+        .arg(&path)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3,300\n");
+    Ok(())
+}
+
+async fn test_select_rejects_unsupported_input(client: StorageClient) -> Result<()> {
+    let path = format!("{}rows.json", TEST_FIXTURE.new_dir_path());
+    client
+        .operator()
+        .write(&path, b"{\"id\": 1}\n".to_vec())
+        .await?;
+
+    storify_cmd()
+        .args(["select", "-q", "SELECT *"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("only filters CSV"));
+
+    storify_cmd()
+        .args(["select", "-q", "SELECT * WHERE col1 ~ 3"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid query"));
+    Ok(())
+}
@@ -9,13 +9,18 @@ pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
         client,
         test_stat_file_human,
         test_stat_file_json,
+        test_stat_json_pretty,
         test_stat_dir_raw,
-        test_stat_not_found
+        test_stat_not_found,
+        test_stat_format_size,
+        test_stat_format_etag,
+        test_stat_exists_only,
+        test_exists_command
     ));
 }
 
 pub async fn test_stat_file_human(client: StorageClient) -> Result<()> {
-    let (path, content, _size) = TEST_FIXTURE.new_file(client.operator());
+    let (path, content, _size) = TEST_FIXTURE.new_file(&client.operator());
     client.operator().write(&path, content).await?;
 
     storify_cmd()
@@ -29,7 +34,7 @@ pub async fn test_stat_file_human(client: StorageClient) -> Result<()> {
 }
 
 pub async fn test_stat_file_json(client: StorageClient) -> Result<()> {
-    let (path, content, _size) = TEST_FIXTURE.new_file(client.operator());
+    let (path, content, _size) = TEST_FIXTURE.new_file(&client.operator());
     client.operator().write(&path, content).await?;
 
     storify_cmd()
@@ -43,6 +48,34 @@ pub async fn test_stat_file_json(client: StorageClient) -> Result<()> {
     Ok(())
 }
 
+pub async fn test_stat_json_pretty(client: StorageClient) -> Result<()> {
+    let (path, content, _size) = TEST_FIXTURE.new_file(&client.operator());
+    client.operator().write(&path, content).await?;
+
+    let compact = storify_cmd().args(["stat", "--json"]).arg(&path).output()?;
+    let pretty = storify_cmd()
+        .args(["--json-pretty", "stat", "--json"])
+        .arg(&path)
+        .output()?;
+    assert!(compact.status.success() && pretty.status.success());
+    let compact = String::from_utf8_lossy(&compact.stdout);
+    let pretty = String::from_utf8_lossy(&pretty.stdout);
+
+    assert_eq!(compact.lines().count(), 1);
+    assert!(pretty.lines().count() > 1);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&compact)?,
+        serde_json::from_str::<serde_json::Value>(&pretty)?
+    );
+    for json in [&compact, &pretty] {
+        let path = json.find("\"path\"").unwrap();
+        let size = json.find("\"size\"").unwrap();
+        let etag = json.find("\"etag\"").unwrap();
+        assert!(path < size && size < etag, "unexpected order in {json}");
+    }
+    Ok(())
+}
+
 pub async fn test_stat_dir_raw(client: StorageClient) -> Result<()> {
     let dir = TEST_FIXTURE.new_dir_path();
     client.operator().create_dir(&dir).await?;
@@ -65,3 +98,102 @@ pub async fn test_stat_not_found(_client: StorageClient) -> Result<()> {
         .failure();
     Ok(())
 }
+
+pub async fn test_stat_format_size(client: StorageClient) -> Result<()> {
+    let (path, content, size) = TEST_FIXTURE.new_file(&client.operator());
+    client.operator().write(&path, content).await?;
+
+    storify_cmd()
+        .arg("stat")
+        .arg(&path)
+        .arg("--format")
+        .arg("{size}")
+        .assert()
+        .success()
+        .stdout(format!("{size}\n"));
+    Ok(())
+}
+
+pub async fn test_stat_format_etag(client: StorageClient) -> Result<()> {
+    let (path, content, _size) = TEST_FIXTURE.new_file(&client.operator());
+    client.operator().write(&path, content).await?;
+    let etag = client
+        .operator()
+        .stat(&path)
+        .await?
+        .etag()
+        .expect("backend should report an etag")
+        .to_string();
+
+    storify_cmd()
+        .arg("stat")
+        .arg(&path)
+        .arg("--format")
+        .arg("{etag}")
+        .assert()
+        .success()
+        .stdout(format!("{etag}\n"));
+    Ok(())
+}
+
+pub async fn test_stat_exists_only(client: StorageClient) -> Result<()> {
+    let (path, content, _size) = TEST_FIXTURE.new_file(&client.operator());
+    client.operator().write(&path, content).await?;
+
+    storify_cmd()
+        .arg("stat")
+        .arg("--exists-only")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout("");
+
+    storify_cmd()
+        .arg("stat")
+        .arg("--exists-only")
+        .arg(TEST_FIXTURE.new_file_path())
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("");
+
+    // A failed check is not mistaken for an absent path.
+    storify_cmd()
+        .env("STORAGE_PROVIDER", "unknown")
+        .args(["stat", "--exists-only", &path])
+        .assert()
+        .code(2);
+    Ok(())
+}
+
+pub async fn test_exists_command(client: StorageClient) -> Result<()> {
+    // Plain writes create no marker: the prefix exists only through its child.
+    let dir = TEST_FIXTURE.new_dir_path();
+    let path = format!("{dir}nested/file.txt");
+    client.operator().write(&path, b"data".to_vec()).await?;
+
+    for present in [path.clone(), dir.clone(), format!("{dir}nested")] {
+        storify_cmd()
+            .args(["exists", &present])
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+    }
+
+    storify_cmd()
+        .args(["exists", &format!("{dir}missing.txt")])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("");
+
+    // A check that could not run is an error, not an absent path.
+    storify_cmd()
+        .env("STORAGE_ENDPOINT", "http://127.0.0.1:1")
+        .args(["exists", &path])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Error"));
+    Ok(())
+}
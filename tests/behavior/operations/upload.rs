@@ -13,7 +13,24 @@ pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
         test_storage_client_write_from_special_dir
     ));
 
-    tests.extend(async_trials!(client, e2e_test_upload_command_succeeds));
+    tests.extend(async_trials!(
+        client,
+        e2e_test_upload_command_succeeds,
+        test_upload_progress_json,
+        test_upload_no_overwrite_newer_skips,
+        test_upload_with_checksum_algorithm,
+        test_upload_progress_to_file,
+        test_upload_only_newer,
+        test_upload_content_disposition_round_trip,
+        test_upload_cache_control_round_trip,
+        test_upload_normalize_unicode,
+        test_upload_concurrent_parts,
+        test_upload_detect_content_type_magic,
+        test_upload_small_file_output_is_clean,
+        test_upload_multiple_sources,
+        test_upload_empty_file,
+        test_upload_file_size_band
+    ));
 }
 
 async fn test_storage_client_write(_client: StorageClient) -> Result<()> {
@@ -87,3 +104,486 @@ async fn e2e_test_upload_command_succeeds(_client: StorageClient) -> Result<()>
 
     Ok(())
 }
+
+async fn test_upload_progress_json(_client: StorageClient) -> Result<()> {
+    let source_path = get_test_data_path("small.txt");
+    let dest_prefix = TEST_FIXTURE.new_file_path();
+    let file_size = fs::metadata(&source_path).await?.len();
+
+    let output = storify_cmd()
+        .arg("put")
+        .arg(&source_path)
+        .arg(&dest_prefix)
+        .arg("--progress-format")
+        .arg("json")
+        .output()
+        .expect("failed to run put");
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let events: Vec<serde_json::Value> = stderr
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).expect("progress line should be valid JSON"))
+        .collect();
+    assert!(!events.is_empty(), "expected at least one progress event");
+
+    for event in &events {
+        assert!(event["file"].is_string());
+        assert!(event["bytes_done"].is_u64());
+        assert!(event["bytes_total"].is_u64());
+        assert!(event["percent"].is_u64());
+    }
+    let last = events.last().unwrap();
+    assert_eq!(last["bytes_done"].as_u64(), Some(file_size));
+    assert_eq!(last["percent"].as_u64(), Some(100));
+    Ok(())
+}
+
+async fn test_upload_no_overwrite_newer_skips(client: StorageClient) -> Result<()> {
+    let local_dir = std::env::temp_dir().join(format!("storify-put-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&local_dir).await?;
+    let local_file = local_dir.join("stale.txt");
+    fs::write(&local_file, b"local content").await?;
+    let day_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(24 * 60 * 60);
+    std::fs::File::options()
+        .write(true)
+        .open(&local_file)?
+        .set_modified(day_ago)?;
+
+    let dest_prefix = TEST_FIXTURE.new_dir_path();
+    let remote_file = join_remote_path(&dest_prefix, "stale.txt");
+    TEST_FIXTURE.add_path(remote_file.clone());
+    client
+        .operator()
+        .write(&remote_file, b"remote content".to_vec())
+        .await?;
+
+    storify_cmd()
+        .arg("put")
+        .arg("--no-overwrite-newer")
+        .arg(&local_file)
+        .arg(&dest_prefix)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipped"));
+
+    let remote_content = client.operator().read(&remote_file).await?;
+    assert_eq!(remote_content.to_vec(), b"remote content");
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_upload_with_checksum_algorithm(client: StorageClient) -> Result<()> {
+    let source_path = get_test_data_path("small.txt");
+    let file_name = source_path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+    let expected_content = fs::read(&source_path).await?;
+
+    for algorithm in ["crc32c", "sha256"] {
+        let dest_prefix = TEST_FIXTURE.new_file_path();
+        storify_cmd()
+            .arg("put")
+            .arg("--checksum-algorithm")
+            .arg(algorithm)
+            .arg(&source_path)
+            .arg(&dest_prefix)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Upload"));
+
+        let uploaded = client
+            .operator()
+            .read(&join_remote_path(&dest_prefix, &file_name))
+            .await?;
+        assert_eq!(expected_content, uploaded.to_vec());
+    }
+    Ok(())
+}
+
+async fn test_upload_progress_to_file(_client: StorageClient) -> Result<()> {
+    let source_path = get_test_data_path("small.txt");
+    let dest_prefix = TEST_FIXTURE.new_file_path();
+    let file_size = fs::metadata(&source_path).await?.len();
+    let progress_file =
+        std::env::temp_dir().join(format!("storify-progress-{}", uuid::Uuid::new_v4()));
+
+    storify_cmd()
+        .arg("put")
+        .arg(&source_path)
+        .arg(&dest_prefix)
+        .arg("--progress-to-file")
+        .arg(&progress_file)
+        .assert()
+        .success();
+
+    let contents = fs::read_to_string(&progress_file).await?;
+    let last_line = contents
+        .lines()
+        .last()
+        .expect("progress file should not be empty");
+    assert!(
+        last_line.contains(&format!(" 100% {file_size}/{file_size} bytes ")),
+        "unexpected progress line: {last_line}"
+    );
+
+    let _ = fs::remove_file(&progress_file).await;
+    Ok(())
+}
+
+async fn test_upload_only_newer(client: StorageClient) -> Result<()> {
+    let local_dir = std::env::temp_dir().join(format!("storify-put-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&local_dir).await?;
+    let day = std::time::Duration::from_secs(24 * 60 * 60);
+    let now = std::time::SystemTime::now();
+
+    let dest_prefix = TEST_FIXTURE.new_dir_path();
+    // (file name, local mtime): one local file is newer than the remote object, one older.
+    for (name, modified) in [("fresh.txt", now + day), ("stale.txt", now - day)] {
+        let local_file = local_dir.join(name);
+        fs::write(&local_file, b"local content").await?;
+        std::fs::File::options()
+            .write(true)
+            .open(&local_file)?
+            .set_modified(modified)?;
+
+        let remote_file = join_remote_path(&dest_prefix, name);
+        TEST_FIXTURE.add_path(remote_file.clone());
+        client
+            .operator()
+            .write(&remote_file, b"remote content".to_vec())
+            .await?;
+    }
+
+    storify_cmd()
+        .arg("put")
+        .arg("-R")
+        .arg("--only-newer")
+        .arg(&local_dir)
+        .arg(&dest_prefix)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipped").and(predicate::str::contains("stale.txt")));
+
+    let fresh = client
+        .operator()
+        .read(&join_remote_path(&dest_prefix, "fresh.txt"))
+        .await?;
+    assert_eq!(fresh.to_vec(), b"local content");
+    let stale = client
+        .operator()
+        .read(&join_remote_path(&dest_prefix, "stale.txt"))
+        .await?;
+    assert_eq!(stale.to_vec(), b"remote content");
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_upload_content_disposition_round_trip(client: StorageClient) -> Result<()> {
+    let source_path = get_test_data_path("small.txt");
+    let dest_prefix = TEST_FIXTURE.new_file_path();
+    let remote_file = join_remote_path(&dest_prefix, "small.txt");
+    let disposition = "attachment; filename=\"report.pdf\"";
+
+    storify_cmd()
+        .arg("put")
+        .arg("--content-disposition")
+        .arg(disposition)
+        .arg(&source_path)
+        .arg(&dest_prefix)
+        .assert()
+        .success();
+
+    let meta = client.operator().stat(&remote_file).await?;
+    assert_eq!(meta.content_disposition(), Some(disposition));
+
+    storify_cmd()
+        .arg("stat")
+        .arg(&remote_file)
+        .arg("--format")
+        .arg("{content_disposition}")
+        .assert()
+        .success()
+        .stdout(format!("{disposition}\n"));
+
+    storify_cmd()
+        .arg("put")
+        .arg("--content-disposition")
+        .arg("download")
+        .arg(&source_path)
+        .arg(&dest_prefix)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("disposition type"));
+    Ok(())
+}
+
+async fn test_upload_cache_control_round_trip(client: StorageClient) -> Result<()> {
+    let source_path = get_test_data_path("small.txt");
+    let dest_prefix = TEST_FIXTURE.new_file_path();
+    let remote_file = join_remote_path(&dest_prefix, "small.txt");
+
+    storify_cmd()
+        .arg("put")
+        .arg("--cache-control")
+        .arg("max-age=3600")
+        .arg(&source_path)
+        .arg(&dest_prefix)
+        .assert()
+        .success();
+
+    storify_cmd()
+        .arg("stat")
+        .arg(&remote_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cache_control=max-age=3600"));
+
+    let meta = client.operator().stat(&remote_file).await?;
+    assert_eq!(meta.cache_control(), Some("max-age=3600"));
+    Ok(())
+}
+
+async fn test_upload_normalize_unicode(client: StorageClient) -> Result<()> {
+    let local_dir = std::env::temp_dir().join(format!("storify-put-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&local_dir).await?;
+    // "café.txt" with a combining acute accent (NFD), as written by macOS.
+    let local_file = local_dir.join("cafe\u{301}.txt");
+    fs::write(&local_file, b"bonjour").await?;
+
+    let dest_prefix = TEST_FIXTURE.new_dir_path();
+    storify_cmd()
+        .arg("put")
+        .arg("--normalize-unicode")
+        .arg("nfc")
+        .arg(&local_file)
+        .arg(&dest_prefix)
+        .assert()
+        .success();
+
+    let nfc_key = join_remote_path(&dest_prefix, "caf\u{e9}.txt");
+    let nfd_key = join_remote_path(&dest_prefix, "cafe\u{301}.txt");
+    assert_eq!(client.operator().read(&nfc_key).await?.to_vec(), b"bonjour");
+    assert!(!client.operator().exists(&nfd_key).await?);
+
+    let local_copy = local_dir.join("downloaded");
+    storify_cmd()
+        .arg("get")
+        .arg(&nfc_key)
+        .arg(&local_copy)
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read(local_copy.join("caf\u{e9}.txt")).await?,
+        b"bonjour"
+    );
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_upload_concurrent_parts(client: StorageClient) -> Result<()> {
+    let local_dir = std::env::temp_dir().join(format!("storify-put-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&local_dir).await?;
+    // Three full 5MiB parts plus a short final part.
+    let content: Vec<u8> = (0..16 * 1024 * 1024 + 123)
+        .map(|i: usize| (i % 251) as u8)
+        .collect();
+    let local_file = local_dir.join("large.bin");
+    fs::write(&local_file, &content).await?;
+
+    let dest_prefix = TEST_FIXTURE.new_dir_path();
+    storify_cmd()
+        .arg("put")
+        .arg("--part-concurrency")
+        .arg("4")
+        .arg("--part-size")
+        .arg("5MiB")
+        .arg(&local_file)
+        .arg(&dest_prefix)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Upload"));
+
+    let uploaded = client
+        .operator()
+        .read(&join_remote_path(&dest_prefix, "large.bin"))
+        .await?;
+    assert_eq!(uploaded.len(), content.len());
+    assert!(uploaded.to_vec() == content, "uploaded bytes differ");
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_upload_detect_content_type_magic(client: StorageClient) -> Result<()> {
+    let local_dir = std::env::temp_dir().join(format!("storify-put-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&local_dir).await?;
+    // A PNG saved without its extension.
+    let local_file = local_dir.join("logo");
+    fs::write(
+        &local_file,
+        b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01",
+    )
+    .await?;
+
+    for (method, expected) in [
+        ("magic", "image/png"),
+        ("extension", "application/octet-stream"),
+    ] {
+        let dest_prefix = TEST_FIXTURE.new_dir_path();
+        storify_cmd()
+            .arg("put")
+            .arg("--detect-content-type")
+            .arg(method)
+            .arg(&local_file)
+            .arg(&dest_prefix)
+            .assert()
+            .success();
+
+        let meta = client
+            .operator()
+            .stat(&join_remote_path(&dest_prefix, "logo"))
+            .await?;
+        assert_eq!(meta.content_type(), Some(expected), "method {method}");
+    }
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_upload_small_file_output_is_clean(_client: StorageClient) -> Result<()> {
+    let source_path = get_test_data_path("small.txt");
+    let dest_path = TEST_FIXTURE.new_dir_path();
+
+    let output = storify_cmd()
+        .arg("put")
+        .arg(&source_path)
+        .arg(&dest_path)
+        .output()?;
+    assert!(output.status.success());
+
+    // Redirected output gets no carriage-return progress and no stray blank line.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("✅ Upload: "), "stdout: {stdout:?}");
+    assert!(!stdout.contains('\r'), "stdout: {stdout:?}");
+    assert!(!stdout.contains("\n\n"), "stdout: {stdout:?}");
+    Ok(())
+}
+
+async fn test_upload_multiple_sources(client: StorageClient) -> Result<()> {
+    let local_dir = std::env::temp_dir().join(format!("storify-put-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(local_dir.join("c")).await?;
+    fs::write(local_dir.join("a.txt"), b"alpha").await?;
+    fs::write(local_dir.join("b.txt"), b"beta").await?;
+    fs::write(local_dir.join("c").join("inner.txt"), b"gamma").await?;
+
+    let dest_prefix = TEST_FIXTURE.new_dir_path();
+    storify_cmd()
+        .arg("put")
+        .arg("-R")
+        .arg(local_dir.join("a.txt"))
+        .arg(local_dir.join("b.txt"))
+        .arg(local_dir.join("c"))
+        .arg(&dest_prefix)
+        .assert()
+        .success();
+
+    for (name, content) in [
+        ("a.txt", &b"alpha"[..]),
+        ("b.txt", &b"beta"[..]),
+        ("inner.txt", &b"gamma"[..]),
+    ] {
+        let uploaded = client
+            .operator()
+            .read(&join_remote_path(&dest_prefix, name))
+            .await?;
+        assert_eq!(uploaded.to_vec(), content, "{name}");
+    }
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_upload_empty_file(client: StorageClient) -> Result<()> {
+    let local_dir = std::env::temp_dir().join(format!("storify-put-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&local_dir).await?;
+    let local_file = local_dir.join("empty.txt");
+    fs::write(&local_file, "").await?;
+
+    let dest_prefix = TEST_FIXTURE.new_dir_path();
+    for extra in [&[][..], &["--part-size", "5MiB"][..]] {
+        storify_cmd()
+            .arg("put")
+            .args(extra)
+            .arg(&local_file)
+            .arg(&dest_prefix)
+            .assert()
+            .success();
+
+        let meta = client
+            .operator()
+            .stat(&join_remote_path(&dest_prefix, "empty.txt"))
+            .await?;
+        assert!(meta.is_file());
+        assert_eq!(meta.content_length(), 0);
+        client
+            .operator()
+            .delete(&join_remote_path(&dest_prefix, "empty.txt"))
+            .await?;
+    }
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
+
+async fn test_upload_file_size_band(client: StorageClient) -> Result<()> {
+    let local_dir = std::env::temp_dir().join(format!("storify-put-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(local_dir.join("nested")).await?;
+    fs::write(local_dir.join("small.bin"), vec![1u8; 100]).await?;
+    fs::write(local_dir.join("fits.bin"), vec![2u8; 2048]).await?;
+    fs::write(local_dir.join("nested/fits.bin"), vec![3u8; 1024]).await?;
+    fs::write(local_dir.join("large.bin"), vec![4u8; 5000]).await?;
+
+    let dest_prefix = TEST_FIXTURE.new_dir_path();
+    storify_cmd()
+        .args([
+            "put",
+            "-R",
+            "--min-file-size",
+            "1K",
+            "--max-file-size",
+            "4K",
+        ])
+        .arg(&local_dir)
+        .arg(&dest_prefix)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("skipped 2 file(s) outside"));
+
+    let op = client.operator();
+    assert!(
+        op.exists(&join_remote_path(&dest_prefix, "fits.bin"))
+            .await?
+    );
+    assert!(
+        op.exists(&join_remote_path(&dest_prefix, "nested/fits.bin"))
+            .await?
+    );
+    assert!(
+        !op.exists(&join_remote_path(&dest_prefix, "small.bin"))
+            .await?
+    );
+    assert!(
+        !op.exists(&join_remote_path(&dest_prefix, "large.bin"))
+            .await?
+    );
+
+    let _ = fs::remove_dir_all(&local_dir).await;
+    Ok(())
+}
@@ -5,7 +5,16 @@ use storify::error::Result;
 use storify::storage::StorageClient;
 
 pub fn tests(client: &StorageClient, tests: &mut Vec<Trial>) {
-    tests.extend(async_trials!(client, test_du_summary_total_size));
+    tests.extend(async_trials!(
+        client,
+        test_du_summary_total_size,
+        test_du_max_depth_rollup,
+        test_du_by_prefix_without_markers,
+        test_du_since_counts_only_window,
+        test_du_compact_single_line,
+        test_du_by_extension,
+        test_du_json_format
+    ));
 }
 
 pub async fn test_du_summary_total_size(client: StorageClient) -> Result<()> {
@@ -56,3 +65,191 @@ pub async fn test_du_summary_total_size(client: StorageClient) -> Result<()> {
 
     Ok(())
 }
+
+pub async fn test_du_max_depth_rollup(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    for (path, size) in [("top", 5), ("a/x", 100), ("a/b/y", 200)] {
+        client
+            .operator()
+            .write(&format!("{dir}{path}"), vec![b'a'; size])
+            .await?;
+    }
+
+    let depth_one = storify_cmd()
+        .args(["du", "-s", "--max-depth", "1"])
+        .arg(&dir)
+        .output()
+        .expect("failed to run du");
+    assert!(depth_one.status.success());
+    let depth_one = String::from_utf8_lossy(&depth_one.stdout);
+    assert!(depth_one.contains(&format!("300B {dir}a/\n")));
+    assert!(!depth_one.contains(&format!("{dir}a/b/")));
+    assert!(depth_one.contains(&format!("305B {dir}\n")));
+
+    let depth_two = storify_cmd()
+        .args(["du", "-s", "--max-depth", "2"])
+        .arg(&dir)
+        .output()
+        .expect("failed to run du");
+    assert!(depth_two.status.success());
+    let depth_two = String::from_utf8_lossy(&depth_two.stdout);
+    assert!(depth_two.contains(&format!("300B {dir}a/\n")));
+    assert!(depth_two.contains(&format!("200B {dir}a/b/\n")));
+    assert!(depth_two.contains(&format!("305B {dir}\n")));
+
+    Ok(())
+}
+
+pub async fn test_du_by_prefix_without_markers(client: StorageClient) -> Result<()> {
+    // Plain writes create no directory markers; prefixes exist only inside the keys.
+    let dir = TEST_FIXTURE.new_dir_path();
+    for (path, size) in [("top", 5), ("a/x", 100), ("a/y", 50), ("b/z", 10)] {
+        client
+            .operator()
+            .write(&format!("{dir}{path}"), vec![b'a'; size])
+            .await?;
+    }
+
+    let output = storify_cmd()
+        .args(["du", "--by-prefix"])
+        .arg(&dir)
+        .output()
+        .expect("failed to run du");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("150B {dir}a/\n")), "{stdout}");
+    assert!(stdout.contains(&format!("10B {dir}b/\n")), "{stdout}");
+    assert!(!stdout.contains(&format!("{dir}a/x")), "{stdout}");
+    assert!(stdout.contains(&format!("165B {dir}\n")), "{stdout}");
+    assert!(stdout.contains("Total files: 4"), "{stdout}");
+
+    Ok(())
+}
+
+pub async fn test_du_since_counts_only_window(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    client
+        .operator()
+        .write(&format!("{dir}a"), vec![b'a'; 40])
+        .await?;
+    // Take the time from a listing: it can be more precise than the one `stat` returns.
+    let modified = client
+        .operator()
+        .list(&dir)
+        .await?
+        .into_iter()
+        .find_map(|entry| entry.metadata().last_modified())
+        .expect("MinIO reports modification times");
+
+    let in_window = storify_cmd()
+        .args(["du", "-s", "--since", &modified.to_rfc3339()])
+        .arg(&dir)
+        .output()
+        .expect("failed to run du");
+    assert!(in_window.status.success());
+    let stdout = String::from_utf8_lossy(&in_window.stdout);
+    assert!(stdout.contains(&format!("40B {dir}\n")), "{stdout}");
+    assert!(stdout.contains("Total files: 1"), "{stdout}");
+
+    let before = (modified - chrono::Duration::seconds(1)).to_rfc3339();
+    let out_of_window = storify_cmd()
+        .args(["du", "-s", "--until", &before])
+        .arg(&dir)
+        .output()
+        .expect("failed to run du");
+    assert!(out_of_window.status.success());
+    let stdout = String::from_utf8_lossy(&out_of_window.stdout);
+    assert!(stdout.contains(&format!("0B {dir}\n")), "{stdout}");
+    assert!(stdout.contains("Total files: 0"), "{stdout}");
+
+    Ok(())
+}
+
+pub async fn test_du_compact_single_line(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    client
+        .operator()
+        .write(&format!("{dir}a.txt"), vec![b'a'; 1000])
+        .await?;
+    client
+        .operator()
+        .write(&format!("{dir}sub/b.txt"), vec![b'b'; 24])
+        .await?;
+
+    storify_cmd()
+        .args(["du", "--compact"])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(format!("1024 2 {dir}\n"));
+
+    storify_cmd()
+        .args(["du", "--compact", "-s"])
+        .arg(&dir)
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+pub async fn test_du_by_extension(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    for (name, size) in [("a.log", 300), ("sub/b.log", 300), ("c.csv", 10)] {
+        client
+            .operator()
+            .write(&format!("{dir}{name}"), vec![b'x'; size])
+            .await?;
+    }
+
+    storify_cmd()
+        .args(["du", "--by-extension"])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(format!(
+            ".log: 600B / 2\n.csv: 10B / 1\n610B {dir}\nTotal files: 3\n"
+        ));
+
+    Ok(())
+}
+
+pub async fn test_du_json_format(client: StorageClient) -> Result<()> {
+    let dir = TEST_FIXTURE.new_dir_path();
+    client
+        .operator()
+        .write(&format!("{dir}a.txt"), vec![b'a'; 1000])
+        .await?;
+    client
+        .operator()
+        .write(&format!("{dir}sub/b.txt"), vec![b'b'; 24])
+        .await?;
+
+    let output = storify_cmd()
+        .args(["du", "-s", "--format", "json"])
+        .arg(&dir)
+        .output()?;
+    assert!(output.status.success());
+    let records: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        records,
+        serde_json::json!([{ "path": dir, "size": 1024, "files": 2 }])
+    );
+
+    let output = storify_cmd()
+        .args(["du", "--by-prefix", "--format", "json"])
+        .arg(&dir)
+        .output()?;
+    assert!(output.status.success());
+    let records: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        records.last(),
+        Some(&serde_json::json!({ "path": dir, "size": 1024, "files": 2 }))
+    );
+    assert!(records.contains(&serde_json::json!({
+        "path": format!("{dir}sub/"),
+        "size": 24,
+        "files": 1
+    })));
+
+    Ok(())
+}
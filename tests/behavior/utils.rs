@@ -33,7 +33,7 @@ pub async fn init_test_service() -> Result<StorageClient> {
     let config = build_minio_config_from_env()?;
     let client = StorageClient::new(config).await?;
 
-    ensure_bucket_exists(client.operator()).await?;
+    ensure_bucket_exists(&client.operator()).await?;
 
     Ok(client)
 }
@@ -198,7 +198,7 @@ impl E2eTestEnv {
         let verifier = StorageClient::new(cfg.clone())
             .await
             .expect("failed to create verifier client");
-        ensure_bucket_exists(verifier.operator())
+        ensure_bucket_exists(&verifier.operator())
             .await
             .expect("Failed to create E2E test bucket");
 
@@ -240,6 +240,11 @@ macro_rules! async_trials {
 
 pub static TEST_FIXTURE: Fixture = Fixture::new();
 
+/// Bucket the behavior tests run against, for URL-style locations such as `minio://<bucket>/key`.
+pub fn test_bucket() -> String {
+    TEST_MINIO_CONFIG.bucket.clone()
+}
+
 pub fn storify_cmd() -> Command {
     let cfg = TEST_MINIO_CONFIG.clone();
     let mut cmd = base_cmd();